@@ -51,6 +51,8 @@ struct ParsedBlogPost {
 
 fn main() {
     println!("cargo:rerun-if-changed=content/blog");
+    // sqlx::migrate! embeds ./migrations at compile time
+    println!("cargo:rerun-if-changed=migrations");
 
     let blog_dir = Path::new("content/blog");
 
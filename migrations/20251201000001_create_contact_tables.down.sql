@@ -0,0 +1,3 @@
+DROP TABLE IF EXISTS contact_rate_limits;
+DROP TABLE IF EXISTS contact_submissions;
+DROP FUNCTION IF EXISTS set_updated_at();
@@ -0,0 +1,59 @@
+-- Contact form storage and per-IP rate limiting
+--
+-- Tables are created with IF NOT EXISTS so that databases bootstrapped
+-- before migrations were versioned pick up this history without errors.
+
+CREATE OR REPLACE FUNCTION set_updated_at() RETURNS TRIGGER AS $$
+BEGIN
+    NEW.updated_at = NOW();
+    RETURN NEW;
+END;
+$$ LANGUAGE plpgsql;
+
+CREATE TABLE IF NOT EXISTS contact_submissions (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    name VARCHAR(255) NOT NULL,
+    email VARCHAR(255) NOT NULL,
+    subject VARCHAR(500),
+    message TEXT NOT NULL,
+
+    -- Spam protection
+    honeypot_filled BOOLEAN DEFAULT FALSE,
+    ip_hash VARCHAR(64),
+    user_agent TEXT,
+
+    -- Status (mirrors db::models::ContactStatus)
+    status VARCHAR(20) NOT NULL DEFAULT 'pending'
+        CONSTRAINT contact_submissions_status_check
+        CHECK (status IN ('pending', 'read', 'replied', 'spam')),
+    read_at TIMESTAMPTZ,
+    replied_at TIMESTAMPTZ,
+
+    -- Timestamps
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_contact_submissions_status
+    ON contact_submissions (status, created_at DESC);
+CREATE INDEX IF NOT EXISTS idx_contact_submissions_created_at
+    ON contact_submissions (created_at DESC);
+CREATE INDEX IF NOT EXISTS idx_contact_submissions_ip_hash
+    ON contact_submissions (ip_hash);
+
+DROP TRIGGER IF EXISTS contact_submissions_set_updated_at ON contact_submissions;
+CREATE TRIGGER contact_submissions_set_updated_at
+    BEFORE UPDATE ON contact_submissions
+    FOR EACH ROW EXECUTE FUNCTION set_updated_at();
+
+CREATE TABLE IF NOT EXISTS contact_rate_limits (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    ip_hash VARCHAR(64) NOT NULL UNIQUE,
+    attempt_count INTEGER NOT NULL DEFAULT 1 CHECK (attempt_count >= 0),
+    window_start TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    last_attempt TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+-- cleanup_rate_limits() deletes by window_start
+CREATE INDEX IF NOT EXISTS idx_contact_rate_limits_window_start
+    ON contact_rate_limits (window_start);
@@ -0,0 +1,2 @@
+DROP TABLE IF EXISTS github_cache_metadata;
+DROP TABLE IF EXISTS github_repos_cache;
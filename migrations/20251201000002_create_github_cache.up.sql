@@ -0,0 +1,39 @@
+-- GitHub repository cache (stale-while-revalidate, see db::github)
+
+CREATE TABLE IF NOT EXISTS github_repos_cache (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    github_id BIGINT NOT NULL UNIQUE,
+    name VARCHAR(255) NOT NULL,
+    full_name VARCHAR(255) NOT NULL,
+    description TEXT,
+    html_url TEXT NOT NULL,
+    language VARCHAR(100),
+    stargazers_count INTEGER NOT NULL DEFAULT 0,
+    forks_count INTEGER NOT NULL DEFAULT 0,
+    open_issues_count INTEGER NOT NULL DEFAULT 0,
+    topics JSONB NOT NULL DEFAULT '[]'::jsonb,
+    github_created_at TIMESTAMPTZ,
+    github_updated_at TIMESTAMPTZ,
+    github_pushed_at TIMESTAMPTZ,
+    cached_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    expires_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    is_active BOOLEAN NOT NULL DEFAULT TRUE
+);
+
+-- is_cache_fresh() / has_cached_data() filter on active rows by cached_at
+CREATE INDEX IF NOT EXISTS idx_github_repos_cache_active_cached_at
+    ON github_repos_cache (cached_at)
+    WHERE is_active = TRUE;
+
+-- Singleton row holding fetch status; always id = 1
+CREATE TABLE IF NOT EXISTS github_cache_metadata (
+    id INTEGER PRIMARY KEY DEFAULT 1 CHECK (id = 1),
+    last_successful_fetch TIMESTAMPTZ,
+    last_fetch_attempt TIMESTAMPTZ,
+    fetch_error_count INTEGER NOT NULL DEFAULT 0,
+    last_error_message TEXT,
+    rate_limit_remaining INTEGER,
+    rate_limit_reset TIMESTAMPTZ
+);
+
+INSERT INTO github_cache_metadata (id) VALUES (1) ON CONFLICT (id) DO NOTHING;
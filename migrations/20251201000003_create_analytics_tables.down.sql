@@ -0,0 +1,2 @@
+DROP TABLE IF EXISTS analytics_events;
+DROP TABLE IF EXISTS page_views;
@@ -0,0 +1,49 @@
+-- Privacy-first analytics (no raw IPs, no cookies)
+
+CREATE TABLE IF NOT EXISTS page_views (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+
+    -- Page info
+    path VARCHAR(2048) NOT NULL,
+    title VARCHAR(500),
+
+    -- Anonymized visitor info
+    session_hash VARCHAR(64),
+    referrer TEXT,
+    referrer_domain VARCHAR(255),
+
+    -- Device info (non-identifying)
+    device_type VARCHAR(20),
+    browser_family VARCHAR(50),
+    os_family VARCHAR(50),
+    country_code CHAR(2),
+
+    -- Engagement
+    time_on_page_seconds INTEGER CHECK (time_on_page_seconds >= 0),
+    scroll_depth_percent INTEGER CHECK (scroll_depth_percent BETWEEN 0 AND 100),
+
+    viewed_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_page_views_viewed_at ON page_views (viewed_at);
+CREATE INDEX IF NOT EXISTS idx_page_views_path_viewed_at ON page_views (path, viewed_at);
+CREATE INDEX IF NOT EXISTS idx_page_views_session_hash ON page_views (session_hash);
+
+CREATE TABLE IF NOT EXISTS analytics_events (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+
+    -- Event info
+    event_name VARCHAR(100) NOT NULL,
+    event_category VARCHAR(100),
+    event_data JSONB,
+
+    -- Context
+    page_path VARCHAR(2048),
+    session_hash VARCHAR(64),
+
+    occurred_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_analytics_events_occurred_at ON analytics_events (occurred_at);
+CREATE INDEX IF NOT EXISTS idx_analytics_events_name_occurred_at
+    ON analytics_events (event_name, occurred_at);
@@ -0,0 +1,5 @@
+DROP TABLE IF EXISTS analytics_daily_events;
+DROP TABLE IF EXISTS analytics_daily_devices;
+DROP TABLE IF EXISTS analytics_daily_countries;
+DROP TABLE IF EXISTS analytics_daily_referrers;
+DROP TABLE IF EXISTS analytics_daily_pages;
@@ -0,0 +1,49 @@
+-- Daily analytics aggregates
+--
+-- Raw page_views / analytics_events are rolled up here by the maintenance
+-- job and purged after the retention window; dashboards read these tables.
+-- Missing dimensions are stored as '' so they can be part of the primary key.
+
+CREATE TABLE IF NOT EXISTS analytics_daily_pages (
+    day DATE NOT NULL,
+    path VARCHAR(2048) NOT NULL,
+    views BIGINT NOT NULL DEFAULT 0,
+    sessions BIGINT NOT NULL DEFAULT 0,
+    -- Engagement sums (averages = total / samples)
+    time_samples BIGINT NOT NULL DEFAULT 0,
+    total_time_seconds BIGINT NOT NULL DEFAULT 0,
+    scroll_samples BIGINT NOT NULL DEFAULT 0,
+    total_scroll_percent BIGINT NOT NULL DEFAULT 0,
+    PRIMARY KEY (day, path)
+);
+
+CREATE TABLE IF NOT EXISTS analytics_daily_referrers (
+    day DATE NOT NULL,
+    referrer_domain VARCHAR(255) NOT NULL,
+    views BIGINT NOT NULL DEFAULT 0,
+    PRIMARY KEY (day, referrer_domain)
+);
+
+CREATE TABLE IF NOT EXISTS analytics_daily_countries (
+    day DATE NOT NULL,
+    country_code VARCHAR(2) NOT NULL,
+    views BIGINT NOT NULL DEFAULT 0,
+    PRIMARY KEY (day, country_code)
+);
+
+CREATE TABLE IF NOT EXISTS analytics_daily_devices (
+    day DATE NOT NULL,
+    device_type VARCHAR(20) NOT NULL,
+    browser_family VARCHAR(50) NOT NULL,
+    os_family VARCHAR(50) NOT NULL,
+    views BIGINT NOT NULL DEFAULT 0,
+    PRIMARY KEY (day, device_type, browser_family, os_family)
+);
+
+CREATE TABLE IF NOT EXISTS analytics_daily_events (
+    day DATE NOT NULL,
+    event_name VARCHAR(100) NOT NULL,
+    event_category VARCHAR(100) NOT NULL,
+    occurrences BIGINT NOT NULL DEFAULT 0,
+    PRIMARY KEY (day, event_name, event_category)
+);
@@ -0,0 +1,2 @@
+DROP TABLE IF EXISTS admin_login_rate_limits;
+DROP TABLE IF EXISTS admin_sessions;
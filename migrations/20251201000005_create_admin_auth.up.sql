@@ -0,0 +1,30 @@
+-- Admin authentication
+--
+-- Sessions are referenced by the SHA-256 of the cookie token, so a database
+-- leak does not hand out usable cookies.
+
+CREATE TABLE IF NOT EXISTS admin_sessions (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    token_hash VARCHAR(64) NOT NULL UNIQUE,
+    username VARCHAR(255) NOT NULL,
+    ip_hash VARCHAR(64),
+    user_agent TEXT,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    last_seen_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    expires_at TIMESTAMPTZ NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_admin_sessions_expires_at
+    ON admin_sessions (expires_at);
+
+-- Same fixed-window layout as contact_rate_limits
+CREATE TABLE IF NOT EXISTS admin_login_rate_limits (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    ip_hash VARCHAR(64) NOT NULL UNIQUE,
+    attempt_count INTEGER NOT NULL DEFAULT 1 CHECK (attempt_count >= 0),
+    window_start TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    last_attempt TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_admin_login_rate_limits_window_start
+    ON admin_login_rate_limits (window_start);
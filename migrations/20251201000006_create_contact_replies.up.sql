@@ -0,0 +1,15 @@
+-- Replies sent to contact submissions from the admin inbox
+
+CREATE TABLE IF NOT EXISTS contact_replies (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    submission_id UUID NOT NULL REFERENCES contact_submissions (id) ON DELETE CASCADE,
+    subject VARCHAR(500) NOT NULL,
+    body TEXT NOT NULL,
+    -- RFC 5322 Message-ID of the sent email
+    message_id VARCHAR(255) NOT NULL,
+    sent_by VARCHAR(255) NOT NULL,
+    sent_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_contact_replies_submission_id
+    ON contact_replies (submission_id, sent_at);
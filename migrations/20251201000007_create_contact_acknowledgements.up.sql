@@ -0,0 +1,13 @@
+-- Per-recipient throttle for contact acknowledgement emails
+--
+-- Keyed by a salted hash of the lowercased address, so the form cannot be
+-- used to flood a third party's inbox with confirmations.
+
+CREATE TABLE IF NOT EXISTS contact_acknowledgements (
+    recipient_hash VARCHAR(64) PRIMARY KEY,
+    sent_count INTEGER NOT NULL DEFAULT 1 CHECK (sent_count >= 1),
+    last_sent_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_contact_acknowledgements_last_sent_at
+    ON contact_acknowledgements (last_sent_at);
@@ -0,0 +1,34 @@
+-- Durable outbox for contact emails
+--
+-- Rows are enqueued in the same transaction as the submission and delivered
+-- by a background worker. The email is rendered from the submission at send
+-- time, so no message content is duplicated here.
+
+CREATE TABLE IF NOT EXISTS email_outbox (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    kind VARCHAR(32) NOT NULL,
+    submission_id UUID NOT NULL REFERENCES contact_submissions (id) ON DELETE CASCADE,
+    -- Language the email is rendered in (en / fr)
+    language VARCHAR(2) NOT NULL DEFAULT 'en',
+
+    -- pending -> sent, or dead after the last failed attempt
+    status VARCHAR(10) NOT NULL DEFAULT 'pending',
+    attempts INTEGER NOT NULL DEFAULT 0,
+    next_attempt_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    -- Set while a worker holds the row; expired leases are claimed again
+    locked_until TIMESTAMPTZ,
+    last_error TEXT,
+
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    sent_at TIMESTAMPTZ
+);
+
+CREATE INDEX IF NOT EXISTS idx_email_outbox_due
+    ON email_outbox (next_attempt_at)
+    WHERE status = 'pending';
+
+CREATE INDEX IF NOT EXISTS idx_email_outbox_status
+    ON email_outbox (status, created_at DESC);
+
+CREATE INDEX IF NOT EXISTS idx_email_outbox_submission_id
+    ON email_outbox (submission_id);
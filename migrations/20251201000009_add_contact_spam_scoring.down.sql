@@ -0,0 +1,6 @@
+DROP INDEX IF EXISTS idx_contact_submissions_message_hash;
+
+ALTER TABLE contact_submissions
+    DROP COLUMN IF EXISTS message_hash,
+    DROP COLUMN IF EXISTS spam_rules,
+    DROP COLUMN IF EXISTS spam_score;
@@ -0,0 +1,14 @@
+-- Content-based spam scoring for contact submissions
+--
+-- spam_rules holds the points each rule added, e.g. {"links": 4}, so the
+-- weights can be tuned. message_hash is a SHA-256 of the normalized message
+-- text, used to spot the same message sent repeatedly.
+
+ALTER TABLE contact_submissions
+    ADD COLUMN IF NOT EXISTS spam_score INTEGER,
+    ADD COLUMN IF NOT EXISTS spam_rules JSONB,
+    ADD COLUMN IF NOT EXISTS message_hash VARCHAR(64);
+
+CREATE INDEX IF NOT EXISTS idx_contact_submissions_message_hash
+    ON contact_submissions (message_hash, created_at DESC)
+    WHERE message_hash IS NOT NULL;
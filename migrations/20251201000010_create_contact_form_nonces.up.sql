@@ -0,0 +1,12 @@
+-- Consumed contact form token nonces
+--
+-- A nonce is recorded when its submission is accepted, so a captured token
+-- cannot be replayed. Rows are deleted once the token would have expired.
+
+CREATE TABLE IF NOT EXISTS contact_form_nonces (
+    nonce VARCHAR(32) PRIMARY KEY,
+    consumed_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_contact_form_nonces_consumed_at
+    ON contact_form_nonces (consumed_at);
@@ -0,0 +1,23 @@
+CREATE TABLE IF NOT EXISTS contact_rate_limits (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    ip_hash VARCHAR(64) NOT NULL UNIQUE,
+    attempt_count INTEGER NOT NULL DEFAULT 1 CHECK (attempt_count >= 0),
+    window_start TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    last_attempt TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_contact_rate_limits_window_start
+    ON contact_rate_limits (window_start);
+
+CREATE TABLE IF NOT EXISTS admin_login_rate_limits (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    ip_hash VARCHAR(64) NOT NULL UNIQUE,
+    attempt_count INTEGER NOT NULL DEFAULT 1 CHECK (attempt_count >= 0),
+    window_start TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    last_attempt TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_admin_login_rate_limits_window_start
+    ON admin_login_rate_limits (window_start);
+
+DROP TABLE IF EXISTS rate_limits;
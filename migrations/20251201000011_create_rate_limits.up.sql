@@ -0,0 +1,20 @@
+-- Shared fixed-window counters for Postgres-backed rate limit policies
+--
+-- One row per (policy, key); the key is a salted hash, never a raw IP.
+-- Replaces the per-action contact_rate_limits and admin_login_rate_limits
+-- tables, whose counters were only ever short-lived.
+
+CREATE TABLE IF NOT EXISTS rate_limits (
+    policy VARCHAR(64) NOT NULL,
+    key VARCHAR(128) NOT NULL,
+    window_start TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    hits INTEGER NOT NULL DEFAULT 1 CHECK (hits >= 1),
+    PRIMARY KEY (policy, key)
+);
+
+-- cleanup_rate_limits() deletes by window_start
+CREATE INDEX IF NOT EXISTS idx_rate_limits_window_start
+    ON rate_limits (window_start);
+
+DROP TABLE IF EXISTS contact_rate_limits;
+DROP TABLE IF EXISTS admin_login_rate_limits;
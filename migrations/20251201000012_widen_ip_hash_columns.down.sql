@@ -0,0 +1,10 @@
+-- Versioned hashes do not fit the old width: drop the throttle rows (they
+-- are short-lived) and cut the rest, which no longer matches anything anyway
+
+DELETE FROM contact_acknowledgements WHERE LENGTH(recipient_hash) > 64;
+
+ALTER TABLE contact_submissions ALTER COLUMN ip_hash TYPE VARCHAR(64) USING LEFT(ip_hash, 64);
+ALTER TABLE contact_acknowledgements ALTER COLUMN recipient_hash TYPE VARCHAR(64);
+ALTER TABLE admin_sessions ALTER COLUMN ip_hash TYPE VARCHAR(64) USING LEFT(ip_hash, 64);
+ALTER TABLE page_views ALTER COLUMN session_hash TYPE VARCHAR(64) USING LEFT(session_hash, 64);
+ALTER TABLE analytics_events ALTER COLUMN session_hash TYPE VARCHAR(64) USING LEFT(session_hash, 64);
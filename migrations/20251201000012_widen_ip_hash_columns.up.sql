@@ -0,0 +1,11 @@
+-- Room for versioned keyed hashes: `v{version}:` followed by 64 hex digits
+--
+-- Hashes stored before this migration are plain salted SHA-256 and no
+-- longer match anything new; rate limit counters and acknowledgement
+-- throttles made with them simply expire.
+
+ALTER TABLE contact_submissions ALTER COLUMN ip_hash TYPE VARCHAR(80);
+ALTER TABLE contact_acknowledgements ALTER COLUMN recipient_hash TYPE VARCHAR(80);
+ALTER TABLE admin_sessions ALTER COLUMN ip_hash TYPE VARCHAR(80);
+ALTER TABLE page_views ALTER COLUMN session_hash TYPE VARCHAR(80);
+ALTER TABLE analytics_events ALTER COLUMN session_hash TYPE VARCHAR(80);
@@ -0,0 +1,13 @@
+-- Sealed rows cannot be turned back into plaintext here: SET NOT NULL
+-- fails while any remain, rather than losing them.
+
+DROP INDEX IF EXISTS idx_contact_submissions_key_id;
+
+ALTER TABLE contact_submissions
+    DROP CONSTRAINT IF EXISTS contact_submissions_sealed_check,
+    ALTER COLUMN name SET NOT NULL,
+    ALTER COLUMN email SET NOT NULL,
+    ALTER COLUMN message SET NOT NULL,
+    DROP COLUMN IF EXISTS sealed_pii,
+    DROP COLUMN IF EXISTS wrapped_dek,
+    DROP COLUMN IF EXISTS key_id;
@@ -0,0 +1,22 @@
+-- Envelope encryption of contact submissions (see src/encryption.rs)
+--
+-- New rows keep name, email, subject and message only in sealed_pii,
+-- encrypted under a per-row data key; wrapped_dek is that key encrypted
+-- under the master key key_id. The plaintext columns stay for rows stored
+-- before this migration until `portfolio reencrypt-contacts` seals them.
+
+ALTER TABLE contact_submissions
+    ADD COLUMN IF NOT EXISTS key_id VARCHAR(32),
+    ADD COLUMN IF NOT EXISTS wrapped_dek BYTEA,
+    ADD COLUMN IF NOT EXISTS sealed_pii BYTEA,
+    ALTER COLUMN name DROP NOT NULL,
+    ALTER COLUMN email DROP NOT NULL,
+    ALTER COLUMN message DROP NOT NULL,
+    ADD CONSTRAINT contact_submissions_sealed_check
+        CHECK ((key_id IS NULL) = (wrapped_dek IS NULL)
+           AND (key_id IS NULL) = (sealed_pii IS NULL)
+           AND (key_id IS NOT NULL OR message IS NOT NULL));
+
+-- reencrypt-contacts looks for rows not under the current key
+CREATE INDEX IF NOT EXISTS idx_contact_submissions_key_id
+    ON contact_submissions (key_id);
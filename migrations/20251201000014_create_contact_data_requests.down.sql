@@ -0,0 +1,6 @@
+DROP TABLE IF EXISTS contact_erasures;
+
+DROP INDEX IF EXISTS idx_contact_submissions_email_index;
+
+ALTER TABLE contact_submissions
+    DROP COLUMN IF EXISTS email_index;
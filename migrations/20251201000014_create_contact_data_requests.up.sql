@@ -0,0 +1,22 @@
+-- Self-service export and erasure of contact submissions
+--
+-- email_index is a keyed hash of the lowercased sender address (see
+-- src/data_request.rs), so submissions can be found by address while the
+-- address itself stays sealed. Rows stored earlier get one from
+-- `portfolio reencrypt-contacts`.
+
+ALTER TABLE contact_submissions
+    ADD COLUMN IF NOT EXISTS email_index VARCHAR(80);
+
+CREATE INDEX IF NOT EXISTS idx_contact_submissions_email_index
+    ON contact_submissions (email_index);
+
+-- One row per erasure request. Deliberately holds no address or hash, only
+-- what was deleted and when.
+CREATE TABLE IF NOT EXISTS contact_erasures (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    submission_count INTEGER NOT NULL CHECK (submission_count >= 0),
+    reply_count INTEGER NOT NULL CHECK (reply_count >= 0),
+    link_issued_at TIMESTAMPTZ NOT NULL,
+    erased_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
@@ -0,0 +1,8 @@
+-- Sealed replies cannot be turned back into plaintext here: SET NOT NULL
+-- fails while any remain, rather than losing them.
+
+ALTER TABLE contact_replies
+    DROP CONSTRAINT IF EXISTS contact_replies_sealed_check,
+    ALTER COLUMN subject SET NOT NULL,
+    ALTER COLUMN body SET NOT NULL,
+    DROP COLUMN IF EXISTS sealed_content;
@@ -0,0 +1,13 @@
+-- Seal admin replies with the data key of their submission (see
+-- src/encryption.rs), since they usually quote the message
+--
+-- sealed_content holds the encrypted subject and body. The plaintext
+-- columns stay for replies stored before this migration until
+-- `portfolio reencrypt-contacts` seals them.
+
+ALTER TABLE contact_replies
+    ADD COLUMN IF NOT EXISTS sealed_content BYTEA,
+    ALTER COLUMN subject DROP NOT NULL,
+    ALTER COLUMN body DROP NOT NULL,
+    ADD CONSTRAINT contact_replies_sealed_check
+        CHECK ((sealed_content IS NULL) = (body IS NOT NULL));
@@ -0,0 +1,6 @@
+-- Keyed hashes do not fit the old column; they are dropped, like the
+-- plain hashes cleared on the way up.
+
+UPDATE contact_submissions SET message_hash = NULL WHERE message_hash LIKE 'v%:%';
+
+ALTER TABLE contact_submissions ALTER COLUMN message_hash TYPE VARCHAR(64);
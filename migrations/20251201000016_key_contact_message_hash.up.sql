@@ -0,0 +1,9 @@
+-- message_hash becomes a versioned keyed hash (see src/spam/mod.rs):
+-- `v{version}:` followed by 64 hex digits
+--
+-- Hashes stored before this migration are plain SHA-256 and no longer
+-- match anything new, so they are cleared rather than kept guessable.
+
+ALTER TABLE contact_submissions ALTER COLUMN message_hash TYPE VARCHAR(80);
+
+UPDATE contact_submissions SET message_hash = NULL WHERE message_hash NOT LIKE 'v%:%';
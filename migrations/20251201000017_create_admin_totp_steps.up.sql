@@ -0,0 +1,12 @@
+-- Last TOTP time step accepted per admin
+--
+-- A code is only good once: logins with a code for this step or an
+-- earlier one are refused, so a code seen over someone's shoulder (or
+-- replayed from a captured request) cannot open a second session.
+
+CREATE TABLE IF NOT EXISTS admin_totp_steps (
+    username VARCHAR(255) PRIMARY KEY,
+    -- Unix time / 30 of the step the last accepted code was for
+    last_step BIGINT NOT NULL,
+    used_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
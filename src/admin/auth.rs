@@ -0,0 +1,147 @@
+//! Admin credentials: argon2 password hash and optional TOTP second factor
+
+use argon2::password_hash::rand_core::OsRng;
+use argon2::password_hash::{PasswordHash, PasswordHasher, PasswordVerifier, SaltString};
+use argon2::Argon2;
+use chrono::Duration;
+use totp_rs::{Algorithm, Secret, TOTP};
+
+/// Default admin session lifetime
+pub const DEFAULT_ADMIN_SESSION_HOURS: i64 = 12;
+
+/// TOTP steps accepted either side of the current one, for clock drift
+const TOTP_SKEW_STEPS: u64 = 1;
+
+/// Admin login configuration loaded from environment variables
+#[derive(Debug, Clone)]
+pub struct AdminAuthConfig {
+    pub username: String,
+    /// PHC string, e.g. `$argon2id$v=19$m=19456,t=2,p=1$...`
+    password_hash: String,
+    totp: Option<TOTP>,
+    pub session_ttl: Duration,
+}
+
+impl AdminAuthConfig {
+    /// Load admin credentials from `ADMIN_USERNAME`, `ADMIN_PASSWORD_HASH`
+    /// and the optional base32 `ADMIN_TOTP_SECRET`.
+    /// Returns None (admin login disabled) if they are missing or invalid.
+    pub fn from_env() -> Option<Self> {
+        let username = std::env::var("ADMIN_USERNAME").ok()?;
+        let password_hash = std::env::var("ADMIN_PASSWORD_HASH").ok()?;
+
+        if let Err(e) = PasswordHash::new(&password_hash) {
+            tracing::error!(%e, "ADMIN_PASSWORD_HASH is not a valid PHC string - admin login disabled");
+            return None;
+        }
+
+        let totp = match std::env::var("ADMIN_TOTP_SECRET") {
+            Ok(secret) if !secret.trim().is_empty() => {
+                let totp = Secret::Encoded(secret.trim().to_uppercase())
+                    .to_bytes()
+                    .map_err(|e| format!("{e:?}"))
+                    .and_then(|bytes| {
+                        // No skew: `totp_step` tries each step itself
+                        TOTP::new(Algorithm::SHA1, 6, 0, 30, bytes).map_err(|e| e.to_string())
+                    });
+                match totp {
+                    Ok(totp) => Some(totp),
+                    Err(e) => {
+                        tracing::error!(%e, "ADMIN_TOTP_SECRET is invalid - admin login disabled");
+                        return None;
+                    }
+                }
+            }
+            _ => None,
+        };
+
+        let session_hours = std::env::var("ADMIN_SESSION_HOURS")
+            .ok()
+            .and_then(|h| h.parse().ok())
+            .filter(|h| *h >= 1)
+            .unwrap_or(DEFAULT_ADMIN_SESSION_HOURS);
+
+        Some(Self {
+            username,
+            password_hash,
+            totp,
+            session_ttl: Duration::hours(session_hours),
+        })
+    }
+
+    /// Whether a TOTP code is required at login
+    pub fn totp_enabled(&self) -> bool {
+        self.totp.is_some()
+    }
+
+    /// Check username, password and (if enabled) TOTP code at `unix_time`.
+    /// The password hash is always verified so timing does not reveal
+    /// whether the username was right. CPU-heavy: run on a blocking thread.
+    ///
+    /// Returns None when the login is refused. Otherwise the TOTP step the
+    /// code matched, if TOTP is enabled: the caller must refuse it unless it
+    /// is later than the last step used (see `crate::db::claim_totp_step`).
+    pub fn verify(
+        &self,
+        username: &str,
+        password: &str,
+        totp_code: &str,
+        unix_time: u64,
+    ) -> Option<Option<i64>> {
+        let password_ok = PasswordHash::new(&self.password_hash)
+            .map(|hash| {
+                Argon2::default()
+                    .verify_password(password.as_bytes(), &hash)
+                    .is_ok()
+            })
+            .unwrap_or(false);
+        let username_ok = username == self.username;
+        let totp_step = match &self.totp {
+            Some(totp) => totp_step(totp, totp_code.trim(), unix_time).map(Some),
+            None => Some(None),
+        };
+
+        totp_step.filter(|_| password_ok && username_ok)
+    }
+}
+
+/// Latest step within the skew that `code` is valid for
+fn totp_step(totp: &TOTP, code: &str, unix_time: u64) -> Option<i64> {
+    let current = unix_time / totp.step;
+    (current.saturating_sub(TOTP_SKEW_STEPS)..=current + TOTP_SKEW_STEPS)
+        .rev()
+        .find(|step| totp.check(code, step * totp.step))
+        .map(|step| step as i64)
+}
+
+/// Hash a password for `ADMIN_PASSWORD_HASH` (argon2id, random salt)
+pub fn hash_password(password: &str) -> Result<String, argon2::password_hash::Error> {
+    let salt = SaltString::generate(&mut OsRng);
+    Ok(Argon2::default()
+        .hash_password(password.as_bytes(), &salt)?
+        .to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn totp_codes_match_their_step() {
+        let totp = TOTP::new(Algorithm::SHA1, 6, 0, 30, b"12345678901234567890".to_vec()).unwrap();
+        let now = 1_000_000_000;
+        let current = (now / 30) as i64;
+
+        assert_eq!(totp_step(&totp, &totp.generate(now), now), Some(current));
+        // One step of drift either way
+        assert_eq!(
+            totp_step(&totp, &totp.generate(now - 30), now),
+            Some(current - 1)
+        );
+        assert_eq!(
+            totp_step(&totp, &totp.generate(now + 30), now),
+            Some(current + 1)
+        );
+        assert_eq!(totp_step(&totp, &totp.generate(now - 60), now), None);
+    }
+}
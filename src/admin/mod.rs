@@ -0,0 +1,49 @@
+//! Admin authentication (server-side only)
+//!
+//! Every admin server function calls `require_admin` before touching data,
+//! so admin pages are protected even if the route itself is reachable.
+//! Contexts must be read before the first `.await` (the reactive owner is
+//! not kept across awaits during SSR), so the pool is passed in.
+
+mod auth;
+mod session;
+
+pub use auth::*;
+pub use session::*;
+
+use axum::http::request::Parts;
+use leptos::prelude::*;
+use leptos_axum::extract;
+use sqlx::PgPool;
+
+/// Ensure the current request carries a valid admin session cookie.
+/// Returns the admin username.
+pub async fn require_admin(pool: &PgPool) -> Result<String, ServerFnError> {
+    current_admin(pool)
+        .await?
+        .ok_or_else(|| ServerFnError::new("Unauthorized"))
+}
+
+/// Username of the logged-in admin, if the request has a valid session.
+/// Sessions of any other username (after `ADMIN_USERNAME` changed), or any
+/// session at all while admin login is not configured, are refused.
+pub async fn current_admin(pool: &PgPool) -> Result<Option<String>, ServerFnError> {
+    let Some(config) = use_context::<AdminAuthConfig>() else {
+        return Ok(None);
+    };
+    let parts: Parts = extract().await?;
+    let Some(token) = session_token(&parts.headers) else {
+        return Ok(None);
+    };
+
+    let session = crate::db::touch_admin_session(pool, &hash_session_token(&token))
+        .await
+        .map_err(|e| {
+            tracing::error!(?e, "Failed to look up admin session");
+            ServerFnError::new("Database error")
+        })?;
+
+    Ok(session
+        .map(|s| s.username)
+        .filter(|username| *username == config.username))
+}
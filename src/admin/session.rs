@@ -0,0 +1,48 @@
+//! Admin session cookies
+//!
+//! The cookie holds a random token; only its SHA-256 is stored in
+//! `admin_sessions`.
+
+use argon2::password_hash::rand_core::{OsRng, RngCore};
+use axum::http::HeaderMap;
+use sha2::{Digest, Sha256};
+
+/// Session cookie name
+pub const SESSION_COOKIE: &str = "admin_session";
+
+/// Generate a new random session token (256 bits, hex)
+pub fn new_session_token() -> String {
+    let mut bytes = [0u8; 32];
+    OsRng.fill_bytes(&mut bytes);
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+/// Hash a session token for storage and lookup
+pub fn hash_session_token(token: &str) -> String {
+    format!("{:x}", Sha256::digest(token.as_bytes()))
+}
+
+/// `Set-Cookie` value for a new session
+pub fn session_cookie(token: &str, max_age_seconds: i64) -> String {
+    format!(
+        "{SESSION_COOKIE}={token}; Path=/; Max-Age={max_age_seconds}; HttpOnly; Secure; SameSite=Strict"
+    )
+}
+
+/// `Set-Cookie` value that removes the session cookie
+pub fn clear_session_cookie() -> String {
+    format!("{SESSION_COOKIE}=; Path=/; Max-Age=0; HttpOnly; Secure; SameSite=Strict")
+}
+
+/// Read the session token from the request's `Cookie` headers
+pub fn session_token(headers: &HeaderMap) -> Option<String> {
+    headers
+        .get_all(axum::http::header::COOKIE)
+        .iter()
+        .filter_map(|v| v.to_str().ok())
+        .flat_map(|v| v.split(';'))
+        .filter_map(|pair| pair.trim().split_once('='))
+        .find(|(name, _)| *name == SESSION_COOKIE)
+        .map(|(_, value)| value.to_string())
+        .filter(|value| value.len() == 64 && value.bytes().all(|b| b.is_ascii_hexdigit()))
+}
@@ -0,0 +1,165 @@
+//! Engagement measurement for the page view on screen
+//!
+//! Tracks visible time (paused while the tab is hidden) and maximum scroll
+//! depth, and reports them with `navigator.sendBeacon` so the request
+//! survives the page being closed.
+
+use crate::server_fns::EngagementInput;
+
+/// Engagement state of the current page view (client-side only)
+#[derive(Debug, Default)]
+pub(super) struct Engagement {
+    /// Incremented on every navigation so late server responses are ignored
+    generation: u64,
+    page_view_id: Option<String>,
+    visible_ms: f64,
+    visible_since: Option<f64>,
+    max_scroll_percent: i32,
+}
+
+impl Engagement {
+    /// Start measuring a new page view. Returns its generation.
+    pub(super) fn start(&mut self) -> u64 {
+        self.generation += 1;
+        self.page_view_id = None;
+        self.visible_ms = 0.0;
+        self.visible_since = (!document_hidden()).then(now_ms);
+        self.max_scroll_percent = scroll_depth_percent();
+        self.generation
+    }
+
+    /// Attach the stored page view ID, unless the visitor has navigated since
+    pub(super) fn attach(&mut self, generation: u64, page_view_id: String) {
+        if self.generation == generation {
+            self.page_view_id = Some(page_view_id);
+        }
+    }
+
+    /// Record the current scroll position
+    #[cfg(feature = "hydrate")]
+    pub(super) fn on_scroll(&mut self) {
+        self.max_scroll_percent = self.max_scroll_percent.max(scroll_depth_percent());
+    }
+
+    /// Stop the visible-time clock (tab hidden or page left)
+    pub(super) fn pause(&mut self) {
+        if let Some(since) = self.visible_since.take() {
+            self.visible_ms += (now_ms() - since).max(0.0);
+        }
+    }
+
+    /// Restart the visible-time clock (tab visible again)
+    #[cfg(feature = "hydrate")]
+    pub(super) fn resume(&mut self) {
+        if self.visible_since.is_none() {
+            self.visible_since = Some(now_ms());
+        }
+    }
+
+    /// Send cumulative engagement for the current page view, if it was stored
+    pub(super) fn report(&self) {
+        let Some(page_view_id) = self.page_view_id.clone() else {
+            return;
+        };
+
+        let running_ms = self.visible_since.map(|s| now_ms() - s).unwrap_or(0.0);
+        send_beacon(EngagementInput {
+            page_view_id,
+            time_on_page_seconds: ((self.visible_ms + running_ms) / 1000.0).round() as i32,
+            scroll_depth_percent: self.max_scroll_percent,
+        });
+    }
+}
+
+/// Register scroll, visibility and page-hide listeners (client-side only)
+#[cfg(feature = "hydrate")]
+pub(super) fn listen(engagement: leptos::prelude::StoredValue<Engagement>) {
+    use leptos::ev;
+    use leptos::prelude::*;
+
+    // Handles are intentionally kept: the tracker lives as long as the app
+    let _ = window_event_listener(ev::scroll, move |_| {
+        engagement.update_value(|e| e.on_scroll());
+    });
+
+    // The tab may be closed while hidden, so report every time it is hidden
+    let _ = window_event_listener_untyped("visibilitychange", move |_| {
+        engagement.update_value(|e| {
+            if document_hidden() {
+                e.pause();
+                e.report();
+            } else {
+                e.resume();
+            }
+        });
+    });
+
+    let _ = window_event_listener_untyped("pagehide", move |_| {
+        engagement.update_value(|e| {
+            e.pause();
+            e.report();
+        });
+    });
+}
+
+/// Milliseconds since page load (client-side only)
+fn now_ms() -> f64 {
+    #[cfg(feature = "hydrate")]
+    {
+        if let Some(performance) = web_sys::window().and_then(|w| w.performance()) {
+            return performance.now();
+        }
+    }
+    0.0
+}
+
+/// Whether the document is currently hidden (client-side only)
+fn document_hidden() -> bool {
+    #[cfg(feature = "hydrate")]
+    {
+        if let Some(document) = web_sys::window().and_then(|w| w.document()) {
+            return document.hidden();
+        }
+    }
+    false
+}
+
+/// How far down the page the viewport bottom currently is, in percent
+fn scroll_depth_percent() -> i32 {
+    #[cfg(feature = "hydrate")]
+    {
+        if let Some(window) = web_sys::window() {
+            let root = window.document().and_then(|d| d.document_element());
+            if let (Some(root), Ok(scroll_y)) = (root, window.scroll_y()) {
+                let total = root.scroll_height() as f64;
+                if total <= 0.0 {
+                    return 100;
+                }
+                let seen = scroll_y + root.client_height() as f64;
+                return ((seen / total) * 100.0).round().clamp(0.0, 100.0) as i32;
+            }
+        }
+    }
+    0
+}
+
+/// POST engagement with navigator.sendBeacon (client-side only)
+fn send_beacon(input: EngagementInput) {
+    #[cfg(feature = "hydrate")]
+    {
+        use crate::server_fns::RecordEngagement;
+        use leptos::server_fn::ServerFn;
+
+        // Same body shape as the JSON-encoded server function call
+        let body = serde_json::json!({ "input": input }).to_string();
+        if let Some(window) = web_sys::window() {
+            let _ = window
+                .navigator()
+                .send_beacon_with_opt_str(RecordEngagement::url(), Some(&body));
+        }
+    }
+    #[cfg(not(feature = "hydrate"))]
+    {
+        let _ = input; // Beacons are only sent from the browser
+    }
+}
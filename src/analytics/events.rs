@@ -0,0 +1,105 @@
+//! Custom event tracking
+//!
+//! Events are queued on the client and sent to the server in small batches,
+//! so a burst of clicks costs a single request.
+
+use leptos::prelude::*;
+
+use crate::server_fns::TrackEventInput;
+
+/// Event names accepted by the server (anything else is dropped)
+pub const ALLOWED_EVENTS: &[&str] = &["theme_toggle", "contact_open", "github_repo_click"];
+
+/// Event categories accepted by the server (anything else is stored as none)
+pub const ALLOWED_EVENT_CATEGORIES: &[&str] = &["preferences", "contact", "outbound"];
+
+/// Maximum serialized size of `event_data` in bytes
+pub const MAX_EVENT_DATA_BYTES: usize = 1024;
+
+/// Maximum number of events accepted per request
+pub const MAX_EVENTS_PER_BATCH: usize = 20;
+
+/// Delay before a queued batch is sent (milliseconds)
+#[cfg(feature = "hydrate")]
+const FLUSH_DELAY_MS: u64 = 2000;
+
+/// Client-side event queue
+#[derive(Clone, Copy)]
+pub struct EventTracker {
+    queue: StoredValue<Vec<TrackEventInput>>,
+}
+
+impl EventTracker {
+    /// Queue an event. The batch is sent after a short delay, or immediately once full.
+    pub fn track(&self, name: &str, category: &str, data: Option<serde_json::Value>) {
+        let was_empty = self.queue.with_value(|q| q.is_empty());
+
+        self.queue.update_value(|q| {
+            q.push(TrackEventInput {
+                name: name.to_string(),
+                category: Some(category.to_string()),
+                data: data.map(|d| d.to_string()),
+                page_path: current_path(),
+            })
+        });
+
+        if self.queue.with_value(|q| q.len()) >= MAX_EVENTS_PER_BATCH {
+            self.flush();
+        } else if was_empty {
+            self.schedule_flush();
+        }
+    }
+
+    /// Send all queued events now
+    pub fn flush(&self) {
+        let batch = self.queue.try_update_value(std::mem::take).unwrap_or_default();
+        if batch.is_empty() {
+            return;
+        }
+
+        #[cfg(feature = "hydrate")]
+        leptos::task::spawn_local(async move {
+            let _ = crate::server_fns::track_events(batch).await;
+        });
+        #[cfg(not(feature = "hydrate"))]
+        {
+            let _ = batch; // Events are only tracked in the browser
+        }
+    }
+
+    fn schedule_flush(&self) {
+        #[cfg(feature = "hydrate")]
+        {
+            let tracker = *self;
+            set_timeout(
+                move || tracker.flush(),
+                std::time::Duration::from_millis(FLUSH_DELAY_MS),
+            );
+        }
+    }
+}
+
+/// Provide the event tracker to the application
+pub fn provide_event_tracker() {
+    provide_context(EventTracker {
+        queue: StoredValue::new(Vec::new()),
+    });
+}
+
+/// Hook to access the event tracker
+pub fn use_track_event() -> EventTracker {
+    expect_context::<EventTracker>()
+}
+
+/// Current location path (client-side only)
+fn current_path() -> Option<String> {
+    #[cfg(feature = "hydrate")]
+    {
+        if let Some(window) = web_sys::window() {
+            if let Ok(path) = window.location().pathname() {
+                return Some(path);
+            }
+        }
+    }
+    None
+}
@@ -0,0 +1,103 @@
+//! Optional GeoIP country resolution (server-side only)
+//!
+//! Loads a local MaxMind-format database (e.g. GeoLite2-Country.mmdb) once at
+//! startup. There are no network lookups, and only the ISO country code is
+//! ever kept. Without a configured database every lookup returns None.
+
+use maxminddb::{geoip2, MaxMindDBError, Reader};
+use std::net::IpAddr;
+use std::path::Path;
+use std::sync::Arc;
+
+/// Country resolver, shared through Leptos context
+#[derive(Clone, Default)]
+pub struct GeoIp {
+    reader: Option<Arc<Reader<Vec<u8>>>>,
+}
+
+impl GeoIp {
+    /// Resolver that never finds anything
+    pub fn disabled() -> Self {
+        Self::default()
+    }
+
+    /// Load the database at `path`
+    pub fn open(path: impl AsRef<Path>) -> Result<Self, MaxMindDBError> {
+        let reader = Reader::open_readfile(path)?;
+        Ok(Self {
+            reader: Some(Arc::new(reader)),
+        })
+    }
+
+    /// Load the database configured in GEOIP_DB_PATH.
+    /// Returns a disabled resolver if the variable is unset or the file is unusable.
+    pub fn from_env() -> Self {
+        let Ok(path) = std::env::var("GEOIP_DB_PATH") else {
+            return Self::disabled();
+        };
+
+        match Self::open(&path) {
+            Ok(geoip) => {
+                tracing::info!(%path, "GeoIP database loaded");
+                geoip
+            }
+            Err(e) => {
+                tracing::warn!(%path, ?e, "Failed to load GeoIP database - country lookup disabled");
+                Self::disabled()
+            }
+        }
+    }
+
+    /// Whether a database is loaded
+    pub fn is_enabled(&self) -> bool {
+        self.reader.is_some()
+    }
+
+    /// Resolve an IP address to an ISO 3166-1 alpha-2 country code
+    pub fn country_code(&self, ip: &str) -> Option<String> {
+        let reader = self.reader.as_ref()?;
+        let ip: IpAddr = ip.trim().parse().ok()?;
+
+        let record: geoip2::Country = reader.lookup(ip).ok()?;
+        let code = record.country?.iso_code?;
+
+        (code.len() == 2 && code.chars().all(|c| c.is_ascii_alphabetic()))
+            .then(|| code.to_ascii_uppercase())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Generated by tests/fixtures/make_geoip_fixture.py
+    const FIXTURE: &str = concat!(
+        env!("CARGO_MANIFEST_DIR"),
+        "/tests/fixtures/geoip-country-test.mmdb"
+    );
+
+    #[test]
+    fn resolves_ipv4_and_ipv6_networks() {
+        let geoip = GeoIp::open(FIXTURE).expect("fixture loads");
+        assert!(geoip.is_enabled());
+        assert_eq!(geoip.country_code("192.0.2.10").as_deref(), Some("FR"));
+        assert_eq!(geoip.country_code("198.51.100.200").as_deref(), Some("HU"));
+        assert_eq!(geoip.country_code("203.0.113.5").as_deref(), Some("CA"));
+        assert_eq!(geoip.country_code("2001:db8::1").as_deref(), Some("DE"));
+    }
+
+    #[test]
+    fn unknown_addresses_resolve_to_none() {
+        let geoip = GeoIp::open(FIXTURE).expect("fixture loads");
+        assert_eq!(geoip.country_code("203.0.113.200"), None);
+        assert_eq!(geoip.country_code("8.8.8.8"), None);
+        assert_eq!(geoip.country_code("unknown"), None);
+    }
+
+    #[test]
+    fn disabled_resolver_does_nothing() {
+        let geoip = GeoIp::disabled();
+        assert!(!geoip.is_enabled());
+        assert_eq!(geoip.country_code("192.0.2.10"), None);
+    }
+}
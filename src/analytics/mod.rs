@@ -0,0 +1,98 @@
+//! Privacy-first analytics
+//!
+//! The hydrated client reports a page view after every route change,
+//! measures engagement until the visitor leaves the page, and batches custom
+//! events; the server decides what (if anything) gets stored.
+//! No cookies, no raw IPs.
+
+mod engagement;
+mod events;
+#[cfg(feature = "ssr")]
+pub mod geoip;
+#[cfg(feature = "ssr")]
+pub mod privacy;
+
+pub use events::{
+    provide_event_tracker, use_track_event, EventTracker, ALLOWED_EVENTS, ALLOWED_EVENT_CATEGORIES,
+    MAX_EVENTS_PER_BATCH, MAX_EVENT_DATA_BYTES,
+};
+
+use leptos::prelude::*;
+use leptos_router::hooks::use_location;
+
+use crate::server_fns::{record_page_view, PageViewInput};
+use engagement::Engagement;
+
+/// Reports a page view on every route change, and the engagement of the
+/// previous page when leaving it (client-side only).
+/// Must be rendered inside the `<Router>`.
+#[component]
+pub fn PageViewTracker() -> impl IntoView {
+    let location = use_location();
+    let is_first_view = StoredValue::new(true);
+    let engagement = StoredValue::new(Engagement::default());
+
+    #[cfg(feature = "hydrate")]
+    engagement::listen(engagement);
+
+    Effect::new(move |_| {
+        let path = location.pathname.get();
+
+        // A route change is a page exit for the previous view
+        let generation = engagement
+            .try_update_value(|e| {
+                e.pause();
+                e.report();
+                e.start()
+            })
+            .unwrap_or_default();
+
+        // document.referrer only tells how the visitor reached the site,
+        // later in-app navigations are internal
+        let referrer = if is_first_view.get_value() {
+            document_referrer()
+        } else {
+            None
+        };
+        is_first_view.set_value(false);
+
+        let input = PageViewInput {
+            path,
+            title: document_title(),
+            referrer,
+        };
+        leptos::task::spawn_local(async move {
+            if let Ok(Some(page_view_id)) = record_page_view(input).await {
+                engagement.update_value(|e| e.attach(generation, page_view_id));
+            }
+        });
+    });
+}
+
+/// Read document.title (client-side only)
+fn document_title() -> Option<String> {
+    #[cfg(feature = "hydrate")]
+    {
+        if let Some(document) = web_sys::window().and_then(|w| w.document()) {
+            let title = document.title();
+            if !title.is_empty() {
+                return Some(title);
+            }
+        }
+    }
+    None
+}
+
+/// Read document.referrer (client-side only)
+fn document_referrer() -> Option<String> {
+    #[cfg(feature = "hydrate")]
+    {
+        if let Some(document) = web_sys::window().and_then(|w| w.document()) {
+            let referrer = document.referrer();
+            if !referrer.is_empty() {
+                return Some(referrer);
+            }
+        }
+    }
+    None
+}
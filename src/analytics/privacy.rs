@@ -0,0 +1,87 @@
+//! Privacy helpers for analytics ingestion (server-side only)
+//!
+//! Visitors are never identified: the session hash is a keyed hash (see
+//! `crate::ip_hash`) of IP and user agent with the day mixed in, so the same
+//! visitor cannot be linked across days. Raw IPs are never stored.
+
+use chrono::NaiveDate;
+use http::HeaderMap;
+
+use crate::ip_hash::IpHasher;
+
+/// Returns true if the browser asked not to be tracked (DNT or Global Privacy Control)
+pub fn tracking_opted_out(headers: &HeaderMap) -> bool {
+    let header_is_one = |name: &str| {
+        headers
+            .get(name)
+            .and_then(|v| v.to_str().ok())
+            .is_some_and(|v| v.trim() == "1")
+    };
+
+    header_is_one("dnt") || header_is_one("sec-gpc")
+}
+
+/// Compute the daily-rotating session hash for a visitor
+pub fn session_hash(hasher: &IpHasher, ip: &str, user_agent: &str, day: NaiveDate) -> String {
+    hasher.hash(
+        &format!("session:{}", day.format("%Y-%m-%d")),
+        &format!("{ip}\n{user_agent}"),
+    )
+}
+
+/// Strip query string and fragment from a referrer URL (they often carry tokens)
+pub fn sanitize_referrer(referrer: &str) -> Option<String> {
+    let referrer = referrer.trim();
+    if !(referrer.starts_with("http://") || referrer.starts_with("https://")) {
+        return None;
+    }
+
+    let end = referrer.find(['?', '#']).unwrap_or(referrer.len());
+    let cleaned = &referrer[..end];
+    (cleaned.len() <= 2048).then(|| cleaned.to_string())
+}
+
+/// Extract the host of a referrer URL, without `www.` and port.
+/// Returns None for self-referrals from `own_host`, and for hosts too long
+/// to be a domain name.
+pub fn referrer_domain(referrer: &str, own_host: Option<&str>) -> Option<String> {
+    let without_scheme = referrer.split_once("://")?.1;
+    let authority = without_scheme.split(['/', '?', '#']).next()?;
+    let host = authority.rsplit('@').next()?;
+    let host = host.split(':').next()?.to_lowercase();
+    let host = host.strip_prefix("www.").unwrap_or(&host).to_string();
+
+    // Longer than any DNS name, and than the column
+    if host.is_empty() || host.len() > 253 {
+        return None;
+    }
+
+    let own = own_host.map(|h| {
+        let h = h.split(':').next().unwrap_or(h).to_lowercase();
+        h.strip_prefix("www.").unwrap_or(&h).to_string()
+    });
+    if own.as_deref() == Some(host.as_str()) {
+        return None;
+    }
+
+    Some(host)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn referrer_domains() {
+        assert_eq!(
+            referrer_domain("https://www.Example.com:8080/post", None).as_deref(),
+            Some("example.com")
+        );
+        assert_eq!(
+            referrer_domain("https://example.com/", Some("www.example.com")),
+            None
+        );
+        let long_host = format!("https://{}.com/", "a".repeat(300));
+        assert_eq!(referrer_domain(&long_host, None), None);
+    }
+}
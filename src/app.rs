@@ -1,18 +1,24 @@
 use leptos::prelude::*;
 use leptos_meta::{provide_meta_context, MetaTags, Stylesheet, Title};
 use leptos_router::{
-    components::{Redirect, Route, Router, Routes},
+    components::{ParentRoute, Redirect, Route, Router, Routes},
     ParamSegment, StaticSegment,
 };
 
+use crate::analytics::{provide_event_tracker, PageViewTracker};
 use crate::components::{ColorModeToggle, ContactFAB, LanguageToggle, ThemeToggle};
 use crate::i18n::{provide_i18n_context, use_i18n};
-use crate::pages::{AboutPage, BlogPage, BlogPostPage, BlogTagPage, CvPage, ProjectsPage};
+use crate::pages::{
+    AboutPage, AdminAnalyticsPage, AdminContactPage, AdminGuard, AdminInboxPage, AdminLoginPage,
+    AdminOutboxPage, BlogPage, BlogPostPage, BlogTagPage, CvPage, PrivacyDataPage, PrivacyPage,
+    ProjectsPage,
+};
 use crate::themes::{provide_color_mode_context, provide_theme_context};
 
 /// Sets the Content-Security-Policy header with the current nonce.
 /// This component runs on the server and has no visual output.
 #[component]
+#[allow(clippy::unused_unit)]
 fn CspHeader() -> impl IntoView {
     #[cfg(feature = "ssr")]
     {
@@ -77,6 +83,8 @@ pub fn App() -> impl IntoView {
     // Provide theme and color mode contexts
     provide_theme_context();
     provide_color_mode_context();
+    // Provide batched custom event tracking
+    provide_event_tracker();
 
     view! {
         // Set CSP header with nonce (server-side only, no visual output)
@@ -91,6 +99,8 @@ pub fn App() -> impl IntoView {
 
         // content for this welcome page
         <Router>
+            // Report page views on route changes (client-side only)
+            <PageViewTracker/>
             <Nav/>
             <main>
                 <Routes fallback=|| "Page not found.".into_view()>
@@ -101,6 +111,16 @@ pub fn App() -> impl IntoView {
                     <Route path=StaticSegment("blog") view=BlogPage/>
                     <Route path=(StaticSegment("blog"), StaticSegment("tags"), ParamSegment("tag")) view=BlogTagPage/>
                     <Route path=(StaticSegment("blog"), ParamSegment("slug")) view=BlogPostPage/>
+                    <Route path=StaticSegment("privacy") view=PrivacyPage/>
+                    <Route path=(StaticSegment("privacy"), StaticSegment("data")) view=PrivacyDataPage/>
+                    <Route path=(StaticSegment("admin"), StaticSegment("login")) view=AdminLoginPage/>
+                    <ParentRoute path=StaticSegment("admin") view=AdminGuard>
+                        <Route path=StaticSegment("") view=|| view! { <Redirect path="/admin/analytics" /> }/>
+                        <Route path=StaticSegment("analytics") view=AdminAnalyticsPage/>
+                        <Route path=StaticSegment("inbox") view=AdminInboxPage/>
+                        <Route path=(StaticSegment("inbox"), ParamSegment("id")) view=AdminContactPage/>
+                        <Route path=StaticSegment("outbox") view=AdminOutboxPage/>
+                    </ParentRoute>
                 </Routes>
             </main>
         </Router>
@@ -0,0 +1,266 @@
+//! Client IP resolution behind trusted reverse proxies
+//!
+//! Forwarding headers are only believed when the TCP peer is a configured
+//! trusted proxy (`TRUSTED_PROXIES`: comma-separated IPs or CIDRs, such as
+//! the ingress network). The client is then the right-most address in the
+//! chain that is not itself a trusted proxy, read from `Forwarded`, else
+//! `X-Forwarded-For`, else `X-Real-IP`. Otherwise the peer address from
+//! `ConnectInfo` is used as-is, so a client cannot choose its own IP.
+
+use std::net::{IpAddr, Ipv4Addr, SocketAddr};
+use std::sync::Arc;
+
+use axum::extract::ConnectInfo;
+use axum::http::request::Parts;
+use axum::http::HeaderMap;
+
+/// An IP network such as `10.0.0.0/8` or `fd00::/8`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct IpCidr {
+    network: IpAddr,
+    prefix: u8,
+}
+
+impl IpCidr {
+    /// Parse `addr/prefix` or a single address
+    pub fn parse(value: &str) -> Option<Self> {
+        let (addr, prefix) = match value.trim().split_once('/') {
+            Some((addr, prefix)) => (addr, Some(prefix.parse::<u8>().ok()?)),
+            None => (value.trim(), None),
+        };
+        let network: IpAddr = addr.parse().ok()?;
+        let max = if network.is_ipv4() { 32 } else { 128 };
+        let prefix = prefix.unwrap_or(max);
+        (prefix <= max).then_some(Self { network, prefix })
+    }
+
+    pub fn contains(&self, ip: IpAddr) -> bool {
+        match (self.network, ip.to_canonical()) {
+            (IpAddr::V4(net), IpAddr::V4(ip)) => {
+                let mask = u32::MAX
+                    .checked_shl(32 - u32::from(self.prefix))
+                    .unwrap_or(0);
+                u32::from(net) & mask == u32::from(ip) & mask
+            }
+            (IpAddr::V6(net), IpAddr::V6(ip)) => {
+                let mask = u128::MAX
+                    .checked_shl(128 - u32::from(self.prefix))
+                    .unwrap_or(0);
+                u128::from(net) & mask == u128::from(ip) & mask
+            }
+            _ => false,
+        }
+    }
+}
+
+/// Proxies whose forwarding headers are believed, shared through the
+/// Leptos context
+#[derive(Debug, Clone, Default)]
+pub struct TrustedProxies {
+    networks: Arc<[IpCidr]>,
+}
+
+impl TrustedProxies {
+    pub fn new(networks: Vec<IpCidr>) -> Self {
+        Self {
+            networks: networks.into(),
+        }
+    }
+
+    /// Load `TRUSTED_PROXIES`. Errors name the first entry that does not parse.
+    pub fn from_env() -> Result<Self, String> {
+        let value = std::env::var("TRUSTED_PROXIES").unwrap_or_default();
+        value
+            .split(',')
+            .map(str::trim)
+            .filter(|entry| !entry.is_empty())
+            .map(|entry| IpCidr::parse(entry).ok_or_else(|| entry.to_string()))
+            .collect::<Result<Vec<_>, _>>()
+            .map(Self::new)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.networks.is_empty()
+    }
+
+    pub fn contains(&self, ip: IpAddr) -> bool {
+        self.networks.iter().any(|net| net.contains(ip))
+    }
+
+    /// Client IP for a request received from `peer`
+    pub fn resolve(&self, peer: IpAddr, headers: &HeaderMap) -> IpAddr {
+        let peer = peer.to_canonical();
+        if !self.contains(peer) {
+            return peer;
+        }
+
+        let chain = forwarded_chain(headers);
+        let mut client = peer;
+        // Walk back from the hop closest to us; stop at the first address a
+        // trusted proxy did not add itself
+        for hop in chain.iter().rev() {
+            match hop {
+                Some(ip) => {
+                    client = ip.to_canonical();
+                    if !self.contains(client) {
+                        break;
+                    }
+                }
+                // Unparsable or obfuscated ("unknown", "_hidden"): the last
+                // known hop is as far as we can go
+                None => break,
+            }
+        }
+        client
+    }
+
+    /// Client IP for the current server function request, falling back to
+    /// the unspecified address when there is no connection info
+    pub fn client_ip(&self, parts: &Parts) -> IpAddr {
+        let peer = parts
+            .extensions
+            .get::<ConnectInfo<SocketAddr>>()
+            .map(|ConnectInfo(addr)| addr.ip())
+            .unwrap_or(IpAddr::V4(Ipv4Addr::UNSPECIFIED));
+        self.resolve(peer, &parts.headers)
+    }
+}
+
+/// Addresses from the first forwarding header present, client first
+fn forwarded_chain(headers: &HeaderMap) -> Vec<Option<IpAddr>> {
+    let values = |name: &str| {
+        headers
+            .get_all(name)
+            .iter()
+            .filter_map(|v| v.to_str().ok())
+            .flat_map(|v| v.split(','))
+            .map(str::trim)
+            .filter(|v| !v.is_empty())
+            .map(str::to_string)
+            .collect::<Vec<_>>()
+    };
+
+    // RFC 7239: `for=192.0.2.60;proto=https, for="[2001:db8::1]:4711"`
+    let forwarded: Vec<Option<IpAddr>> = values("forwarded")
+        .iter()
+        .map(|element| {
+            element
+                .split(';')
+                .filter_map(|pair| pair.trim().split_once('='))
+                .find(|(name, _)| name.eq_ignore_ascii_case("for"))
+                .and_then(|(_, value)| parse_node(value))
+        })
+        .collect();
+    if !forwarded.is_empty() {
+        return forwarded;
+    }
+
+    let x_forwarded_for = values("x-forwarded-for");
+    if !x_forwarded_for.is_empty() {
+        return x_forwarded_for.iter().map(|v| parse_node(v)).collect();
+    }
+
+    values("x-real-ip")
+        .iter()
+        .take(1)
+        .map(|v| parse_node(v))
+        .collect()
+}
+
+/// Parse `1.2.3.4`, `1.2.3.4:80`, `2001:db8::1` or `"[2001:db8::1]:80"`
+fn parse_node(value: &str) -> Option<IpAddr> {
+    let value = value.trim().trim_matches('"');
+    if let Ok(ip) = value.parse() {
+        return Some(ip);
+    }
+    if let Some(rest) = value.strip_prefix('[') {
+        return rest.split_once(']')?.0.parse().ok();
+    }
+    value
+        .rsplit_once(':')?
+        .0
+        .parse::<Ipv4Addr>()
+        .ok()
+        .map(IpAddr::V4)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::http::HeaderValue;
+
+    fn headers(pairs: &[(&'static str, &str)]) -> HeaderMap {
+        let mut map = HeaderMap::new();
+        for (name, value) in pairs {
+            map.append(*name, HeaderValue::from_str(value).unwrap());
+        }
+        map
+    }
+
+    fn ip(value: &str) -> IpAddr {
+        value.parse().unwrap()
+    }
+
+    #[test]
+    fn ignores_headers_from_untrusted_peers() {
+        let proxies = TrustedProxies::new(vec![IpCidr::parse("10.0.0.0/8").unwrap()]);
+        let forged = headers(&[("x-forwarded-for", "1.1.1.1"), ("x-real-ip", "1.1.1.1")]);
+        assert_eq!(
+            proxies.resolve(ip("203.0.113.9"), &forged),
+            ip("203.0.113.9")
+        );
+        assert_eq!(
+            TrustedProxies::default().resolve(ip("10.0.0.2"), &forged),
+            ip("10.0.0.2")
+        );
+    }
+
+    #[test]
+    fn walks_the_chain_past_trusted_proxies() {
+        let proxies = TrustedProxies::new(vec![
+            IpCidr::parse("10.0.0.0/8").unwrap(),
+            IpCidr::parse("fd00::/8").unwrap(),
+        ]);
+        let peer = ip("10.0.0.2");
+
+        // The client prepended a fake entry; the ingress appended the real one
+        let xff = headers(&[("x-forwarded-for", "6.6.6.6, 198.51.100.7, 10.1.2.3")]);
+        assert_eq!(proxies.resolve(peer, &xff), ip("198.51.100.7"));
+
+        let forwarded = headers(&[
+            ("forwarded", "for=6.6.6.6"),
+            (
+                "forwarded",
+                r#"for="[2001:db8::17]:4711";proto=https, for=10.1.2.3"#,
+            ),
+            ("x-forwarded-for", "192.0.2.1"),
+        ]);
+        assert_eq!(proxies.resolve(peer, &forwarded), ip("2001:db8::17"));
+
+        let real_ip = headers(&[("x-real-ip", "192.0.2.44")]);
+        assert_eq!(
+            proxies.resolve(ip("::ffff:10.0.0.2"), &real_ip),
+            ip("192.0.2.44")
+        );
+
+        // Obfuscated hop: stop at the last address we know
+        let hidden = headers(&[("forwarded", "for=_hidden, for=10.1.2.3")]);
+        assert_eq!(proxies.resolve(peer, &hidden), ip("10.1.2.3"));
+
+        assert_eq!(proxies.resolve(peer, &HeaderMap::new()), peer);
+    }
+
+    #[test]
+    fn parses_cidrs() {
+        let net = IpCidr::parse("192.168.0.0/16").unwrap();
+        assert!(net.contains(ip("192.168.44.1")));
+        assert!(!net.contains(ip("192.169.0.1")));
+        assert!(IpCidr::parse("0.0.0.0/0").unwrap().contains(ip("8.8.8.8")));
+        assert!(IpCidr::parse("127.0.0.1")
+            .unwrap()
+            .contains(ip("127.0.0.1")));
+        assert_eq!(IpCidr::parse("10.0.0.0/33"), None);
+        assert_eq!(IpCidr::parse("traefik"), None);
+        assert!(TrustedProxies::new(vec![IpCidr::parse("::1").unwrap()]).contains(ip("::1")));
+    }
+}
@@ -0,0 +1,179 @@
+//! Inline SVG charts
+//!
+//! Charts are plain SVG elements rendered with the page (server-side on the
+//! first load), so they need no chart library or extra scripts under the CSP.
+
+use leptos::prelude::*;
+
+use crate::server_fns::ChartPoint;
+
+const LINE_WIDTH: f64 = 640.0;
+const LINE_HEIGHT: f64 = 220.0;
+const LINE_PADDING_X: f64 = 40.0;
+const LINE_PADDING_Y: f64 = 24.0;
+
+const BAR_WIDTH: f64 = 640.0;
+const BAR_ROW_HEIGHT: f64 = 28.0;
+const BAR_LABEL_WIDTH: f64 = 200.0;
+const BAR_VALUE_WIDTH: f64 = 60.0;
+
+/// Line chart of values over time (e.g. daily views)
+#[component]
+pub fn LineChart(points: Vec<ChartPoint>, #[prop(into)] label: String) -> impl IntoView {
+    let max = points.iter().map(|p| p.value).max().unwrap_or(0).max(1) as f64;
+    let plot_width = LINE_WIDTH - LINE_PADDING_X * 2.0;
+    let plot_height = LINE_HEIGHT - LINE_PADDING_Y * 2.0;
+    let step = if points.len() > 1 {
+        plot_width / (points.len() - 1) as f64
+    } else {
+        0.0
+    };
+
+    let coords: Vec<(f64, f64)> = points
+        .iter()
+        .enumerate()
+        .map(|(i, p)| {
+            let x = LINE_PADDING_X + step * i as f64;
+            let y = LINE_PADDING_Y + plot_height - (p.value as f64 / max) * plot_height;
+            (x, y)
+        })
+        .collect();
+
+    let line = coords
+        .iter()
+        .map(|(x, y)| format!("{x:.1},{y:.1}"))
+        .collect::<Vec<_>>()
+        .join(" ");
+    let baseline = LINE_PADDING_Y + plot_height;
+    let area = match (coords.first(), coords.last()) {
+        (Some((first_x, _)), Some((last_x, _))) => {
+            format!("{first_x:.1},{baseline:.1} {line} {last_x:.1},{baseline:.1}")
+        }
+        _ => String::new(),
+    };
+
+    // Label the first, middle and last day to keep the axis readable
+    let axis_labels: Vec<(f64, String)> = [0, points.len() / 2, points.len().saturating_sub(1)]
+        .into_iter()
+        .filter(|i| *i < points.len())
+        .collect::<std::collections::BTreeSet<_>>()
+        .into_iter()
+        .map(|i| (coords[i].0, points[i].label.clone()))
+        .collect();
+
+    let markers = coords
+        .iter()
+        .zip(points.iter())
+        .map(|((x, y), p)| {
+            view! {
+                <circle class="chart-point" cx=format!("{x:.1}") cy=format!("{y:.1}") r="3">
+                    <title>{format!("{}: {}", p.label, p.value)}</title>
+                </circle>
+            }
+        })
+        .collect_view();
+
+    view! {
+        <svg
+            class="chart chart-line"
+            viewBox=format!("0 0 {LINE_WIDTH} {LINE_HEIGHT}")
+            role="img"
+            aria-label=label
+        >
+            <line
+                class="chart-axis"
+                x1=LINE_PADDING_X
+                y1=baseline
+                x2=LINE_WIDTH - LINE_PADDING_X
+                y2=baseline
+            />
+            <text class="chart-label" x="4" y=LINE_PADDING_Y + 4.0>{max as i64}</text>
+            <text class="chart-label" x="4" y=baseline>"0"</text>
+            <polygon class="chart-area" points=area/>
+            <polyline class="chart-stroke" points=line/>
+            {markers}
+            {axis_labels
+                .into_iter()
+                .map(|(x, text)| {
+                    view! {
+                        <text
+                            class="chart-label"
+                            x=format!("{x:.1}")
+                            y=LINE_HEIGHT - 4.0
+                            text-anchor="middle"
+                        >
+                            {text}
+                        </text>
+                    }
+                })
+                .collect_view()}
+        </svg>
+    }
+}
+
+/// Horizontal bar chart for ranked counts (referrers, devices, events...)
+#[component]
+pub fn BarChart(items: Vec<ChartPoint>, #[prop(into)] label: String) -> impl IntoView {
+    if items.is_empty() {
+        return view! { <p class="chart-empty">"No data for this period."</p> }.into_any();
+    }
+
+    let max = items.iter().map(|p| p.value).max().unwrap_or(0).max(1) as f64;
+    let bar_space = BAR_WIDTH - BAR_LABEL_WIDTH - BAR_VALUE_WIDTH;
+    let height = BAR_ROW_HEIGHT * items.len() as f64;
+
+    let rows = items
+        .into_iter()
+        .enumerate()
+        .map(|(i, item)| {
+            let y = BAR_ROW_HEIGHT * i as f64;
+            let width = (item.value as f64 / max * bar_space).max(2.0);
+            view! {
+                <g class="chart-bar-row">
+                    <text class="chart-label" x="0" y=y + BAR_ROW_HEIGHT * 0.65>
+                        {truncate_label(&item.label)}
+                        <title>{item.label.clone()}</title>
+                    </text>
+                    <rect
+                        class="chart-bar"
+                        x=BAR_LABEL_WIDTH
+                        y=y + 6.0
+                        width=format!("{width:.1}")
+                        height=BAR_ROW_HEIGHT - 12.0
+                        rx="3"
+                    />
+                    <text
+                        class="chart-value"
+                        x=format!("{:.1}", BAR_LABEL_WIDTH + width + 8.0)
+                        y=y + BAR_ROW_HEIGHT * 0.65
+                    >
+                        {item.value}
+                    </text>
+                </g>
+            }
+        })
+        .collect_view();
+
+    view! {
+        <svg
+            class="chart chart-bars"
+            viewBox=format!("0 0 {BAR_WIDTH} {height}")
+            role="img"
+            aria-label=label
+        >
+            {rows}
+        </svg>
+    }
+    .into_any()
+}
+
+/// Shorten long labels (paths, titles) so they fit the label column
+fn truncate_label(label: &str) -> String {
+    const MAX_CHARS: usize = 28;
+    if label.chars().count() > MAX_CHARS {
+        let short: String = label.chars().take(MAX_CHARS - 1).collect();
+        format!("{short}…")
+    } else {
+        label.to_string()
+    }
+}
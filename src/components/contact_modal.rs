@@ -1,22 +1,33 @@
 //! Contact form modal component
 //!
-//! Floating action button (FAB) that opens a modal contact form.
+//! Floating action button (FAB) that opens a modal contact form. Opening the
+//! modal fetches the signed time-trap token sent with the form, and solves
+//! its proof of work in the background while the visitor types.
 
 use leptos::prelude::*;
 
+use crate::analytics::use_track_event;
 use crate::i18n::use_i18n;
-use crate::server_fns::{submit_contact, ContactFormInput};
+#[cfg(feature = "hydrate")]
+use crate::server_fns::ContactChallenge;
+use crate::server_fns::{get_contact_token, submit_contact, ContactFormInput};
 
 /// Floating action button that opens the contact modal
 #[component]
 pub fn ContactFAB() -> impl IntoView {
     let is_open = RwSignal::new(false);
+    let tracker = use_track_event();
+
+    let open_modal = move |_| {
+        is_open.set(true);
+        tracker.track("contact_open", "contact", None);
+    };
 
     view! {
         // Floating Action Button
         <button
             class="contact-fab"
-            on:click=move |_| is_open.set(true)
+            on:click=open_modal
             aria-label="Open contact form"
         >
             <span class="fab-icon">"@"</span>
@@ -48,6 +59,36 @@ where
     let is_submitting = RwSignal::new(false);
     let result_message = RwSignal::new(Option::<(bool, String)>::None);
 
+    // Time-trap token, fetched when the modal opens and after each send
+    let token_action = Action::new(|_: &()| get_contact_token());
+    token_action.dispatch(());
+
+    // Token being solved, and (token, proof-of-work counter) once solved
+    let pow_token = RwSignal::new(Option::<String>::None);
+    let pow_solution = RwSignal::new(Option::<(String, u64)>::None);
+    // Submit pressed before the proof of work was done
+    let waiting_for_pow = RwSignal::new(false);
+
+    Effect::new(move || {
+        pow_solution.set(None);
+        let value = token_action.value().get();
+        pow_token.set(value.as_ref().and_then(|v| v.as_ref().ok()).map(|c| c.token.clone()));
+        match value {
+            Some(Ok(challenge)) if challenge.difficulty == 0 => {
+                pow_solution.set(Some((challenge.token, 0)));
+            }
+            Some(Ok(challenge)) => {
+                #[cfg(feature = "hydrate")]
+                solve_pow(challenge, 0, pow_token, pow_solution);
+                #[cfg(not(feature = "hydrate"))]
+                let _ = challenge;
+            }
+            // Let the server answer with "expired" so a new token is fetched
+            Some(Err(_)) => pow_solution.set(Some((String::new(), 0))),
+            None => {}
+        }
+    });
+
     // Create server action
     let submit_action = Action::new(move |input: &ContactFormInput| {
         let input = input.clone();
@@ -63,6 +104,11 @@ where
                 Ok(res) => {
                     let msg = get_message_for_key(&res.message_key, i18n_for_effect);
                     result_message.set(Some((res.success, msg)));
+                    // A sent or expired token cannot be reused
+                    if res.success || res.message_key == "contact_error_expired" {
+                        pow_solution.set(None);
+                        token_action.dispatch(());
+                    }
                     if res.success {
                         // Clear form on success
                         name.set(String::new());
@@ -76,23 +122,46 @@ where
                         false,
                         i18n_for_effect.t().contact_error_generic.to_string(),
                     )));
+                    // Send the retry with a fresh token
+                    pow_solution.set(None);
+                    token_action.dispatch(());
                 }
             }
         }
     });
 
+    let send = move || {
+        let (token, pow) = pow_solution.get_untracked().unwrap_or_default();
+        submit_action.dispatch(ContactFormInput {
+            name: name.get_untracked(),
+            email: email.get_untracked(),
+            subject: subject.get_untracked(),
+            message: message.get_untracked(),
+            website: honeypot.get_untracked(), // Honeypot
+            language: i18n.language.get_untracked(),
+            token,
+            pow,
+        });
+    };
+
+    // Send as soon as the proof of work finishes
+    Effect::new(move || {
+        if waiting_for_pow.get() && pow_solution.with(Option::is_some) {
+            waiting_for_pow.set(false);
+            send();
+        }
+    });
+
     let on_submit = move |ev: leptos::ev::SubmitEvent| {
         ev.prevent_default();
         is_submitting.set(true);
         result_message.set(None);
 
-        submit_action.dispatch(ContactFormInput {
-            name: name.get(),
-            email: email.get(),
-            subject: subject.get(),
-            message: message.get(),
-            website: honeypot.get(), // Honeypot
-        });
+        if pow_solution.with_untracked(Option::is_some) {
+            send();
+        } else {
+            waiting_for_pow.set(true);
+        }
     };
 
     // Close on escape key
@@ -105,6 +174,8 @@ where
 
     // Close on backdrop click (the backdrop closes, modal stops propagation)
     let on_close_backdrop = on_close.clone();
+    // Close when following the privacy link
+    let on_close_link = on_close.clone();
 
     view! {
         <div
@@ -214,12 +285,46 @@ where
                             }
                         }}
                     </button>
+
+                    <a class="contact-privacy-link" href="/privacy" on:click=move |_| on_close_link()>
+                        {move || i18n.t().contact_privacy_link}
+                    </a>
                 </form>
             </div>
         </div>
     }
 }
 
+/// Hashes tried between two yields to the browser
+#[cfg(feature = "hydrate")]
+const POW_SLICE: u64 = 5_000;
+
+/// Solve the proof of work in slices on the main thread, yielding between
+/// them so typing stays smooth. Stops when a newer token replaces this one
+/// or the modal closes.
+#[cfg(feature = "hydrate")]
+fn solve_pow(
+    challenge: ContactChallenge,
+    from: u64,
+    current: RwSignal<Option<String>>,
+    solution: RwSignal<Option<(String, u64)>>,
+) {
+    let is_current = current
+        .try_with_untracked(|token| token.as_deref() == Some(challenge.token.as_str()))
+        .unwrap_or(false);
+    if !is_current {
+        return;
+    }
+
+    match crate::pow::search(&challenge.token, challenge.difficulty, from, POW_SLICE) {
+        Some(counter) => solution.set(Some((challenge.token, counter))),
+        None => set_timeout(
+            move || solve_pow(challenge, from + POW_SLICE, current, solution),
+            std::time::Duration::ZERO,
+        ),
+    }
+}
+
 /// Helper to get translated message for a key
 fn get_message_for_key(key: &str, i18n: crate::i18n::I18nContext) -> String {
     match key {
@@ -229,6 +334,7 @@ fn get_message_for_key(key: &str, i18n: crate::i18n::I18nContext) -> String {
         "contact_error_message" => i18n.t().contact_error_message.to_string(),
         "contact_error_subject" => i18n.t().contact_error_subject.to_string(),
         "contact_error_rate_limit" => i18n.t().contact_error_rate_limit.to_string(),
+        "contact_error_expired" => i18n.t().contact_error_expired.to_string(),
         _ => i18n.t().contact_error_generic.to_string(),
     }
 }
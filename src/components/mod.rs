@@ -1,8 +1,10 @@
+mod charts;
 mod color_mode_toggle;
 mod contact_modal;
 mod language_toggle;
 mod theme_toggle;
 
+pub use charts::{BarChart, LineChart};
 pub use color_mode_toggle::ColorModeToggle;
 pub use contact_modal::ContactFAB;
 pub use language_toggle::LanguageToggle;
@@ -1,13 +1,20 @@
 use leptos::prelude::*;
+use crate::analytics::use_track_event;
 use crate::themes::use_theme;
 
 /// Theme toggle button component (cycles through Terminal -> Forge -> Bitart)
 #[component]
 pub fn ThemeToggle() -> impl IntoView {
     let theme_ctx = use_theme();
+    let tracker = use_track_event();
 
     let cycle_theme = move |_| {
         theme_ctx.theme.update(|t| *t = t.next());
+        tracker.track(
+            "theme_toggle",
+            "preferences",
+            Some(serde_json::json!({ "theme": theme_ctx.theme.get_untracked().code() })),
+        );
     };
 
     let label = move || theme_ctx.theme.get().label();
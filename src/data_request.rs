@@ -0,0 +1,250 @@
+//! Self-service access to contact submissions
+//!
+//! A visitor who used the contact form can ask for a link to download or
+//! erase what they sent. The link goes to the address they typed, so only
+//! its owner can use it; it is signed with `DATA_LINK_SECRET` and expires
+//! after `DATA_LINK_TTL_SECONDS`. In production the server refuses to start
+//! without the secret, or with a short or publicly known one.
+//!
+//! Submissions are found through `email_index`, a keyed hash of the
+//! lowercased sender address (see `crate::ip_hash`). The link carries the
+//! address's hash under every configured key, so it also finds rows indexed
+//! before a key rotation; `portfolio reencrypt-contacts` moves those to the
+//! current key.
+
+use std::sync::Arc;
+
+use argon2::password_hash::rand_core::{OsRng, RngCore};
+use hmac::{Hmac, Mac};
+use sha2::Sha256;
+use sqlx::PgPool;
+
+use crate::db::count_contacts_by_email_index;
+use crate::ip_hash::{check_secret, IpHasher};
+
+/// How long a data link can be used (seconds)
+pub const DATA_LINK_TTL_SECONDS: i64 = 60 * 60;
+
+/// Hash purpose of `contact_submissions.email_index`
+const EMAIL_INDEX_PURPOSE: &str = "contact_email";
+
+/// Current-key index of a sender address, stored with each submission
+pub fn email_index(hasher: &IpHasher, email: &str) -> String {
+    hasher.hash(EMAIL_INDEX_PURPOSE, &normalize_email(email))
+}
+
+/// Index of a sender address under every key, current first
+pub fn email_indexes(hasher: &IpHasher, email: &str) -> Vec<String> {
+    hasher.hashes(EMAIL_INDEX_PURPOSE, &normalize_email(email))
+}
+
+fn normalize_email(email: &str) -> String {
+    email.trim().to_lowercase()
+}
+
+/// Token of a data link for `email` issued at `now` (Unix seconds), or None
+/// when no submission was sent from it
+pub async fn data_link_token(
+    pool: &PgPool,
+    hasher: &IpHasher,
+    key: &DataLinkKey,
+    email: &str,
+    now: i64,
+) -> Result<Option<String>, sqlx::Error> {
+    let indexes = email_indexes(hasher, email);
+    if count_contacts_by_email_index(pool, &indexes).await? == 0 {
+        return Ok(None);
+    }
+    Ok(Some(key.issue(now, &indexes)))
+}
+
+/// Why a data link was refused
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LinkRejection {
+    Malformed,
+    BadSignature,
+    Expired,
+}
+
+/// A verified data link
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DataLink {
+    /// Unix seconds
+    pub issued_at: i64,
+    /// `email_index` values to look up
+    pub email_indexes: Vec<String>,
+}
+
+/// Signing key for data links, shared through the Leptos context
+#[derive(Clone)]
+pub struct DataLinkKey {
+    secret: Arc<[u8]>,
+}
+
+impl std::fmt::Debug for DataLinkKey {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("DataLinkKey(..)")
+    }
+}
+
+impl DataLinkKey {
+    pub fn new(secret: &[u8]) -> Self {
+        Self {
+            secret: secret.into(),
+        }
+    }
+
+    /// Load the key from `DATA_LINK_SECRET`. Outside production a random key
+    /// is generated when it is unset; in production it must be set, at least
+    /// `MIN_SECRET_BYTES` long and not one of the defaults from the source.
+    pub fn from_env(production: bool) -> Result<Self, String> {
+        match std::env::var("DATA_LINK_SECRET") {
+            Ok(secret) if !secret.is_empty() => {
+                if production {
+                    check_secret(secret.as_bytes()).map_err(|e| format!("secret is {e}"))?;
+                }
+                Ok(Self::new(secret.as_bytes()))
+            }
+            _ if production => Err("DATA_LINK_SECRET must be set in production".to_string()),
+            _ => {
+                tracing::warn!("DATA_LINK_SECRET not set - data links stop working on restart");
+                let mut secret = [0u8; 32];
+                OsRng.fill_bytes(&mut secret);
+                Ok(Self::new(&secret))
+            }
+        }
+    }
+
+    fn mac(&self, payload: &str) -> Hmac<Sha256> {
+        let mut mac =
+            Hmac::<Sha256>::new_from_slice(&self.secret).expect("HMAC accepts any key length");
+        mac.update(payload.as_bytes());
+        mac
+    }
+
+    /// Issue a token for `email_indexes` at `now` (Unix seconds)
+    pub fn issue(&self, now: i64, email_indexes: &[String]) -> String {
+        let payload = format!("{}.{}", now, email_indexes.join(","));
+        let tag = self.mac(&payload).finalize().into_bytes();
+        format!("{}.{}", payload, to_hex(&tag))
+    }
+
+    /// Check a token used at `now`
+    pub fn verify(&self, token: &str, now: i64) -> Result<DataLink, LinkRejection> {
+        let mut parts = token.trim().split('.');
+        let (Some(issued), Some(indexes), Some(tag), None) =
+            (parts.next(), parts.next(), parts.next(), parts.next())
+        else {
+            return Err(LinkRejection::Malformed);
+        };
+        let issued_at: i64 = issued.parse().map_err(|_| LinkRejection::Malformed)?;
+        let tag = from_hex(tag).ok_or(LinkRejection::Malformed)?;
+
+        // Constant-time comparison
+        self.mac(&format!("{}.{}", issued, indexes))
+            .verify_slice(&tag)
+            .map_err(|_| LinkRejection::BadSignature)?;
+
+        let email_indexes: Vec<String> = indexes.split(',').map(str::to_string).collect();
+        if email_indexes.iter().any(String::is_empty) {
+            return Err(LinkRejection::Malformed);
+        }
+        if now - issued_at > DATA_LINK_TTL_SECONDS || issued_at > now {
+            return Err(LinkRejection::Expired);
+        }
+        Ok(DataLink {
+            issued_at,
+            email_indexes,
+        })
+    }
+}
+
+fn to_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+fn from_hex(hex: &str) -> Option<Vec<u8>> {
+    if !hex.len().is_multiple_of(2) || !hex.is_ascii() {
+        return None;
+    }
+    (0..hex.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(&hex[i..i + 2], 16).ok())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn links_are_signed_and_expire() {
+        let key = DataLinkKey::new(b"test secret");
+        let hasher = IpHasher::parse("2:second-secret-value, 1:first-secret-value").unwrap();
+        let indexes = email_indexes(&hasher, " Ada@Example.com ");
+        assert_eq!(indexes[0], email_index(&hasher, "ada@example.com"));
+
+        let token = key.issue(1_000, &indexes);
+        let link = key.verify(&token, 1_000 + DATA_LINK_TTL_SECONDS).unwrap();
+        assert_eq!(link.issued_at, 1_000);
+        assert_eq!(link.email_indexes, indexes);
+
+        assert_eq!(
+            key.verify(&token, 1_001 + DATA_LINK_TTL_SECONDS),
+            Err(LinkRejection::Expired)
+        );
+        assert_eq!(
+            DataLinkKey::new(b"other secret").verify(&token, 1_000),
+            Err(LinkRejection::BadSignature)
+        );
+
+        // Swapping in another address's index breaks the signature
+        let other = email_index(&hasher, "eve@example.com");
+        let (_, tag) = token.rsplit_once('.').unwrap();
+        let forged = format!("1000.{other}.{tag}");
+        assert_eq!(key.verify(&forged, 1_000), Err(LinkRejection::BadSignature));
+        assert_eq!(key.verify("1000.abc", 1_000), Err(LinkRejection::Malformed));
+    }
+
+    #[sqlx::test]
+    async fn links_are_only_issued_to_senders(pool: PgPool) {
+        use crate::db::{insert_contact, ContactStatus, NewContactSubmission};
+        use crate::encryption::{ContactCipher, ContactPii};
+
+        let key = DataLinkKey::new(b"test secret");
+        let hasher = IpHasher::parse("1:first-secret-value").unwrap();
+        let cipher = ContactCipher::parse(&format!("test:{}", "11".repeat(32))).unwrap();
+        let id = uuid::Uuid::new_v4();
+        let pii = ContactPii {
+            name: "Ada".to_string(),
+            email: "ada@example.com".to_string(),
+            subject: None,
+            message: "Hello".to_string(),
+        };
+        let submission = NewContactSubmission {
+            id,
+            pii: cipher.seal(id, &pii),
+            email_index: email_index(&hasher, &pii.email),
+            honeypot_filled: false,
+            ip_hash: None,
+            user_agent: None,
+            status: ContactStatus::Pending,
+            spam_score: None,
+            spam_rules: None,
+            message_hash: None,
+        };
+        insert_contact(&pool, submission, &[], None, None)
+            .await
+            .unwrap();
+
+        let token = data_link_token(&pool, &hasher, &key, " Ada@Example.com", 1_000)
+            .await
+            .unwrap()
+            .expect("sender gets a link");
+        let link = key.verify(&token, 1_000).unwrap();
+        assert_eq!(link.email_indexes, email_indexes(&hasher, "ada@example.com"));
+
+        let stranger = data_link_token(&pool, &hasher, &key, "eve@example.com", 1_000).await;
+        assert_eq!(stranger.unwrap(), None);
+    }
+}
@@ -0,0 +1,483 @@
+//! Analytics database operations
+//!
+//! Stores page views and custom events (no raw IPs, no cookies), provides
+//! basic queries, and rolls raw rows up into daily aggregates before they
+//! are purged.
+
+use chrono::{DateTime, NaiveDate, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, PgPool};
+use uuid::Uuid;
+
+use super::{NewAnalyticsEvent, NewPageView, PageView};
+
+/// Raw analytics retention (days) when ANALYTICS_RETENTION_DAYS is not set
+pub const DEFAULT_ANALYTICS_RETENTION_DAYS: i64 = 30;
+
+/// View count for a single path
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct PathViewCount {
+    pub path: String,
+    pub views: i64,
+    pub sessions: i64,
+}
+
+/// Occurrence count for a single event name
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct EventCount {
+    pub event_name: String,
+    pub event_category: Option<String>,
+    pub occurrences: i64,
+}
+
+/// Total views for one day (from the daily aggregates)
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct DailyViews {
+    pub day: NaiveDate,
+    pub views: i64,
+}
+
+/// Aggregated totals for one path over a date range
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct PageRollup {
+    pub path: String,
+    pub views: i64,
+    pub time_samples: i64,
+    pub total_time_seconds: i64,
+    pub scroll_samples: i64,
+    pub total_scroll_percent: i64,
+}
+
+/// Count for one value of a rollup dimension
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct DimensionCount {
+    pub name: String,
+    pub count: i64,
+}
+
+/// Engagement sums over a date range (averages = total / samples)
+#[derive(Debug, Clone, Default, Serialize, Deserialize, FromRow)]
+pub struct EngagementTotals {
+    pub time_samples: i64,
+    pub total_time_seconds: i64,
+    pub scroll_samples: i64,
+    pub total_scroll_percent: i64,
+}
+
+/// Rollup dimensions that can be counted with `dimension_counts`
+#[derive(Debug, Clone, Copy)]
+pub enum RollupDimension {
+    Referrer,
+    Country,
+    DeviceType,
+    Browser,
+    OperatingSystem,
+    Event,
+}
+
+impl RollupDimension {
+    fn query(&self) -> &'static str {
+        match self {
+            RollupDimension::Referrer => {
+                r#"
+                SELECT referrer_domain AS name, SUM(views)::BIGINT AS count
+                FROM analytics_daily_referrers
+                WHERE day >= $1
+                GROUP BY 1 ORDER BY count DESC, name LIMIT $2
+                "#
+            }
+            RollupDimension::Country => {
+                r#"
+                SELECT country_code AS name, SUM(views)::BIGINT AS count
+                FROM analytics_daily_countries
+                WHERE day >= $1
+                GROUP BY 1 ORDER BY count DESC, name LIMIT $2
+                "#
+            }
+            RollupDimension::DeviceType => {
+                r#"
+                SELECT device_type AS name, SUM(views)::BIGINT AS count
+                FROM analytics_daily_devices
+                WHERE day >= $1
+                GROUP BY 1 ORDER BY count DESC, name LIMIT $2
+                "#
+            }
+            RollupDimension::Browser => {
+                r#"
+                SELECT browser_family AS name, SUM(views)::BIGINT AS count
+                FROM analytics_daily_devices
+                WHERE day >= $1
+                GROUP BY 1 ORDER BY count DESC, name LIMIT $2
+                "#
+            }
+            RollupDimension::OperatingSystem => {
+                r#"
+                SELECT os_family AS name, SUM(views)::BIGINT AS count
+                FROM analytics_daily_devices
+                WHERE day >= $1
+                GROUP BY 1 ORDER BY count DESC, name LIMIT $2
+                "#
+            }
+            RollupDimension::Event => {
+                r#"
+                SELECT event_name AS name, SUM(occurrences)::BIGINT AS count
+                FROM analytics_daily_events
+                WHERE day >= $1
+                GROUP BY 1 ORDER BY count DESC, name LIMIT $2
+                "#
+            }
+        }
+    }
+}
+
+/// Insert a new page view.
+/// Returns the UUID of the newly created record.
+pub async fn insert_page_view(pool: &PgPool, view: NewPageView) -> Result<Uuid, sqlx::Error> {
+    let row: (Uuid,) = sqlx::query_as(
+        r#"
+        INSERT INTO page_views
+            (path, title, session_hash, referrer, referrer_domain,
+             device_type, browser_family, os_family, country_code)
+        VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9)
+        RETURNING id
+        "#,
+    )
+    .bind(&view.path)
+    .bind(&view.title)
+    .bind(&view.session_hash)
+    .bind(&view.referrer)
+    .bind(&view.referrer_domain)
+    .bind(&view.device_type)
+    .bind(&view.browser_family)
+    .bind(&view.os_family)
+    .bind(&view.country_code)
+    .fetch_one(pool)
+    .await?;
+
+    Ok(row.0)
+}
+
+/// Store engagement for a page view.
+/// Only the visitor that created the view (same session hash) may update it,
+/// and only within a day. Values never decrease, so repeated beacons are safe.
+/// Returns true if a row was updated.
+pub async fn update_page_view_engagement(
+    pool: &PgPool,
+    id: Uuid,
+    session_hash: &str,
+    time_on_page_seconds: i32,
+    scroll_depth_percent: i32,
+) -> Result<bool, sqlx::Error> {
+    let result = sqlx::query(
+        r#"
+        UPDATE page_views
+        SET time_on_page_seconds = GREATEST(COALESCE(time_on_page_seconds, 0), $3),
+            scroll_depth_percent = GREATEST(COALESCE(scroll_depth_percent, 0), $4)
+        WHERE id = $1
+          AND session_hash = $2
+          AND viewed_at > NOW() - INTERVAL '1 day'
+        "#,
+    )
+    .bind(id)
+    .bind(session_hash)
+    .bind(time_on_page_seconds)
+    .bind(scroll_depth_percent)
+    .execute(pool)
+    .await?;
+
+    Ok(result.rows_affected() > 0)
+}
+
+/// Count page views recorded since the given instant
+pub async fn count_page_views_since(
+    pool: &PgPool,
+    since: DateTime<Utc>,
+) -> Result<i64, sqlx::Error> {
+    let row: (i64,) = sqlx::query_as(r#"SELECT COUNT(*) FROM page_views WHERE viewed_at >= $1"#)
+        .bind(since)
+        .fetch_one(pool)
+        .await?;
+
+    Ok(row.0)
+}
+
+/// Most viewed paths since the given instant
+pub async fn top_pages_since(
+    pool: &PgPool,
+    since: DateTime<Utc>,
+    limit: i64,
+) -> Result<Vec<PathViewCount>, sqlx::Error> {
+    sqlx::query_as(
+        r#"
+        SELECT path,
+               COUNT(*) AS views,
+               COUNT(DISTINCT session_hash) AS sessions
+        FROM page_views
+        WHERE viewed_at >= $1
+        GROUP BY path
+        ORDER BY views DESC, path
+        LIMIT $2
+        "#,
+    )
+    .bind(since)
+    .bind(limit)
+    .fetch_all(pool)
+    .await
+}
+
+/// Most recent page views (newest first)
+pub async fn recent_page_views(pool: &PgPool, limit: i64) -> Result<Vec<PageView>, sqlx::Error> {
+    sqlx::query_as(
+        r#"
+        SELECT id, path, title, session_hash, referrer, referrer_domain,
+               device_type, browser_family, os_family, country_code,
+               time_on_page_seconds, scroll_depth_percent, viewed_at
+        FROM page_views
+        ORDER BY viewed_at DESC
+        LIMIT $1
+        "#,
+    )
+    .bind(limit)
+    .fetch_all(pool)
+    .await
+}
+
+/// Insert a batch of analytics events in a single transaction.
+/// Returns the number of inserted rows.
+pub async fn insert_analytics_events(
+    pool: &PgPool,
+    events: Vec<NewAnalyticsEvent>,
+) -> Result<u64, sqlx::Error> {
+    let mut tx = pool.begin().await?;
+    let mut inserted = 0;
+
+    for event in events {
+        let result = sqlx::query(
+            r#"
+            INSERT INTO analytics_events
+                (event_name, event_category, event_data, page_path, session_hash)
+            VALUES ($1, $2, $3, $4, $5)
+            "#,
+        )
+        .bind(&event.event_name)
+        .bind(&event.event_category)
+        .bind(&event.event_data)
+        .bind(&event.page_path)
+        .bind(&event.session_hash)
+        .execute(&mut *tx)
+        .await?;
+        inserted += result.rows_affected();
+    }
+
+    tx.commit().await?;
+    Ok(inserted)
+}
+
+/// Event counts since the given instant, most frequent first
+pub async fn event_counts_since(
+    pool: &PgPool,
+    since: DateTime<Utc>,
+) -> Result<Vec<EventCount>, sqlx::Error> {
+    sqlx::query_as(
+        r#"
+        SELECT event_name, event_category, COUNT(*) AS occurrences
+        FROM analytics_events
+        WHERE occurred_at >= $1
+        GROUP BY event_name, event_category
+        ORDER BY occurrences DESC, event_name
+        "#,
+    )
+    .bind(since)
+    .fetch_all(pool)
+    .await
+}
+
+/// Recompute daily aggregates for every day from `since` (UTC) up to today.
+/// Aggregates are overwritten, so running this repeatedly is safe; only days
+/// whose raw rows are still complete should be passed.
+/// Returns the number of aggregate rows written.
+pub async fn rollup_analytics(pool: &PgPool, since: NaiveDate) -> Result<u64, sqlx::Error> {
+    let since = start_of_day(since);
+    let mut tx = pool.begin().await?;
+    let mut written = 0;
+
+    let rollups = [
+        r#"
+        INSERT INTO analytics_daily_pages
+            (day, path, views, sessions, time_samples, total_time_seconds,
+             scroll_samples, total_scroll_percent)
+        SELECT (viewed_at AT TIME ZONE 'UTC')::date,
+               path,
+               COUNT(*),
+               COUNT(DISTINCT session_hash),
+               COUNT(time_on_page_seconds),
+               COALESCE(SUM(time_on_page_seconds), 0),
+               COUNT(scroll_depth_percent),
+               COALESCE(SUM(scroll_depth_percent), 0)
+        FROM page_views
+        WHERE viewed_at >= $1
+        GROUP BY 1, 2
+        ON CONFLICT (day, path) DO UPDATE SET
+            views = EXCLUDED.views,
+            sessions = EXCLUDED.sessions,
+            time_samples = EXCLUDED.time_samples,
+            total_time_seconds = EXCLUDED.total_time_seconds,
+            scroll_samples = EXCLUDED.scroll_samples,
+            total_scroll_percent = EXCLUDED.total_scroll_percent
+        "#,
+        r#"
+        INSERT INTO analytics_daily_referrers (day, referrer_domain, views)
+        SELECT (viewed_at AT TIME ZONE 'UTC')::date, referrer_domain, COUNT(*)
+        FROM page_views
+        WHERE viewed_at >= $1 AND referrer_domain IS NOT NULL
+        GROUP BY 1, 2
+        ON CONFLICT (day, referrer_domain) DO UPDATE SET views = EXCLUDED.views
+        "#,
+        r#"
+        INSERT INTO analytics_daily_countries (day, country_code, views)
+        SELECT (viewed_at AT TIME ZONE 'UTC')::date, COALESCE(country_code, ''), COUNT(*)
+        FROM page_views
+        WHERE viewed_at >= $1
+        GROUP BY 1, 2
+        ON CONFLICT (day, country_code) DO UPDATE SET views = EXCLUDED.views
+        "#,
+        r#"
+        INSERT INTO analytics_daily_devices (day, device_type, browser_family, os_family, views)
+        SELECT (viewed_at AT TIME ZONE 'UTC')::date,
+               COALESCE(device_type, ''),
+               COALESCE(browser_family, ''),
+               COALESCE(os_family, ''),
+               COUNT(*)
+        FROM page_views
+        WHERE viewed_at >= $1
+        GROUP BY 1, 2, 3, 4
+        ON CONFLICT (day, device_type, browser_family, os_family)
+            DO UPDATE SET views = EXCLUDED.views
+        "#,
+        r#"
+        INSERT INTO analytics_daily_events (day, event_name, event_category, occurrences)
+        SELECT (occurred_at AT TIME ZONE 'UTC')::date,
+               event_name,
+               COALESCE(event_category, ''),
+               COUNT(*)
+        FROM analytics_events
+        WHERE occurred_at >= $1
+        GROUP BY 1, 2, 3
+        ON CONFLICT (day, event_name, event_category)
+            DO UPDATE SET occurrences = EXCLUDED.occurrences
+        "#,
+    ];
+
+    for rollup in rollups {
+        let result = sqlx::query(rollup).bind(since).execute(&mut *tx).await?;
+        written += result.rows_affected();
+    }
+
+    tx.commit().await?;
+    Ok(written)
+}
+
+/// Delete raw page views and events from before `before` (UTC day boundary).
+/// Call after `rollup_analytics` so nothing is lost from the aggregates.
+pub async fn cleanup_analytics(pool: &PgPool, before: NaiveDate) -> Result<u64, sqlx::Error> {
+    let cutoff = start_of_day(before);
+
+    let views = sqlx::query(r#"DELETE FROM page_views WHERE viewed_at < $1"#)
+        .bind(cutoff)
+        .execute(pool)
+        .await?;
+
+    let events = sqlx::query(r#"DELETE FROM analytics_events WHERE occurred_at < $1"#)
+        .bind(cutoff)
+        .execute(pool)
+        .await?;
+
+    Ok(views.rows_affected() + events.rows_affected())
+}
+
+/// Views per day since `since` (days without views are omitted)
+pub async fn daily_views_since(
+    pool: &PgPool,
+    since: NaiveDate,
+) -> Result<Vec<DailyViews>, sqlx::Error> {
+    sqlx::query_as(
+        r#"
+        SELECT day, SUM(views)::BIGINT AS views
+        FROM analytics_daily_pages
+        WHERE day >= $1
+        GROUP BY day
+        ORDER BY day
+        "#,
+    )
+    .bind(since)
+    .fetch_all(pool)
+    .await
+}
+
+/// Most viewed paths since `since`, optionally restricted to a path prefix
+pub async fn top_page_rollups_since(
+    pool: &PgPool,
+    since: NaiveDate,
+    path_prefix: Option<&str>,
+    limit: i64,
+) -> Result<Vec<PageRollup>, sqlx::Error> {
+    sqlx::query_as(
+        r#"
+        SELECT path,
+               SUM(views)::BIGINT AS views,
+               SUM(time_samples)::BIGINT AS time_samples,
+               SUM(total_time_seconds)::BIGINT AS total_time_seconds,
+               SUM(scroll_samples)::BIGINT AS scroll_samples,
+               SUM(total_scroll_percent)::BIGINT AS total_scroll_percent
+        FROM analytics_daily_pages
+        WHERE day >= $1 AND ($2::TEXT IS NULL OR starts_with(path, $2))
+        GROUP BY path
+        ORDER BY views DESC, path
+        LIMIT $3
+        "#,
+    )
+    .bind(since)
+    .bind(path_prefix)
+    .bind(limit)
+    .fetch_all(pool)
+    .await
+}
+
+/// Counts per value of a rollup dimension since `since`, largest first
+pub async fn dimension_counts_since(
+    pool: &PgPool,
+    dimension: RollupDimension,
+    since: NaiveDate,
+    limit: i64,
+) -> Result<Vec<DimensionCount>, sqlx::Error> {
+    sqlx::query_as(dimension.query())
+        .bind(since)
+        .bind(limit)
+        .fetch_all(pool)
+        .await
+}
+
+/// Site-wide engagement sums since `since`
+pub async fn engagement_totals_since(
+    pool: &PgPool,
+    since: NaiveDate,
+) -> Result<EngagementTotals, sqlx::Error> {
+    sqlx::query_as(
+        r#"
+        SELECT COALESCE(SUM(time_samples), 0)::BIGINT AS time_samples,
+               COALESCE(SUM(total_time_seconds), 0)::BIGINT AS total_time_seconds,
+               COALESCE(SUM(scroll_samples), 0)::BIGINT AS scroll_samples,
+               COALESCE(SUM(total_scroll_percent), 0)::BIGINT AS total_scroll_percent
+        FROM analytics_daily_pages
+        WHERE day >= $1
+        "#,
+    )
+    .bind(since)
+    .fetch_one(pool)
+    .await
+}
+
+/// Midnight UTC at the start of `day`
+fn start_of_day(day: NaiveDate) -> DateTime<Utc> {
+    day.and_hms_opt(0, 0, 0).unwrap_or_default().and_utc()
+}
@@ -0,0 +1,130 @@
+//! Admin session database operations
+
+use chrono::{DateTime, Utc};
+use sqlx::{FromRow, PgPool};
+use uuid::Uuid;
+
+/// Admin session record
+#[derive(Debug, Clone, FromRow)]
+pub struct AdminSession {
+    pub id: Uuid,
+    pub token_hash: String,
+    pub username: String,
+    pub ip_hash: Option<String>,
+    pub user_agent: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub last_seen_at: DateTime<Utc>,
+    pub expires_at: DateTime<Utc>,
+}
+
+/// New admin session (for INSERT)
+#[derive(Debug, Clone)]
+pub struct NewAdminSession {
+    pub token_hash: String,
+    pub username: String,
+    pub ip_hash: Option<String>,
+    pub user_agent: Option<String>,
+    pub expires_at: DateTime<Utc>,
+}
+
+/// Store a new session.
+pub async fn insert_admin_session(
+    pool: &PgPool,
+    session: NewAdminSession,
+) -> Result<Uuid, sqlx::Error> {
+    let row: (Uuid,) = sqlx::query_as(
+        r#"
+        INSERT INTO admin_sessions (token_hash, username, ip_hash, user_agent, expires_at)
+        VALUES ($1, $2, $3, $4, $5)
+        RETURNING id
+        "#,
+    )
+    .bind(&session.token_hash)
+    .bind(&session.username)
+    .bind(&session.ip_hash)
+    .bind(&session.user_agent)
+    .bind(session.expires_at)
+    .fetch_one(pool)
+    .await?;
+
+    Ok(row.0)
+}
+
+/// Look up an unexpired session by token hash and record activity.
+pub async fn touch_admin_session(
+    pool: &PgPool,
+    token_hash: &str,
+) -> Result<Option<AdminSession>, sqlx::Error> {
+    sqlx::query_as(
+        r#"
+        UPDATE admin_sessions
+        SET last_seen_at = NOW()
+        WHERE token_hash = $1 AND expires_at > NOW()
+        RETURNING *
+        "#,
+    )
+    .bind(token_hash)
+    .fetch_optional(pool)
+    .await
+}
+
+/// Delete a session (logout).
+pub async fn delete_admin_session(pool: &PgPool, token_hash: &str) -> Result<bool, sqlx::Error> {
+    let result = sqlx::query(r#"DELETE FROM admin_sessions WHERE token_hash = $1"#)
+        .bind(token_hash)
+        .execute(pool)
+        .await?;
+
+    Ok(result.rows_affected() > 0)
+}
+
+/// Delete expired sessions.
+pub async fn cleanup_admin_sessions(pool: &PgPool) -> Result<u64, sqlx::Error> {
+    let result = sqlx::query(r#"DELETE FROM admin_sessions WHERE expires_at <= NOW()"#)
+        .execute(pool)
+        .await?;
+
+    Ok(result.rows_affected())
+}
+
+/// Record `step` as the last TOTP step used by `username`.
+/// Returns false if that step or a later one was already used, so each
+/// code logs in once.
+pub async fn claim_totp_step(
+    pool: &PgPool,
+    username: &str,
+    step: i64,
+) -> Result<bool, sqlx::Error> {
+    // Single upsert, so two logins with the same code cannot both pass
+    let claimed: Option<(i64,)> = sqlx::query_as(
+        r#"
+        INSERT INTO admin_totp_steps (username, last_step)
+        VALUES ($1, $2)
+        ON CONFLICT (username) DO UPDATE
+        SET last_step = EXCLUDED.last_step, used_at = NOW()
+        WHERE admin_totp_steps.last_step < EXCLUDED.last_step
+        RETURNING last_step
+        "#,
+    )
+    .bind(username)
+    .bind(step)
+    .fetch_optional(pool)
+    .await?;
+
+    Ok(claimed.is_some())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[sqlx::test]
+    async fn totp_steps_are_used_once(pool: PgPool) {
+        assert!(claim_totp_step(&pool, "admin", 100).await.unwrap());
+        assert!(!claim_totp_step(&pool, "admin", 100).await.unwrap());
+        // An earlier code still inside the skew window
+        assert!(!claim_totp_step(&pool, "admin", 99).await.unwrap());
+        assert!(claim_totp_step(&pool, "admin", 101).await.unwrap());
+        assert!(claim_totp_step(&pool, "other", 100).await.unwrap());
+    }
+}
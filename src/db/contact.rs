@@ -1,139 +1,755 @@
 //! Contact form database operations
 //!
-//! Handles rate limiting and contact submission storage.
+//! Handles contact submission storage, form nonces, the admin inbox and
+//! visitors' own export and erasure requests.
+//! Personal data is stored sealed (see `crate::encryption`), so nothing here
+//! can filter on it, apart from the keyed hash of the sender address.
 
-use chrono::{Duration, Utc};
-use sqlx::PgPool;
+use sqlx::{PgConnection, PgPool};
 use uuid::Uuid;
 
-use super::NewContactSubmission;
+use super::{
+    enqueue_outbox_emails, ContactErasure, ContactReply, ContactStatus, ContactSubmission,
+    NewAcknowledgement, NewContactReply, NewContactSubmission, NewOutboxEmail,
+};
+use crate::encryption::SealedPii;
 
-/// Rate limit configuration
-pub const RATE_LIMIT_WINDOW_MINUTES: i64 = 60;
-pub const RATE_LIMIT_MAX_ATTEMPTS: i32 = 3;
-
-/// Rate limit record from database
-#[derive(Debug, Clone, sqlx::FromRow)]
-pub struct ContactRateLimit {
-    pub id: Uuid,
-    pub ip_hash: String,
-    pub attempt_count: i32,
-    pub window_start: chrono::DateTime<Utc>,
-    pub last_attempt: chrono::DateTime<Utc>,
-}
+/// Insert a new contact submission into the database, together with the
+/// emails to deliver for it (in one transaction, so none can be lost, and
+/// the form nonce and acknowledgement slot are only used up when the
+/// submission is stored).
+/// Returns the UUID of the newly created record and whether the
+/// acknowledgement was queued, or None if `form_nonce` was already used.
+pub async fn insert_contact(
+    pool: &PgPool,
+    submission: NewContactSubmission,
+    emails: &[NewOutboxEmail],
+    acknowledgement: Option<&NewAcknowledgement>,
+    form_nonce: Option<&str>,
+) -> Result<Option<(Uuid, bool)>, sqlx::Error> {
+    let mut tx = pool.begin().await?;
 
-/// Check and update rate limit for an IP hash.
-/// Returns Ok(true) if the request is allowed, Ok(false) if rate limited.
-pub async fn check_rate_limit(pool: &PgPool, ip_hash: &str) -> Result<bool, sqlx::Error> {
-    let window_cutoff = Utc::now() - Duration::minutes(RATE_LIMIT_WINDOW_MINUTES);
+    if let Some(nonce) = form_nonce {
+        if !consume_form_nonce(&mut tx, nonce).await? {
+            return Ok(None);
+        }
+    }
 
-    // Try to get existing rate limit record
-    let existing: Option<ContactRateLimit> = sqlx::query_as(
+    let row: (Uuid,) = sqlx::query_as(
         r#"
-        SELECT id, ip_hash, attempt_count, window_start, last_attempt
-        FROM contact_rate_limits
-        WHERE ip_hash = $1
+        INSERT INTO contact_submissions
+            (id, key_id, wrapped_dek, sealed_pii, email_index, honeypot_filled, ip_hash,
+             user_agent, status, spam_score, spam_rules, message_hash)
+        VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12)
+        RETURNING id
         "#,
     )
-    .bind(ip_hash)
-    .fetch_optional(pool)
+    .bind(submission.id)
+    .bind(&submission.pii.key_id)
+    .bind(&submission.pii.wrapped_dek)
+    .bind(&submission.pii.ciphertext)
+    .bind(&submission.email_index)
+    .bind(submission.honeypot_filled)
+    .bind(&submission.ip_hash)
+    .bind(&submission.user_agent)
+    .bind(submission.status.as_str())
+    .bind(submission.spam_score)
+    .bind(&submission.spam_rules)
+    .bind(&submission.message_hash)
+    .fetch_one(&mut *tx)
     .await?;
 
-    match existing {
-        Some(record) => {
-            if record.window_start < window_cutoff {
-                // Window has expired - reset the counter
-                sqlx::query(
-                    r#"
-                    UPDATE contact_rate_limits
-                    SET attempt_count = 1, window_start = NOW(), last_attempt = NOW()
-                    WHERE ip_hash = $1
-                    "#,
-                )
-                .bind(ip_hash)
-                .execute(pool)
-                .await?;
-                Ok(true)
-            } else if record.attempt_count >= RATE_LIMIT_MAX_ATTEMPTS {
-                // Rate limited - update last_attempt but don't increment
-                sqlx::query(
-                    r#"
-                    UPDATE contact_rate_limits
-                    SET last_attempt = NOW()
-                    WHERE ip_hash = $1
-                    "#,
-                )
-                .bind(ip_hash)
-                .execute(pool)
-                .await?;
-                Ok(false)
-            } else {
-                // Allowed - increment counter
-                sqlx::query(
-                    r#"
-                    UPDATE contact_rate_limits
-                    SET attempt_count = attempt_count + 1, last_attempt = NOW()
-                    WHERE ip_hash = $1
-                    "#,
-                )
-                .bind(ip_hash)
-                .execute(pool)
-                .await?;
-                Ok(true)
-            }
-        }
-        None => {
-            // First attempt - insert new record
-            sqlx::query(
-                r#"
-                INSERT INTO contact_rate_limits (ip_hash, attempt_count, window_start, last_attempt)
-                VALUES ($1, 1, NOW(), NOW())
-                "#,
-            )
-            .bind(ip_hash)
-            .execute(pool)
-            .await?;
-            Ok(true)
+    enqueue_outbox_emails(&mut tx, row.0, emails).await?;
+
+    let mut acknowledged = false;
+    if let Some(ack) = acknowledgement {
+        if claim_acknowledgement_slot(&mut tx, &ack.recipient_hashes).await? {
+            enqueue_outbox_emails(&mut tx, row.0, std::slice::from_ref(&ack.email)).await?;
+            acknowledged = true;
         }
     }
+
+    tx.commit().await?;
+    Ok(Some((row.0, acknowledged)))
 }
 
-/// Insert a new contact submission into the database.
-/// Returns the UUID of the newly created record.
-pub async fn insert_contact(
+/// Submissions with the same message in the last `days` days, given its
+/// `message_hash` under each hash key (see `crate::spam::message_hashes`).
+pub async fn count_duplicate_messages(
     pool: &PgPool,
-    submission: NewContactSubmission,
-) -> Result<Uuid, sqlx::Error> {
-    let row: (Uuid,) = sqlx::query_as(
+    message_hashes: &[String],
+    days: i32,
+) -> Result<i64, sqlx::Error> {
+    let row: (i64,) = sqlx::query_as(
         r#"
-        INSERT INTO contact_submissions
-            (name, email, subject, message, honeypot_filled, ip_hash, user_agent, status)
-        VALUES ($1, $2, $3, $4, $5, $6, $7, 'pending')
-        RETURNING id
+        SELECT COUNT(*) FROM contact_submissions
+        WHERE message_hash = ANY($1) AND created_at > NOW() - make_interval(days => $2)
         "#,
     )
-    .bind(&submission.name)
-    .bind(&submission.email)
-    .bind(&submission.subject)
-    .bind(&submission.message)
-    .bind(submission.honeypot_filled)
-    .bind(&submission.ip_hash)
-    .bind(&submission.user_agent)
+    .bind(message_hashes)
+    .bind(days)
     .fetch_one(pool)
     .await?;
 
     Ok(row.0)
 }
 
-/// Clean up old rate limit records (older than 24 hours).
-/// Call this periodically to prevent table bloat.
-pub async fn cleanup_rate_limits(pool: &PgPool) -> Result<u64, sqlx::Error> {
-    let cutoff = Utc::now() - Duration::hours(24);
+/// Admin inbox filter.
+/// Honeypot-flagged rows only appear when `quarantined` is set. Text search
+/// happens after decryption, in the admin inbox.
+#[derive(Debug, Clone, Default)]
+pub struct ContactFilter {
+    pub status: Option<ContactStatus>,
+    pub quarantined: bool,
+}
+
+/// Number of submissions per status (non-quarantined), plus the quarantine size
+#[derive(Debug, Clone, Default)]
+pub struct ContactCounts {
+    pub pending: i64,
+    pub read: i64,
+    pub replied: i64,
+    pub spam: i64,
+    pub quarantined: i64,
+}
+
+const CONTACT_FILTER_SQL: &str = r#"
+    COALESCE(honeypot_filled, FALSE) = $1
+    AND ($2::VARCHAR IS NULL OR status = $2)
+"#;
+
+/// List submissions matching `filter`, newest first.
+pub async fn list_contacts(
+    pool: &PgPool,
+    filter: &ContactFilter,
+    limit: i64,
+    offset: i64,
+) -> Result<Vec<ContactSubmission>, sqlx::Error> {
+    let query = format!(
+        "SELECT * FROM contact_submissions WHERE {} ORDER BY created_at DESC LIMIT $3 OFFSET $4",
+        CONTACT_FILTER_SQL
+    );
+    sqlx::query_as(&query)
+        .bind(filter.quarantined)
+        .bind(filter.status.as_ref().map(ContactStatus::as_str))
+        .bind(limit)
+        .bind(offset)
+        .fetch_all(pool)
+        .await
+}
+
+/// Count submissions matching `filter`.
+pub async fn count_contacts(pool: &PgPool, filter: &ContactFilter) -> Result<i64, sqlx::Error> {
+    let query = format!(
+        "SELECT COUNT(*) FROM contact_submissions WHERE {}",
+        CONTACT_FILTER_SQL
+    );
+    let row: (i64,) = sqlx::query_as(&query)
+        .bind(filter.quarantined)
+        .bind(filter.status.as_ref().map(ContactStatus::as_str))
+        .fetch_one(pool)
+        .await?;
+
+    Ok(row.0)
+}
+
+/// Count submissions matching `filter` received more than `days` days ago.
+pub async fn count_expired_contacts(
+    pool: &PgPool,
+    filter: &ContactFilter,
+    days: i64,
+) -> Result<i64, sqlx::Error> {
+    let query = format!(
+        r#"
+        SELECT COUNT(*) FROM contact_submissions
+        WHERE {} AND created_at < NOW() - make_interval(days => $3)
+        "#,
+        CONTACT_FILTER_SQL
+    );
+    let row: (i64,) = sqlx::query_as(&query)
+        .bind(filter.quarantined)
+        .bind(filter.status.as_ref().map(ContactStatus::as_str))
+        .bind(days as i32)
+        .fetch_one(pool)
+        .await?;
+
+    Ok(row.0)
+}
 
-    let result = sqlx::query(r#"DELETE FROM contact_rate_limits WHERE window_start < $1"#)
-        .bind(cutoff)
+/// Delete submissions matching `filter` received more than `days` days ago
+/// (replies and queued emails go with them).
+pub async fn purge_expired_contacts(
+    pool: &PgPool,
+    filter: &ContactFilter,
+    days: i64,
+) -> Result<u64, sqlx::Error> {
+    let query = format!(
+        r#"
+        DELETE FROM contact_submissions
+        WHERE {} AND created_at < NOW() - make_interval(days => $3)
+        "#,
+        CONTACT_FILTER_SQL
+    );
+    let result = sqlx::query(&query)
+        .bind(filter.quarantined)
+        .bind(filter.status.as_ref().map(ContactStatus::as_str))
+        .bind(days as i32)
         .execute(pool)
         .await?;
 
     Ok(result.rows_affected())
 }
+
+/// Submission counts for the inbox folders.
+pub async fn contact_counts(pool: &PgPool) -> Result<ContactCounts, sqlx::Error> {
+    let rows: Vec<(bool, String, i64)> = sqlx::query_as(
+        r#"
+        SELECT COALESCE(honeypot_filled, FALSE), status, COUNT(*)
+        FROM contact_submissions
+        GROUP BY 1, 2
+        "#,
+    )
+    .fetch_all(pool)
+    .await?;
+
+    let mut counts = ContactCounts::default();
+    for (quarantined, status, count) in rows {
+        if quarantined {
+            counts.quarantined += count;
+            continue;
+        }
+        match ContactStatus::parse(&status) {
+            Some(ContactStatus::Pending) => counts.pending += count,
+            Some(ContactStatus::Read) => counts.read += count,
+            Some(ContactStatus::Replied) => counts.replied += count,
+            Some(ContactStatus::Spam) => counts.spam += count,
+            None => {}
+        }
+    }
+
+    Ok(counts)
+}
+
+/// Get a single submission by ID.
+pub async fn get_contact(pool: &PgPool, id: Uuid) -> Result<Option<ContactSubmission>, sqlx::Error> {
+    sqlx::query_as(r#"SELECT * FROM contact_submissions WHERE id = $1"#)
+        .bind(id)
+        .fetch_optional(pool)
+        .await
+}
+
+/// Change a submission's status and keep `read_at` / `replied_at` in step:
+/// read and replied set `read_at` (replied also sets `replied_at`) if not
+/// already set, and going back to pending clears `read_at`.
+/// Returns the updated row, or None if the ID does not exist.
+pub async fn update_contact_status(
+    pool: &PgPool,
+    id: Uuid,
+    status: ContactStatus,
+) -> Result<Option<ContactSubmission>, sqlx::Error> {
+    sqlx::query_as(
+        r#"
+        UPDATE contact_submissions
+        SET status = $2,
+            read_at = CASE
+                WHEN $2 IN ('read', 'replied') THEN COALESCE(read_at, NOW())
+                WHEN $2 = 'pending' THEN NULL
+                ELSE read_at
+            END,
+            replied_at = CASE
+                WHEN $2 = 'replied' THEN COALESCE(replied_at, NOW())
+                ELSE replied_at
+            END
+        WHERE id = $1
+        RETURNING *
+        "#,
+    )
+    .bind(id)
+    .bind(status.as_str())
+    .fetch_optional(pool)
+    .await
+}
+
+// $1: current key ID, $2: index prefix of the current hash key
+const REENCRYPT_FILTER_SQL: &str = r#"
+    (key_id IS DISTINCT FROM $1 OR email_index IS NULL OR email_index NOT LIKE $2
+     OR EXISTS (
+        SELECT 1 FROM contact_replies r
+        WHERE r.submission_id = contact_submissions.id AND r.sealed_content IS NULL
+     ))
+"#;
+
+/// Submissions not yet sealed under `current_key_id` (plaintext rows, or
+/// rows under an older key), whose email index is missing or not under
+/// the hash key version `index_version`, or with plaintext replies, in ID
+/// order after `after`.
+pub async fn list_contacts_to_reencrypt(
+    pool: &PgPool,
+    current_key_id: &str,
+    index_version: u32,
+    after: Uuid,
+    limit: i64,
+) -> Result<Vec<ContactSubmission>, sqlx::Error> {
+    let query = format!(
+        "SELECT * FROM contact_submissions WHERE {} AND id > $3 ORDER BY id LIMIT $4",
+        REENCRYPT_FILTER_SQL
+    );
+    sqlx::query_as(&query)
+        .bind(current_key_id)
+        .bind(format!("v{index_version}:%"))
+        .bind(after)
+        .bind(limit)
+        .fetch_all(pool)
+        .await
+}
+
+/// Number of submissions `list_contacts_to_reencrypt` would return.
+pub async fn count_contacts_to_reencrypt(
+    pool: &PgPool,
+    current_key_id: &str,
+    index_version: u32,
+) -> Result<i64, sqlx::Error> {
+    let query = format!(
+        "SELECT COUNT(*) FROM contact_submissions WHERE {}",
+        REENCRYPT_FILTER_SQL
+    );
+    sqlx::query_scalar(&query)
+        .bind(current_key_id)
+        .bind(format!("v{index_version}:%"))
+        .fetch_one(pool)
+        .await
+}
+
+/// Store a submission's sealed data and email index, and clear its
+/// plaintext columns.
+pub async fn store_sealed_contact(
+    pool: &PgPool,
+    id: Uuid,
+    pii: &SealedPii,
+    email_index: &str,
+) -> Result<(), sqlx::Error> {
+    sqlx::query(
+        r#"
+        UPDATE contact_submissions
+        SET key_id = $2, wrapped_dek = $3, sealed_pii = $4, email_index = $5,
+            name = NULL, email = NULL, subject = NULL, message = NULL
+        WHERE id = $1
+        "#,
+    )
+    .bind(id)
+    .bind(&pii.key_id)
+    .bind(&pii.wrapped_dek)
+    .bind(&pii.ciphertext)
+    .bind(email_index)
+    .execute(pool)
+    .await?;
+
+    Ok(())
+}
+
+/// Submissions sent from an address, given its `email_index` under each
+/// hash key, oldest first.
+pub async fn list_contacts_by_email_index(
+    pool: &PgPool,
+    email_indexes: &[String],
+) -> Result<Vec<ContactSubmission>, sqlx::Error> {
+    sqlx::query_as(
+        r#"
+        SELECT * FROM contact_submissions
+        WHERE email_index = ANY($1)
+        ORDER BY created_at
+        "#,
+    )
+    .bind(email_indexes)
+    .fetch_all(pool)
+    .await
+}
+
+/// Number of submissions sent from an address.
+pub async fn count_contacts_by_email_index(
+    pool: &PgPool,
+    email_indexes: &[String],
+) -> Result<i64, sqlx::Error> {
+    sqlx::query_scalar(r#"SELECT COUNT(*) FROM contact_submissions WHERE email_index = ANY($1)"#)
+        .bind(email_indexes)
+        .fetch_one(pool)
+        .await
+}
+
+/// Delete every submission sent from an address (replies and queued
+/// emails go with them) and record the erasure. Returns None when there was
+/// nothing to delete, in which case nothing is recorded.
+pub async fn erase_contacts_by_email_index(
+    pool: &PgPool,
+    email_indexes: &[String],
+    link_issued_at: chrono::DateTime<chrono::Utc>,
+) -> Result<Option<ContactErasure>, sqlx::Error> {
+    let mut tx = pool.begin().await?;
+
+    let reply_count: i64 = sqlx::query_scalar(
+        r#"
+        SELECT COUNT(*) FROM contact_replies r
+        JOIN contact_submissions s ON s.id = r.submission_id
+        WHERE s.email_index = ANY($1)
+        "#,
+    )
+    .bind(email_indexes)
+    .fetch_one(&mut *tx)
+    .await?;
+
+    let deleted = sqlx::query(r#"DELETE FROM contact_submissions WHERE email_index = ANY($1)"#)
+        .bind(email_indexes)
+        .execute(&mut *tx)
+        .await?
+        .rows_affected();
+    if deleted == 0 {
+        return Ok(None);
+    }
+
+    let erasure: ContactErasure = sqlx::query_as(
+        r#"
+        INSERT INTO contact_erasures (submission_count, reply_count, link_issued_at)
+        VALUES ($1, $2, $3)
+        RETURNING *
+        "#,
+    )
+    .bind(deleted as i32)
+    .bind(reply_count as i32)
+    .bind(link_issued_at)
+    .fetch_one(&mut *tx)
+    .await?;
+
+    tx.commit().await?;
+    Ok(Some(erasure))
+}
+
+/// Hours before the same address can receive another acknowledgement
+pub const ACK_THROTTLE_HOURS: i64 = 24;
+
+/// Claim the acknowledgement slot for a recipient, inside the caller's
+/// transaction. `recipient_hashes` is the address under each configured
+/// key, current first; the slot is recorded under the current one.
+/// Returns Ok(true) if an acknowledgement may be sent now (and records it),
+/// Ok(false) if one was already sent within `ACK_THROTTLE_HOURS`.
+async fn claim_acknowledgement_slot(
+    conn: &mut PgConnection,
+    recipient_hashes: &[String],
+) -> Result<bool, sqlx::Error> {
+    // Single upsert, so concurrent submissions cannot both claim the slot.
+    // Acknowledgements recorded under a previous key still count.
+    let claimed: Option<(String,)> = sqlx::query_as(
+        r#"
+        INSERT INTO contact_acknowledgements (recipient_hash, sent_count, last_sent_at)
+        SELECT $1[1], 1, NOW()
+        WHERE NOT EXISTS (
+            SELECT 1 FROM contact_acknowledgements
+            WHERE recipient_hash = ANY($1[2:])
+              AND last_sent_at >= NOW() - make_interval(hours => $2)
+        )
+        ON CONFLICT (recipient_hash) DO UPDATE
+        SET sent_count = contact_acknowledgements.sent_count + 1,
+            last_sent_at = NOW()
+        WHERE contact_acknowledgements.last_sent_at < NOW() - make_interval(hours => $2)
+        RETURNING recipient_hash
+        "#,
+    )
+    .bind(recipient_hashes)
+    .bind(ACK_THROTTLE_HOURS as i32)
+    .fetch_optional(conn)
+    .await?;
+
+    Ok(claimed.is_some())
+}
+
+/// Delete acknowledgement throttle records past the throttle window.
+pub async fn cleanup_acknowledgement_throttle(pool: &PgPool) -> Result<u64, sqlx::Error> {
+    let result = sqlx::query(
+        r#"
+        DELETE FROM contact_acknowledgements
+        WHERE last_sent_at < NOW() - make_interval(hours => $1)
+        "#,
+    )
+    .bind(ACK_THROTTLE_HOURS as i32)
+    .execute(pool)
+    .await?;
+
+    Ok(result.rows_affected())
+}
+
+/// Record a form token nonce as used, inside the caller's transaction.
+/// Returns Ok(false) if it was already consumed (a replayed token).
+async fn consume_form_nonce(conn: &mut PgConnection, nonce: &str) -> Result<bool, sqlx::Error> {
+    let consumed: Option<(String,)> = sqlx::query_as(
+        r#"
+        INSERT INTO contact_form_nonces (nonce)
+        VALUES ($1)
+        ON CONFLICT (nonce) DO NOTHING
+        RETURNING nonce
+        "#,
+    )
+    .bind(nonce)
+    .fetch_optional(conn)
+    .await?;
+
+    Ok(consumed.is_some())
+}
+
+/// Delete nonces whose tokens have expired anyway.
+pub async fn cleanup_form_nonces(pool: &PgPool) -> Result<u64, sqlx::Error> {
+    let result = sqlx::query(
+        r#"
+        DELETE FROM contact_form_nonces
+        WHERE consumed_at < NOW() - make_interval(secs => $1)
+        "#,
+    )
+    .bind(crate::spam::MAX_TOKEN_AGE_SECONDS as f64)
+    .execute(pool)
+    .await?;
+
+    Ok(result.rows_affected())
+}
+
+/// Store a sent reply and mark its submission replied (`replied_at` and
+/// `read_at` are set if not already).
+pub async fn insert_contact_reply(
+    pool: &PgPool,
+    reply: NewContactReply,
+) -> Result<ContactReply, sqlx::Error> {
+    let mut tx = pool.begin().await?;
+
+    let stored: ContactReply = sqlx::query_as(
+        r#"
+        INSERT INTO contact_replies (id, submission_id, sealed_content, message_id, sent_by)
+        VALUES ($1, $2, $3, $4, $5)
+        RETURNING *
+        "#,
+    )
+    .bind(reply.id)
+    .bind(reply.submission_id)
+    .bind(&reply.sealed_content)
+    .bind(&reply.message_id)
+    .bind(&reply.sent_by)
+    .fetch_one(&mut *tx)
+    .await?;
+
+    sqlx::query(
+        r#"
+        UPDATE contact_submissions
+        SET status = 'replied',
+            read_at = COALESCE(read_at, NOW()),
+            replied_at = COALESCE(replied_at, NOW())
+        WHERE id = $1
+        "#,
+    )
+    .bind(reply.submission_id)
+    .execute(&mut *tx)
+    .await?;
+
+    tx.commit().await?;
+    Ok(stored)
+}
+
+/// Replies sent to a submission, oldest first.
+pub async fn list_contact_replies(
+    pool: &PgPool,
+    submission_id: Uuid,
+) -> Result<Vec<ContactReply>, sqlx::Error> {
+    sqlx::query_as(
+        r#"
+        SELECT * FROM contact_replies
+        WHERE submission_id = $1
+        ORDER BY sent_at
+        "#,
+    )
+    .bind(submission_id)
+    .fetch_all(pool)
+    .await
+}
+
+/// Store a reply's sealed content and clear its plaintext columns.
+pub async fn store_sealed_reply(
+    pool: &PgPool,
+    id: Uuid,
+    sealed_content: &[u8],
+) -> Result<(), sqlx::Error> {
+    sqlx::query(
+        r#"
+        UPDATE contact_replies
+        SET sealed_content = $2, subject = NULL, body = NULL
+        WHERE id = $1
+        "#,
+    )
+    .bind(id)
+    .bind(sealed_content)
+    .execute(pool)
+    .await?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::{list_submission_outbox_emails, OutboxKind};
+    use crate::encryption::SealedPii;
+
+    fn submission(id: Uuid) -> NewContactSubmission {
+        NewContactSubmission {
+            id,
+            // Never opened here
+            pii: SealedPii {
+                key_id: "test".to_string(),
+                wrapped_dek: vec![0],
+                ciphertext: vec![0],
+            },
+            email_index: "v1:index".to_string(),
+            honeypot_filled: false,
+            ip_hash: None,
+            user_agent: None,
+            status: ContactStatus::Pending,
+            spam_score: None,
+            spam_rules: None,
+            message_hash: None,
+        }
+    }
+
+    fn acknowledgement() -> NewAcknowledgement {
+        NewAcknowledgement {
+            recipient_hashes: vec!["v2:recipient".to_string(), "v1:recipient".to_string()],
+            email: NewOutboxEmail {
+                kind: OutboxKind::ContactAcknowledgement,
+                language: "en".to_string(),
+            },
+        }
+    }
+
+    async fn queued_kinds(pool: &PgPool, id: Uuid) -> Vec<String> {
+        let emails = list_submission_outbox_emails(pool, id).await.unwrap();
+        emails.into_iter().map(|email| email.kind).collect()
+    }
+
+    #[sqlx::test]
+    async fn acknowledgements_are_claimed_with_the_submission(pool: PgPool) {
+        let ack = acknowledgement();
+        let notification = NewOutboxEmail {
+            kind: OutboxKind::ContactNotification,
+            language: "en".to_string(),
+        };
+
+        // A failed insert does not use up the slot
+        let first = Uuid::new_v4();
+        insert_contact(&pool, submission(first), &[], None, None)
+            .await
+            .unwrap();
+        let duplicate = insert_contact(&pool, submission(first), &[], Some(&ack), None).await;
+        assert!(duplicate.is_err());
+
+        let (id, acknowledged) = insert_contact(
+            &pool,
+            submission(Uuid::new_v4()),
+            std::slice::from_ref(&notification),
+            Some(&ack),
+            None,
+        )
+        .await
+        .unwrap()
+        .unwrap();
+        assert!(acknowledged);
+        assert_eq!(
+            queued_kinds(&pool, id).await,
+            ["contact_notification", "contact_acknowledgement"]
+        );
+
+        // Same recipient within the throttle window
+        let (id, acknowledged) = insert_contact(
+            &pool,
+            submission(Uuid::new_v4()),
+            &[notification],
+            Some(&ack),
+            None,
+        )
+        .await
+        .unwrap()
+        .unwrap();
+        assert!(!acknowledged);
+        assert_eq!(queued_kinds(&pool, id).await, ["contact_notification"]);
+
+        // And again once it has passed
+        sqlx::query("UPDATE contact_acknowledgements SET last_sent_at = NOW() - make_interval(hours => $1 + 1)")
+            .bind(ACK_THROTTLE_HOURS as i32)
+            .execute(&pool)
+            .await
+            .unwrap();
+        let (_, acknowledged) =
+            insert_contact(&pool, submission(Uuid::new_v4()), &[], Some(&ack), None)
+                .await
+                .unwrap()
+                .unwrap();
+        assert!(acknowledged);
+    }
+
+    #[sqlx::test]
+    async fn acknowledgements_under_a_previous_key_still_count(pool: PgPool) {
+        let ack = acknowledgement();
+        sqlx::query(
+            "INSERT INTO contact_acknowledgements (recipient_hash) VALUES ('v1:recipient')",
+        )
+        .execute(&pool)
+        .await
+        .unwrap();
+
+        let (_, acknowledged) =
+            insert_contact(&pool, submission(Uuid::new_v4()), &[], Some(&ack), None)
+                .await
+                .unwrap()
+                .unwrap();
+        assert!(!acknowledged);
+
+        sqlx::query("UPDATE contact_acknowledgements SET last_sent_at = NOW() - make_interval(hours => $1 + 1)")
+            .bind(ACK_THROTTLE_HOURS as i32)
+            .execute(&pool)
+            .await
+            .unwrap();
+        let (_, acknowledged) =
+            insert_contact(&pool, submission(Uuid::new_v4()), &[], Some(&ack), None)
+                .await
+                .unwrap()
+                .unwrap();
+        assert!(acknowledged);
+        let (current,): (bool,) = sqlx::query_as(
+            "SELECT EXISTS (SELECT 1 FROM contact_acknowledgements WHERE recipient_hash = 'v2:recipient')",
+        )
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+        assert!(current);
+    }
+
+    #[sqlx::test]
+    async fn form_nonces_are_used_with_the_submission(pool: PgPool) {
+        let first = Uuid::new_v4();
+        insert_contact(&pool, submission(first), &[], None, None)
+            .await
+            .unwrap();
+
+        // A failed insert leaves the nonce for the retry
+        let failed = insert_contact(&pool, submission(first), &[], None, Some("nonce")).await;
+        assert!(failed.is_err());
+        let stored = insert_contact(&pool, submission(Uuid::new_v4()), &[], None, Some("nonce"))
+            .await
+            .unwrap();
+        assert!(stored.is_some());
+
+        // A replay stores nothing
+        let replayed = insert_contact(&pool, submission(Uuid::new_v4()), &[], None, Some("nonce"))
+            .await
+            .unwrap();
+        assert!(replayed.is_none());
+        assert_eq!(
+            count_contacts(&pool, &ContactFilter::default())
+                .await
+                .unwrap(),
+            2
+        );
+    }
+}
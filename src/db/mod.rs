@@ -2,6 +2,10 @@
 //!
 //! Provides PostgreSQL connectivity via SQLx with compile-time checked queries.
 
+#[cfg(feature = "ssr")]
+mod analytics;
+#[cfg(feature = "ssr")]
+mod auth;
 #[cfg(feature = "ssr")]
 mod contact;
 #[cfg(feature = "ssr")]
@@ -9,11 +13,23 @@ pub mod github;
 #[cfg(feature = "ssr")]
 mod models;
 #[cfg(feature = "ssr")]
+mod outbox;
+#[cfg(feature = "ssr")]
 mod pool;
+#[cfg(feature = "ssr")]
+mod rate_limit;
 
+#[cfg(feature = "ssr")]
+pub use analytics::*;
+#[cfg(feature = "ssr")]
+pub use auth::*;
 #[cfg(feature = "ssr")]
 pub use contact::*;
 #[cfg(feature = "ssr")]
 pub use models::*;
 #[cfg(feature = "ssr")]
+pub use outbox::*;
+#[cfg(feature = "ssr")]
 pub use pool::*;
+#[cfg(feature = "ssr")]
+pub use rate_limit::*;
@@ -18,19 +18,56 @@ pub enum ContactStatus {
     Spam,
 }
 
-/// Contact form submission from visitors
+impl ContactStatus {
+    /// Value stored in the `status` column
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ContactStatus::Pending => "pending",
+            ContactStatus::Read => "read",
+            ContactStatus::Replied => "replied",
+            ContactStatus::Spam => "spam",
+        }
+    }
+
+    /// Parse a `status` column value
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "pending" => Some(ContactStatus::Pending),
+            "read" => Some(ContactStatus::Read),
+            "replied" => Some(ContactStatus::Replied),
+            "spam" => Some(ContactStatus::Spam),
+            _ => None,
+        }
+    }
+}
+
+/// Contact form submission from visitors.
+/// Name, email, subject and message are sealed (see `crate::encryption`);
+/// decrypt them with `ContactCipher::open`.
 #[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
 pub struct ContactSubmission {
     pub id: Uuid,
-    pub name: String,
-    pub email: String,
+    // Plaintext, only for rows stored before encryption (NULL once sealed)
+    pub name: Option<String>,
+    pub email: Option<String>,
     pub subject: Option<String>,
-    pub message: String,
+    pub message: Option<String>,
+
+    // Envelope encryption
+    pub key_id: Option<String>,
+    pub wrapped_dek: Option<Vec<u8>>,
+    pub sealed_pii: Option<Vec<u8>>,
+    /// Keyed hash of the sender address (see `crate::data_request`)
+    pub email_index: Option<String>,
 
     // Spam protection
     pub honeypot_filled: Option<bool>,
     pub ip_hash: Option<String>,
     pub user_agent: Option<String>,
+    pub spam_score: Option<i32>,
+    /// Points per spam rule, e.g. {"links": 4}
+    pub spam_rules: Option<serde_json::Value>,
+    pub message_hash: Option<String>,
 
     // Status
     pub status: String,
@@ -43,15 +80,117 @@ pub struct ContactSubmission {
 }
 
 /// New contact submission (for INSERT)
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone)]
 pub struct NewContactSubmission {
-    pub name: String,
-    pub email: String,
-    pub subject: Option<String>,
-    pub message: String,
+    /// Chosen before sealing, since the sealed data is bound to it
+    pub id: Uuid,
+    pub pii: crate::encryption::SealedPii,
+    pub email_index: String,
     pub honeypot_filled: bool,
     pub ip_hash: Option<String>,
     pub user_agent: Option<String>,
+    pub status: ContactStatus,
+    pub spam_score: Option<i32>,
+    pub spam_rules: Option<serde_json::Value>,
+    pub message_hash: Option<String>,
+}
+
+/// Acknowledgement to queue with a new submission, unless its recipient
+/// already got one within `ACK_THROTTLE_HOURS`
+#[derive(Debug, Clone)]
+pub struct NewAcknowledgement {
+    /// Keyed hashes of the lowercased recipient address, current key first
+    pub recipient_hashes: Vec<String>,
+    pub email: NewOutboxEmail,
+}
+
+/// Reply sent to a contact submission from the admin inbox.
+/// Subject and body are sealed with the submission's data key; decrypt
+/// them with `ContactCipher::open_reply`.
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct ContactReply {
+    pub id: Uuid,
+    pub submission_id: Uuid,
+    // Plaintext, only for replies stored before encryption (NULL once sealed)
+    pub subject: Option<String>,
+    pub body: Option<String>,
+    pub sealed_content: Option<Vec<u8>>,
+    pub message_id: String,
+    pub sent_by: String,
+    pub sent_at: DateTime<Utc>,
+}
+
+/// New contact reply (for INSERT, after the email was sent)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NewContactReply {
+    pub id: Uuid,
+    pub submission_id: Uuid,
+    /// Subject and body, from `ContactCipher::seal_reply`
+    pub sealed_content: Vec<u8>,
+    pub message_id: String,
+    pub sent_by: String,
+}
+
+/// Audit record of a visitor erasing their submissions. Holds no address or
+/// hash, only what was deleted and when.
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct ContactErasure {
+    pub id: Uuid,
+    pub submission_count: i32,
+    pub reply_count: i32,
+    /// When the data link used for the erasure was issued
+    pub link_issued_at: DateTime<Utc>,
+    pub erased_at: DateTime<Utc>,
+}
+
+/// Kind of email in the outbox
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum OutboxKind {
+    ContactNotification,
+    ContactAcknowledgement,
+}
+
+impl OutboxKind {
+    /// Value stored in the `kind` column
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            OutboxKind::ContactNotification => "contact_notification",
+            OutboxKind::ContactAcknowledgement => "contact_acknowledgement",
+        }
+    }
+
+    /// Parse a `kind` column value
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "contact_notification" => Some(OutboxKind::ContactNotification),
+            "contact_acknowledgement" => Some(OutboxKind::ContactAcknowledgement),
+            _ => None,
+        }
+    }
+}
+
+/// Queued email for a contact submission
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct OutboxEmail {
+    pub id: Uuid,
+    pub kind: String,
+    pub submission_id: Uuid,
+    pub language: String,
+    pub status: String,
+    pub attempts: i32,
+    pub next_attempt_at: DateTime<Utc>,
+    pub locked_until: Option<DateTime<Utc>>,
+    pub last_error: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub sent_at: Option<DateTime<Utc>>,
+}
+
+/// New outbox email (enqueued with its submission)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NewOutboxEmail {
+    pub kind: OutboxKind,
+    /// Language code (en / fr)
+    pub language: String,
 }
 
 /// Page view record (privacy-first analytics)
@@ -0,0 +1,364 @@
+//! Email outbox database operations
+//!
+//! Contact emails are enqueued with their submission and delivered by the
+//! outbox worker in `jobs`. Workers claim due rows with
+//! `FOR UPDATE SKIP LOCKED` and hold them with a short lease, so concurrent
+//! workers never send the same email twice and a crashed worker's rows are
+//! picked up again once the lease expires.
+
+use sqlx::{PgConnection, PgPool};
+use uuid::Uuid;
+
+use super::{NewOutboxEmail, OutboxEmail};
+
+/// Attempts before an email is marked dead
+pub const OUTBOX_MAX_ATTEMPTS: i32 = 8;
+
+/// Delay before the first retry; doubled after every failure
+pub const OUTBOX_BASE_BACKOFF_SECONDS: i32 = 60;
+
+/// Longest delay between two attempts
+pub const OUTBOX_MAX_BACKOFF_SECONDS: i32 = 6 * 60 * 60;
+
+/// How long a claimed row stays locked to its worker
+pub const OUTBOX_LEASE_MINUTES: i32 = 5;
+
+/// Days sent emails are kept for the admin view
+pub const OUTBOX_SENT_RETENTION_DAYS: i32 = 30;
+
+/// Outbox size per status
+#[derive(Debug, Clone, Default)]
+pub struct OutboxCounts {
+    pub pending: i64,
+    /// Pending emails that already failed at least once
+    pub retrying: i64,
+    pub sent: i64,
+    pub dead: i64,
+}
+
+/// Enqueue emails for a submission inside the caller's transaction.
+pub async fn enqueue_outbox_emails(
+    conn: &mut PgConnection,
+    submission_id: Uuid,
+    emails: &[NewOutboxEmail],
+) -> Result<(), sqlx::Error> {
+    for email in emails {
+        sqlx::query(
+            r#"
+            INSERT INTO email_outbox (kind, submission_id, language)
+            VALUES ($1, $2, $3)
+            "#,
+        )
+        .bind(email.kind.as_str())
+        .bind(submission_id)
+        .bind(&email.language)
+        .execute(&mut *conn)
+        .await?;
+    }
+
+    Ok(())
+}
+
+/// Claim up to `limit` due emails and count the attempt.
+/// Claimed rows are leased for `OUTBOX_LEASE_MINUTES`.
+pub async fn claim_outbox_emails(pool: &PgPool, limit: i64) -> Result<Vec<OutboxEmail>, sqlx::Error> {
+    sqlx::query_as(
+        r#"
+        UPDATE email_outbox
+        SET attempts = attempts + 1,
+            locked_until = NOW() + make_interval(mins => $2)
+        WHERE id IN (
+            SELECT id FROM email_outbox
+            WHERE status = 'pending'
+              AND next_attempt_at <= NOW()
+              AND (locked_until IS NULL OR locked_until < NOW())
+            ORDER BY next_attempt_at
+            LIMIT $1
+            FOR UPDATE SKIP LOCKED
+        )
+        RETURNING *
+        "#,
+    )
+    .bind(limit)
+    .bind(OUTBOX_LEASE_MINUTES)
+    .fetch_all(pool)
+    .await
+}
+
+/// Mark a claimed email as delivered.
+pub async fn mark_outbox_sent(pool: &PgPool, id: Uuid) -> Result<(), sqlx::Error> {
+    sqlx::query(
+        r#"
+        UPDATE email_outbox
+        SET status = 'sent', sent_at = NOW(), locked_until = NULL, last_error = NULL
+        WHERE id = $1
+        "#,
+    )
+    .bind(id)
+    .execute(pool)
+    .await?;
+
+    Ok(())
+}
+
+/// Record a failed attempt: schedule a retry with exponential backoff, or
+/// mark the email dead after `OUTBOX_MAX_ATTEMPTS`. Returns the new status.
+pub async fn mark_outbox_failed(pool: &PgPool, id: Uuid, error: &str) -> Result<String, sqlx::Error> {
+    let row: (String,) = sqlx::query_as(
+        r#"
+        UPDATE email_outbox
+        SET status = CASE WHEN attempts >= $3 THEN 'dead' ELSE 'pending' END,
+            next_attempt_at = NOW() + make_interval(
+                secs => LEAST($4 * power(2, GREATEST(attempts - 1, 0)), $5)
+            ),
+            locked_until = NULL,
+            last_error = $2
+        WHERE id = $1
+        RETURNING status
+        "#,
+    )
+    .bind(id)
+    .bind(error)
+    .bind(OUTBOX_MAX_ATTEMPTS)
+    .bind(OUTBOX_BASE_BACKOFF_SECONDS)
+    .bind(OUTBOX_MAX_BACKOFF_SECONDS)
+    .fetch_one(pool)
+    .await?;
+
+    Ok(row.0)
+}
+
+/// Mark a claimed email dead without retrying, for failures no later
+/// attempt can fix (its submission was deleted, or its kind is unknown).
+pub async fn mark_outbox_dead(pool: &PgPool, id: Uuid, error: &str) -> Result<(), sqlx::Error> {
+    sqlx::query(
+        r#"
+        UPDATE email_outbox
+        SET status = 'dead', locked_until = NULL, last_error = $2
+        WHERE id = $1
+        "#,
+    )
+    .bind(id)
+    .bind(error)
+    .execute(pool)
+    .await?;
+
+    Ok(())
+}
+
+/// Put a dead email back in the queue with a fresh set of attempts.
+/// Returns false if the email does not exist or is not dead.
+pub async fn retry_outbox_email(pool: &PgPool, id: Uuid) -> Result<bool, sqlx::Error> {
+    let result = sqlx::query(
+        r#"
+        UPDATE email_outbox
+        SET status = 'pending', attempts = 0, next_attempt_at = NOW(), locked_until = NULL
+        WHERE id = $1 AND status = 'dead'
+        "#,
+    )
+    .bind(id)
+    .execute(pool)
+    .await?;
+
+    Ok(result.rows_affected() > 0)
+}
+
+/// Outbox emails, newest first, optionally limited to one status.
+pub async fn list_outbox_emails(
+    pool: &PgPool,
+    status: Option<&str>,
+    limit: i64,
+) -> Result<Vec<OutboxEmail>, sqlx::Error> {
+    sqlx::query_as(
+        r#"
+        SELECT * FROM email_outbox
+        WHERE $1::text IS NULL OR status = $1
+        ORDER BY created_at DESC
+        LIMIT $2
+        "#,
+    )
+    .bind(status)
+    .bind(limit)
+    .fetch_all(pool)
+    .await
+}
+
+/// Emails queued for one submission, oldest first.
+pub async fn list_submission_outbox_emails(
+    pool: &PgPool,
+    submission_id: Uuid,
+) -> Result<Vec<OutboxEmail>, sqlx::Error> {
+    sqlx::query_as(
+        r#"
+        SELECT * FROM email_outbox
+        WHERE submission_id = $1
+        ORDER BY created_at
+        "#,
+    )
+    .bind(submission_id)
+    .fetch_all(pool)
+    .await
+}
+
+/// Outbox size per status.
+pub async fn outbox_counts(pool: &PgPool) -> Result<OutboxCounts, sqlx::Error> {
+    let row: (i64, i64, i64, i64) = sqlx::query_as(
+        r#"
+        SELECT
+            COUNT(*) FILTER (WHERE status = 'pending'),
+            COUNT(*) FILTER (WHERE status = 'pending' AND attempts > 0),
+            COUNT(*) FILTER (WHERE status = 'sent'),
+            COUNT(*) FILTER (WHERE status = 'dead')
+        FROM email_outbox
+        "#,
+    )
+    .fetch_one(pool)
+    .await?;
+
+    Ok(OutboxCounts {
+        pending: row.0,
+        retrying: row.1,
+        sent: row.2,
+        dead: row.3,
+    })
+}
+
+/// Delete sent emails older than `OUTBOX_SENT_RETENTION_DAYS`.
+/// Dead emails are kept until retried or their submission is deleted.
+pub async fn cleanup_outbox(pool: &PgPool) -> Result<u64, sqlx::Error> {
+    let result = sqlx::query(
+        r#"
+        DELETE FROM email_outbox
+        WHERE status = 'sent' AND sent_at < NOW() - make_interval(days => $1)
+        "#,
+    )
+    .bind(OUTBOX_SENT_RETENTION_DAYS)
+    .execute(pool)
+    .await?;
+
+    Ok(result.rows_affected())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::OutboxKind;
+
+    /// Legacy plaintext submission with one queued notification
+    async fn queued_email(pool: &PgPool) -> Uuid {
+        let (submission_id,): (Uuid,) = sqlx::query_as(
+            "INSERT INTO contact_submissions (name, email, message) VALUES ('Ada', 'ada@example.com', 'Hello') RETURNING id",
+        )
+        .fetch_one(pool)
+        .await
+        .unwrap();
+        let mut conn = pool.acquire().await.unwrap();
+        let email = NewOutboxEmail {
+            kind: OutboxKind::ContactNotification,
+            language: "en".to_string(),
+        };
+        enqueue_outbox_emails(&mut conn, submission_id, &[email])
+            .await
+            .unwrap();
+        let (id,): (Uuid,) = sqlx::query_as("SELECT id FROM email_outbox WHERE submission_id = $1")
+            .bind(submission_id)
+            .fetch_one(pool)
+            .await
+            .unwrap();
+        id
+    }
+
+    async fn make_due(pool: &PgPool, id: Uuid) {
+        sqlx::query("UPDATE email_outbox SET next_attempt_at = NOW() WHERE id = $1")
+            .bind(id)
+            .execute(pool)
+            .await
+            .unwrap();
+    }
+
+    #[sqlx::test]
+    async fn failed_emails_back_off_then_die(pool: PgPool) {
+        let id = queued_email(&pool).await;
+
+        let claimed = claim_outbox_emails(&pool, 10).await.unwrap();
+        assert_eq!(claimed.len(), 1);
+        assert_eq!(claimed[0].attempts, 1);
+        // The lease keeps a second worker off the row
+        assert!(claim_outbox_emails(&pool, 10).await.unwrap().is_empty());
+
+        assert_eq!(
+            mark_outbox_failed(&pool, id, "timeout").await.unwrap(),
+            "pending"
+        );
+        let (delay, locked): (f64, bool) = sqlx::query_as(
+            "SELECT EXTRACT(EPOCH FROM next_attempt_at - NOW())::float8, locked_until IS NOT NULL FROM email_outbox WHERE id = $1",
+        )
+        .bind(id)
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+        assert!((55.0..=60.0).contains(&delay), "first retry after {delay}s");
+        assert!(!locked);
+        // Not due yet
+        assert!(claim_outbox_emails(&pool, 10).await.unwrap().is_empty());
+
+        // Second failure doubles the delay
+        make_due(&pool, id).await;
+        claim_outbox_emails(&pool, 10).await.unwrap();
+        mark_outbox_failed(&pool, id, "timeout").await.unwrap();
+        let (delay,): (f64,) = sqlx::query_as(
+            "SELECT EXTRACT(EPOCH FROM next_attempt_at - NOW())::float8 FROM email_outbox WHERE id = $1",
+        )
+        .bind(id)
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+        assert!(
+            (115.0..=120.0).contains(&delay),
+            "second retry after {delay}s"
+        );
+
+        // The last attempt failing marks it dead
+        sqlx::query(
+            "UPDATE email_outbox SET attempts = $2 - 1, next_attempt_at = NOW() WHERE id = $1",
+        )
+        .bind(id)
+        .bind(OUTBOX_MAX_ATTEMPTS)
+        .execute(&pool)
+        .await
+        .unwrap();
+        let claimed = claim_outbox_emails(&pool, 10).await.unwrap();
+        assert_eq!(claimed[0].attempts, OUTBOX_MAX_ATTEMPTS);
+        assert_eq!(
+            mark_outbox_failed(&pool, id, "timeout").await.unwrap(),
+            "dead"
+        );
+        make_due(&pool, id).await;
+        assert!(claim_outbox_emails(&pool, 10).await.unwrap().is_empty());
+
+        // A retry starts over
+        assert!(retry_outbox_email(&pool, id).await.unwrap());
+        assert!(!retry_outbox_email(&pool, id).await.unwrap());
+        let claimed = claim_outbox_emails(&pool, 10).await.unwrap();
+        assert_eq!(claimed[0].attempts, 1);
+    }
+
+    #[sqlx::test]
+    async fn permanent_failures_die_at_once(pool: PgPool) {
+        let id = queued_email(&pool).await;
+        claim_outbox_emails(&pool, 10).await.unwrap();
+
+        mark_outbox_dead(&pool, id, "Submission no longer exists")
+            .await
+            .unwrap();
+        let counts = outbox_counts(&pool).await.unwrap();
+        assert_eq!((counts.pending, counts.dead), (0, 1));
+        let dead = list_outbox_emails(&pool, Some("dead"), 10).await.unwrap();
+        assert_eq!(dead[0].attempts, 1);
+        assert_eq!(dead[0].locked_until, None);
+        assert_eq!(
+            dead[0].last_error.as_deref(),
+            Some("Submission no longer exists")
+        );
+    }
+}
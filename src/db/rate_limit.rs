@@ -0,0 +1,98 @@
+//! Rate limit counters for Postgres-backed policies
+//!
+//! Fixed windows in `rate_limits`, one row per (policy, key). Each hit is a
+//! single upsert, so concurrent requests cannot slip past the limit. Keys
+//! are versioned IP hashes (see `crate::ip_hash`).
+
+use sqlx::PgPool;
+
+use crate::rate_limit::RatePolicy;
+
+/// Counter state after recording a hit
+#[derive(Debug, Clone, Copy)]
+pub struct RateLimitHit {
+    /// Hits in the current window (stops at `max_requests + 1`)
+    pub hits: i32,
+    /// Seconds until the window resets
+    pub resets_in: f64,
+}
+
+/// Record a hit for `key` under `policy`, starting a new window if the
+/// previous one has ended. An open window counted under one of the
+/// `previous` keys (the same client hashed with a key since rotated out)
+/// moves to `key`, so a rotation does not reset anyone's budget.
+pub async fn record_rate_limit_hit(
+    pool: &PgPool,
+    policy: &RatePolicy,
+    key: &str,
+    previous: &[String],
+) -> Result<RateLimitHit, sqlx::Error> {
+    // The carried-over window seeds the insert; when the row already exists
+    // the usual update applies. SET expressions see the row as it was
+    // before the update.
+    let (hits, resets_in): (i32, f64) = sqlx::query_as(
+        r#"
+        WITH carried AS (
+            DELETE FROM rate_limits
+            WHERE policy = $1 AND key = ANY($5)
+              AND window_start > NOW() - make_interval(secs => $3)
+            RETURNING window_start, hits
+        )
+        INSERT INTO rate_limits (policy, key, window_start, hits)
+        SELECT $1, $2, COALESCE(MIN(window_start), NOW()), LEAST(COALESCE(MAX(hits), 0) + 1, $4 + 1)
+        FROM carried
+        ON CONFLICT (policy, key) DO UPDATE
+        SET hits = CASE
+                WHEN rate_limits.window_start <= NOW() - make_interval(secs => $3) THEN 1
+                ELSE LEAST(rate_limits.hits + 1, $4 + 1)
+            END,
+            window_start = CASE
+                WHEN rate_limits.window_start <= NOW() - make_interval(secs => $3) THEN NOW()
+                ELSE rate_limits.window_start
+            END
+        RETURNING hits,
+            GREATEST(EXTRACT(EPOCH FROM window_start + make_interval(secs => $3) - NOW()), 0)::FLOAT8
+        "#,
+    )
+    .bind(policy.name)
+    .bind(key)
+    .bind(policy.window.as_secs_f64())
+    .bind(policy.max_requests as i32)
+    .bind(previous)
+    .fetch_one(pool)
+    .await?;
+
+    Ok(RateLimitHit { hits, resets_in })
+}
+
+/// Hits recorded under `policy` in the current window, across all keys.
+/// Limited keys count once more, since their hits stop adding up.
+pub async fn rate_limit_pressure(pool: &PgPool, policy: &RatePolicy) -> Result<i64, sqlx::Error> {
+    sqlx::query_scalar(
+        r#"
+        SELECT COALESCE(SUM(hits), 0)::BIGINT
+        FROM rate_limits
+        WHERE policy = $1
+          AND window_start > NOW() - make_interval(secs => $2)
+        "#,
+    )
+    .bind(policy.name)
+    .bind(policy.window.as_secs_f64())
+    .fetch_one(pool)
+    .await
+}
+
+/// Clean up rate limit counters older than 24 hours (longer than any
+/// policy window). Call this periodically to prevent table bloat.
+pub async fn cleanup_rate_limits(pool: &PgPool) -> Result<u64, sqlx::Error> {
+    let result = sqlx::query(
+        r#"
+        DELETE FROM rate_limits
+        WHERE window_start < NOW() - INTERVAL '24 hours'
+        "#,
+    )
+    .execute(pool)
+    .await?;
+
+    Ok(result.rows_affected())
+}
@@ -0,0 +1,350 @@
+//! Optional DKIM signing
+//!
+//! Enabled when `DKIM_SELECTOR` and `DKIM_PRIVATE_KEY_PATH` are set.
+//! `DKIM_DOMAIN` defaults to the domain of `SMTP_FROM`. `DKIM_ALGORITHM` is
+//! `rsa` (default; PKCS#1 PEM, e.g. `openssl genrsa -traditional 2048`) or
+//! `ed25519` (base64 of the 32-byte private key). Headers and body use
+//! relaxed canonicalization, so relays that rewrap headers keep the
+//! signature valid.
+
+use std::path::PathBuf;
+
+use lettre::message::dkim::{
+    DkimCanonicalization, DkimCanonicalizationType, DkimConfig, DkimSigningAlgorithm,
+    DkimSigningKey,
+};
+use lettre::message::header::HeaderName;
+
+/// Headers covered by the signature (absent ones are signed as empty, so
+/// they cannot be added in transit). `Content-Type` is only a message
+/// header when the message is built with `super::alternative_message`.
+const SIGNED_HEADERS: &[&str] = &[
+    "From",
+    "Reply-To",
+    "To",
+    "Subject",
+    "Date",
+    "Message-ID",
+    "In-Reply-To",
+    "References",
+    "MIME-Version",
+    "Content-Type",
+    "Content-Transfer-Encoding",
+];
+
+#[derive(Debug, thiserror::Error)]
+pub enum DkimError {
+    #[error("Unknown DKIM_ALGORITHM: {0}")]
+    Algorithm(String),
+
+    #[error("Could not read DKIM key {path}: {source}")]
+    Read {
+        path: PathBuf,
+        source: std::io::Error,
+    },
+
+    #[error("Invalid DKIM key {path}: {message}")]
+    Key { path: PathBuf, message: String },
+
+    #[error("Invalid DKIM domain: {0}")]
+    Domain(String),
+}
+
+/// Load the DKIM signer from environment variables.
+/// Returns Ok(None) when DKIM is not configured.
+pub fn dkim_from_env(from_address: &str) -> Result<Option<DkimConfig>, DkimError> {
+    let (Ok(selector), Ok(key_path)) = (
+        std::env::var("DKIM_SELECTOR"),
+        std::env::var("DKIM_PRIVATE_KEY_PATH"),
+    ) else {
+        return Ok(None);
+    };
+
+    let domain = match std::env::var("DKIM_DOMAIN") {
+        Ok(domain) => domain,
+        Err(_) => from_address
+            .rsplit_once('@')
+            .map(|(_, domain)| domain.trim_end_matches('>').to_string())
+            .ok_or_else(|| DkimError::Domain(from_address.to_string()))?,
+    };
+    let algorithm = match std::env::var("DKIM_ALGORITHM").as_deref() {
+        Ok("rsa") | Err(_) => DkimSigningAlgorithm::Rsa,
+        Ok("ed25519") => DkimSigningAlgorithm::Ed25519,
+        Ok(other) => return Err(DkimError::Algorithm(other.to_string())),
+    };
+
+    let path = PathBuf::from(key_path);
+    let key = std::fs::read_to_string(&path).map_err(|source| DkimError::Read {
+        path: path.clone(),
+        source,
+    })?;
+
+    dkim_config(selector, domain, key.trim(), algorithm)
+        .map(Some)
+        .map_err(|message| DkimError::Key { path, message })
+}
+
+/// Build a signer from a private key
+pub fn dkim_config(
+    selector: String,
+    domain: String,
+    private_key: &str,
+    algorithm: DkimSigningAlgorithm,
+) -> Result<DkimConfig, String> {
+    if domain.is_empty() || selector.is_empty() {
+        return Err("selector and domain must not be empty".to_string());
+    }
+    let key = DkimSigningKey::new(private_key, algorithm).map_err(|e| e.to_string())?;
+
+    Ok(DkimConfig::new(
+        selector,
+        domain,
+        key,
+        SIGNED_HEADERS
+            .iter()
+            .map(|name| HeaderName::new_from_ascii_str(name))
+            .collect(),
+        DkimCanonicalization {
+            header: DkimCanonicalizationType::Relaxed,
+            body: DkimCanonicalizationType::Relaxed,
+        },
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    //! Signatures are checked with an independent relaxed/relaxed verifier
+    //! (RFC 6376 §3.4.2, §3.4.4, §3.7) against the fixture keys' public halves.
+
+    use super::*;
+    use base64::{engine::general_purpose::STANDARD as BASE64, Engine};
+    use lettre::message::Mailbox;
+    use lettre::Message;
+    use sha2::{Digest, Sha256};
+
+    const RSA_KEY: &str = include_str!("testdata/dkim_rsa.pem");
+    const ED25519_KEY: &str = include_str!("testdata/dkim_ed25519.key");
+
+    fn fixed_message() -> Message {
+        let from: Mailbox = "Portfolio <noreply@example.com>".parse().unwrap();
+        let builder = Message::builder()
+            .from(from)
+            .reply_to("owner@example.com".parse().unwrap())
+            .to("visitor@example.org".parse().unwrap())
+            .subject("Merci pour votre message – réponse sous 2 jours")
+            .message_id(Some("<ack-1@example.com>".to_string()))
+            .in_reply_to("<contact-1@example.com>".to_string());
+        crate::email::alternative_message(
+            builder,
+            "Bonjour,\n\nLine with trailing spaces   \n\n\n".to_string(),
+            "<p>Bonjour,</p>\t<p>Merci</p>".to_string(),
+        )
+        .unwrap()
+    }
+
+    /// Relaxed body canonicalization
+    fn canonical_body(body: &[u8]) -> Vec<u8> {
+        let text = String::from_utf8_lossy(body);
+        let mut lines: Vec<String> = text
+            .split("\r\n")
+            .map(|line| {
+                let collapsed = line
+                    .split([' ', '\t'])
+                    .filter(|w| !w.is_empty())
+                    .collect::<Vec<_>>();
+                let mut out = collapsed.join(" ");
+                if line.starts_with([' ', '\t']) && !out.is_empty() {
+                    out.insert(0, ' ');
+                }
+                out
+            })
+            .collect();
+        while lines.last().is_some_and(|l| l.is_empty()) {
+            lines.pop();
+        }
+        lines
+            .iter()
+            .map(|l| format!("{l}\r\n"))
+            .collect::<String>()
+            .into_bytes()
+    }
+
+    /// Relaxed header canonicalization of an unfolded `name: value`
+    fn canonical_header(name: &str, value: &str) -> String {
+        let value = value.replace("\r\n", "");
+        let value = value
+            .split([' ', '\t'])
+            .filter(|w| !w.is_empty())
+            .collect::<Vec<_>>()
+            .join(" ");
+        format!("{}:{}", name.to_lowercase(), value)
+    }
+
+    /// Parsed headers (name, raw value) and body of a formatted message
+    fn split_message(raw: &str) -> (Vec<(String, String)>, &str) {
+        let (head, body) = raw.split_once("\r\n\r\n").unwrap();
+        let mut headers: Vec<(String, String)> = Vec::new();
+        for line in head.split("\r\n") {
+            if line.starts_with([' ', '\t']) {
+                headers
+                    .last_mut()
+                    .unwrap()
+                    .1
+                    .push_str(&format!("\r\n{line}"));
+            } else {
+                let (name, value) = line.split_once(':').unwrap();
+                headers.push((name.to_string(), value.to_string()));
+            }
+        }
+        (headers, body)
+    }
+
+    /// Returns (algorithm tag, header hash, signature) after checking `bh=`
+    fn verify_and_extract(raw: &str) -> (String, Vec<u8>, Vec<u8>) {
+        let (headers, body) = split_message(raw);
+        let (sig_name, sig_value) = headers
+            .iter()
+            .find(|(name, _)| name.eq_ignore_ascii_case("DKIM-Signature"))
+            .expect("message is signed");
+
+        let tags: Vec<(String, String)> = sig_value
+            .replace("\r\n", "")
+            .split(';')
+            .filter_map(|tag| tag.split_once('='))
+            .map(|(k, v)| {
+                (
+                    k.trim().to_string(),
+                    v.split_whitespace().collect::<String>(),
+                )
+            })
+            .collect();
+        let tag = |name: &str| {
+            tags.iter()
+                .find(|(k, _)| k == name)
+                .map(|(_, v)| v.clone())
+                .unwrap()
+        };
+
+        assert_eq!(tag("c"), "relaxed/relaxed");
+        assert_eq!(tag("d"), "example.com");
+        assert_eq!(tag("s"), "mail");
+
+        let body_hash = BASE64.encode(Sha256::digest(canonical_body(body.as_bytes())));
+        assert_eq!(tag("bh"), body_hash, "body hash");
+
+        let mut hashed = String::new();
+        for name in tag("h").split(':') {
+            if let Some((n, v)) = headers
+                .iter()
+                .rev()
+                .find(|(n, _)| n.eq_ignore_ascii_case(name))
+            {
+                hashed.push_str(&canonical_header(n, v));
+                hashed.push_str("\r\n");
+            }
+        }
+        // The signature header itself, with an empty b= and no trailing CRLF
+        let last_tag = sig_value.rfind(';').unwrap() + 1;
+        let rest = &sig_value[last_tag..];
+        let indent = rest.len() - rest.trim_start().len();
+        assert!(rest.trim_start().starts_with("b="));
+        let without_b = format!("{}b=", &sig_value[..last_tag + indent]);
+        hashed.push_str(&canonical_header(sig_name, &without_b));
+
+        (
+            tag("a"),
+            Sha256::digest(hashed.as_bytes()).to_vec(),
+            BASE64.decode(tag("b")).unwrap(),
+        )
+    }
+
+    #[test]
+    fn rsa_signature_verifies() {
+        use rsa::pkcs1::DecodeRsaPrivateKey;
+        use rsa::{Pkcs1v15Sign, RsaPrivateKey, RsaPublicKey};
+
+        let config = dkim_config(
+            "mail".to_string(),
+            "example.com".to_string(),
+            RSA_KEY,
+            DkimSigningAlgorithm::Rsa,
+        )
+        .unwrap();
+        let mut message = fixed_message();
+        message.sign(&config);
+        let raw = String::from_utf8(message.formatted()).unwrap();
+
+        let (algorithm, hash, signature) = verify_and_extract(&raw);
+        assert_eq!(algorithm, "rsa-sha256");
+        let public = RsaPublicKey::from(RsaPrivateKey::from_pkcs1_pem(RSA_KEY).unwrap());
+        public
+            .verify(Pkcs1v15Sign::new::<Sha256>(), &hash, &signature)
+            .expect("valid RSA signature");
+
+        // Tampering with a signed header breaks the signature
+        let tampered = raw.replacen("To: visitor@example.org", "To: attacker@example.net", 1);
+        let (_, hash, signature) = verify_and_extract(&tampered);
+        assert!(public
+            .verify(Pkcs1v15Sign::new::<Sha256>(), &hash, &signature)
+            .is_err());
+
+        // Content-Type is signed too, so the parts cannot be swapped around
+        let tampered = raw.replacen("multipart/alternative", "multipart/mixed", 1);
+        let (_, hash, signature) = verify_and_extract(&tampered);
+        assert!(public
+            .verify(Pkcs1v15Sign::new::<Sha256>(), &hash, &signature)
+            .is_err());
+    }
+
+    #[test]
+    fn ed25519_signature_verifies() {
+        use ed25519_dalek::{Signature, SigningKey, Verifier};
+
+        let config = dkim_config(
+            "mail".to_string(),
+            "example.com".to_string(),
+            ED25519_KEY.trim(),
+            DkimSigningAlgorithm::Ed25519,
+        )
+        .unwrap();
+        let mut message = fixed_message();
+        message.sign(&config);
+        let raw = String::from_utf8(message.formatted()).unwrap();
+
+        let (algorithm, hash, signature) = verify_and_extract(&raw);
+        assert_eq!(algorithm, "ed25519-sha256");
+        let seed: [u8; 32] = BASE64
+            .decode(ED25519_KEY.trim())
+            .unwrap()
+            .try_into()
+            .unwrap();
+        let public = SigningKey::from_bytes(&seed).verifying_key();
+        let signature = Signature::from_slice(&signature).unwrap();
+        public
+            .verify(&hash, &signature)
+            .expect("valid Ed25519 signature");
+
+        // Tampering with the body breaks the body hash
+        let tampered = raw.replacen("Merci</p>", "Pay here</p>", 1);
+        let result = std::panic::catch_unwind(|| verify_and_extract(&tampered));
+        assert!(result.is_err(), "body hash must not match");
+    }
+
+    #[test]
+    fn rejects_invalid_keys() {
+        assert!(dkim_config(
+            "mail".to_string(),
+            "example.com".to_string(),
+            "not a key",
+            DkimSigningAlgorithm::Rsa,
+        )
+        .is_err());
+        assert!(dkim_config(
+            "mail".to_string(),
+            "example.com".to_string(),
+            "c2hvcnQ=",
+            DkimSigningAlgorithm::Ed25519,
+        )
+        .is_err());
+    }
+}
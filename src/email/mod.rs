@@ -1,98 +1,415 @@
 //! Email notification service using lettre
 //!
-//! Sends email notifications for contact form submissions.
+//! Sends email notifications for contact form submissions, acknowledgements
+//! to the visitor, admin replies and links to a visitor's own data.
+//!
+//! Notifications carry a deterministic `Message-ID` derived from the
+//! submission ID, so replies can reference it with `In-Reply-To` and every
+//! message about one submission lands in the same thread.
+//!
+//! Bodies are rendered from the compiled-in templates in [`templates`] and
+//! sent as `multipart/alternative` (plain text and HTML) through the
+//! transport selected in [`transport`], DKIM-signed when configured.
+
+mod dkim;
+mod templates;
+mod transport;
+
+pub use dkim::{dkim_config, dkim_from_env, DkimError};
+pub use templates::{Branding, EmailTemplate, RenderedEmail};
+pub use transport::{
+    mail_transport, MailTransport, MaildirTransport, SendFuture, SmtpSecurity, TransportConfig,
+};
 
 use lettre::{
-    message::header::ContentType, transport::smtp::authentication::Credentials,
-    AsyncSmtpTransport, AsyncTransport, Message, Tokio1Executor,
+    message::{
+        header::{self, ContentTransferEncoding, ContentType, HeaderName, HeaderValue},
+        Body, Mailbox, MessageBuilder, MultiPart,
+    },
+    Message,
 };
+use std::sync::Arc;
+use uuid::Uuid;
+
+use crate::i18n::Language;
+
+type EmailError = Box<dyn std::error::Error + Send + Sync>;
 
 /// Email configuration loaded from environment variables
 #[derive(Debug, Clone)]
 pub struct EmailConfig {
-    pub smtp_host: String,
-    pub smtp_port: u16,
-    pub smtp_user: String,
-    pub smtp_password: String,
+    pub transport: TransportConfig,
     pub from_address: String,
     pub to_address: String,
+    /// Send an acknowledgement email to the visitor
+    pub send_acknowledgements: bool,
+    /// Expected response time quoted in acknowledgements (business days)
+    pub response_time_days: u32,
+    /// Language of the owner notification
+    pub owner_language: Language,
+    /// Site name and URL shown in emails
+    pub branding: Branding,
+    /// DKIM signer, when configured
+    pub dkim: Option<Arc<lettre::message::dkim::DkimConfig>>,
 }
 
 impl EmailConfig {
-    /// Load email configuration from environment variables.
-    /// Returns None if required variables are missing (email will be skipped).
-    pub fn from_env() -> Option<Self> {
-        Some(Self {
-            smtp_host: std::env::var("SMTP_HOST").ok()?,
-            smtp_port: std::env::var("SMTP_PORT")
-                .ok()
-                .and_then(|p| p.parse().ok())
-                .unwrap_or(587),
-            smtp_user: std::env::var("SMTP_USER").ok()?,
-            smtp_password: std::env::var("SMTP_PASSWORD").ok()?,
-            from_address: std::env::var("SMTP_FROM")
-                .unwrap_or_else(|_| "noreply@pilgrim.ovh".to_string()),
+    /// Load email configuration from environment variables, once at startup
+    /// (it is shared through the Leptos context). Returns Ok(None) if
+    /// required variables are missing (email will be skipped), and an error
+    /// if the DKIM key does not parse.
+    pub fn from_env() -> Result<Option<Self>, DkimError> {
+        let Some(transport) = TransportConfig::from_env() else {
+            return Ok(None);
+        };
+        let from_address =
+            std::env::var("SMTP_FROM").unwrap_or_else(|_| "noreply@pilgrim.ovh".to_string());
+        let dkim = dkim_from_env(&from_address)?.map(Arc::new);
+
+        Ok(Some(Self {
+            transport,
+            from_address,
             to_address: std::env::var("CONTACT_EMAIL")
                 .unwrap_or_else(|_| "kovacs@pilgrim.ovh".to_string()),
-        })
+            send_acknowledgements: std::env::var("CONTACT_ACK_ENABLED")
+                .map(|v| v == "true" || v == "1")
+                .unwrap_or(false),
+            response_time_days: std::env::var("CONTACT_RESPONSE_DAYS")
+                .ok()
+                .and_then(|d| d.parse().ok())
+                .unwrap_or(2),
+            owner_language: std::env::var("OWNER_LANGUAGE")
+                .map(|code| Language::from_code(&code))
+                .unwrap_or_default(),
+            branding: Branding {
+                site_name: std::env::var("SITE_NAME")
+                    .unwrap_or_else(|_| "Kovács Krisztián Géza".to_string()),
+                site_url: std::env::var("SITE_URL")
+                    .map(|url| url.trim_end_matches('/').to_string())
+                    .unwrap_or_else(|_| "https://kovacs.pilgrim.ovh".to_string()),
+            },
+            dkim,
+        }))
+    }
+}
+
+/// Sign (if DKIM is configured) and hand a message to the transport
+async fn deliver(config: &EmailConfig, mut message: Message) -> Result<(), EmailError> {
+    if let Some(dkim) = &config.dkim {
+        message.sign(dkim);
     }
+    mail_transport(&config.transport)?.send(message).await
+}
+
+/// Finish a message with its plain text and HTML alternatives. lettre
+/// writes a multipart's `Content-Type` with the body, after DKIM signing;
+/// built this way it is a message header, so the signature covers it.
+fn alternative_message(
+    builder: MessageBuilder,
+    text: String,
+    html: String,
+) -> Result<Message, EmailError> {
+    let multipart = MultiPart::alternative_plain_html(text, html);
+    let content_type = multipart
+        .headers()
+        .get::<ContentType>()
+        .ok_or("multipart has no Content-Type")?;
+    // The parts are already encoded; keep what follows the multipart headers.
+    // Quoted-printable lines run to 76 characters, which lettre's encoding
+    // check would refuse as 7-bit, so the body is passed through as is.
+    let formatted = multipart.formatted();
+    let body_start = formatted
+        .windows(4)
+        .position(|w| w == b"\r\n\r\n")
+        .ok_or("multipart has no body")?
+        + 4;
+    let body = Body::dangerous_pre_encoded(
+        formatted[body_start..].to_vec(),
+        ContentTransferEncoding::SevenBit,
+    );
+
+    Ok(builder
+        .header(header::MIME_VERSION_1_0)
+        .header(content_type)
+        .body(body)?)
+}
+
+/// Domain part of the configured From address, used in Message-IDs
+fn message_id_domain(config: &EmailConfig) -> Result<String, EmailError> {
+    let from: Mailbox = config.from_address.parse()?;
+    Ok(from.email.domain().to_string())
+}
+
+/// Message-ID of the notification for a contact submission
+pub fn contact_message_id(config: &EmailConfig, submission_id: Uuid) -> Result<String, EmailError> {
+    Ok(format!(
+        "<contact-{}@{}>",
+        submission_id,
+        message_id_domain(config)?
+    ))
 }
 
 /// Send a contact form notification email.
 ///
 /// # Arguments
 /// * `config` - Email configuration
+/// * `submission_id` - Stored submission ID (used for the Message-ID)
 /// * `name` - Sender's name
 /// * `email` - Sender's email (used as reply-to)
 /// * `subject` - Optional subject line
 /// * `message` - The message content
 pub async fn send_contact_notification(
     config: &EmailConfig,
+    submission_id: Uuid,
     name: &str,
     email: &str,
     subject: Option<&str>,
     message: &str,
-) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
-    let subject_line = format!(
-        "[Portfolio Contact] {}",
-        subject.unwrap_or("New message from portfolio")
+) -> Result<(), EmailError> {
+    let admin_url = format!("{}/admin/inbox/{}", config.branding.site_url, submission_id);
+    let rendered = templates::render(
+        EmailTemplate::ContactNotification,
+        config.owner_language,
+        &config.branding,
+        &[
+            ("name", name),
+            ("email", email),
+            ("subject", subject.unwrap_or("(no subject)")),
+            ("message", message),
+            ("admin_url", &admin_url),
+        ],
     );
 
-    let body = format!(
-        r#"New contact form submission from your portfolio:
+    let builder = Message::builder()
+        .from(config.from_address.parse()?)
+        .reply_to(email.parse()?)
+        .to(config.to_address.parse()?)
+        .subject(rendered.subject)
+        .message_id(Some(contact_message_id(config, submission_id)?));
+    let email_message = alternative_message(builder, rendered.text, rendered.html)?;
 
-From: {} <{}>
-Subject: {}
+    deliver(config, email_message).await?;
 
-Message:
-{}
+    Ok(())
+}
 
----
-This email was sent from the contact form at kovacs.pilgrim.ovh
-"#,
-        name,
-        email,
-        subject.unwrap_or("(no subject)"),
-        message
+/// Send the visitor a confirmation in their language, quoting their message.
+/// Replies from the visitor go to `CONTACT_EMAIL`.
+pub async fn send_contact_acknowledgement(
+    config: &EmailConfig,
+    submission_id: Uuid,
+    language: Language,
+    name: &str,
+    email: &str,
+    message: &str,
+) -> Result<(), EmailError> {
+    let days = config.response_time_days.to_string();
+    let quoted = templates::quote_text(message);
+    let rendered = templates::render(
+        EmailTemplate::ContactAcknowledgement,
+        language,
+        &config.branding,
+        &[
+            ("name", name),
+            ("response_days", &days),
+            ("message", message),
+            ("message_quoted", &quoted),
+        ],
     );
 
-    let email_message = Message::builder()
+    let thread_id = contact_message_id(config, submission_id)?;
+    let builder = Message::builder()
         .from(config.from_address.parse()?)
-        .reply_to(email.parse()?)
-        .to(config.to_address.parse()?)
-        .subject(subject_line)
-        .header(ContentType::TEXT_PLAIN)
-        .body(body)?;
+        .reply_to(config.to_address.parse()?)
+        .to(email.parse()?)
+        .subject(rendered.subject)
+        .message_id(Some(format!(
+            "<ack-{}@{}>",
+            submission_id,
+            message_id_domain(config)?
+        )))
+        .in_reply_to(thread_id.clone())
+        .references(thread_id)
+        // RFC 3834: tells the visitor's mail server not to auto-reply back
+        .raw_header(HeaderValue::new(
+            HeaderName::new_from_ascii_str("Auto-Submitted"),
+            "auto-replied".to_string(),
+        ));
+    let email_message = alternative_message(builder, rendered.text, rendered.html)?;
 
-    let creds = Credentials::new(config.smtp_user.clone(), config.smtp_password.clone());
+    deliver(config, email_message).await?;
 
-    let mailer: AsyncSmtpTransport<Tokio1Executor> =
-        AsyncSmtpTransport::<Tokio1Executor>::starttls_relay(&config.smtp_host)?
-            .port(config.smtp_port)
-            .credentials(creds)
-            .build();
+    Ok(())
+}
+
+/// An admin reply to a contact submission
+pub struct ContactReplyEmail<'a> {
+    pub submission_id: Uuid,
+    pub reply_id: Uuid,
+    /// Visitor's address
+    pub to: &'a str,
+    pub subject: &'a str,
+    pub body: &'a str,
+}
+
+/// Send a reply to a contact submission, threaded under the original
+/// notification. Replies from the visitor go to `CONTACT_EMAIL`.
+/// Returns the reply's Message-ID.
+pub async fn send_contact_reply(
+    config: &EmailConfig,
+    reply: ContactReplyEmail<'_>,
+) -> Result<String, EmailError> {
+    let thread_id = contact_message_id(config, reply.submission_id)?;
+    let message_id = format!("<reply-{}@{}>", reply.reply_id, message_id_domain(config)?);
+    let rendered = templates::render(
+        EmailTemplate::ContactReply,
+        config.owner_language,
+        &config.branding,
+        &[("subject", reply.subject), ("body", reply.body)],
+    );
+
+    let builder = Message::builder()
+        .from(config.from_address.parse()?)
+        .reply_to(config.to_address.parse()?)
+        .to(reply.to.parse()?)
+        .subject(rendered.subject)
+        .message_id(Some(message_id.clone()))
+        .in_reply_to(thread_id.clone())
+        .references(thread_id);
+    let email_message = alternative_message(builder, rendered.text, rendered.html)?;
+
+    deliver(config, email_message).await?;
+
+    Ok(message_id)
+}
 
-    mailer.send(email_message).await?;
+/// Send a visitor the link to export or erase their submissions (see
+/// `crate::data_request`).
+pub async fn send_contact_data_link(
+    config: &EmailConfig,
+    language: Language,
+    email: &str,
+    link: &str,
+    expires_minutes: i64,
+) -> Result<(), EmailError> {
+    let minutes = expires_minutes.to_string();
+    let rendered = templates::render(
+        EmailTemplate::ContactDataLink,
+        language,
+        &config.branding,
+        &[("link", link), ("expires_minutes", &minutes)],
+    );
+
+    let builder = Message::builder()
+        .from(config.from_address.parse()?)
+        .to(email.parse()?)
+        .subject(rendered.subject)
+        .message_id(Some(format!(
+            "<data-link-{}@{}>",
+            Uuid::new_v4(),
+            message_id_domain(config)?
+        )))
+        .raw_header(HeaderValue::new(
+            HeaderName::new_from_ascii_str("Auto-Submitted"),
+            "auto-generated".to_string(),
+        ));
+    let email_message = alternative_message(builder, rendered.text, rendered.html)?;
+
+    deliver(config, email_message).await?;
 
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn maildir_config(dir: &std::path::Path) -> EmailConfig {
+        EmailConfig {
+            transport: TransportConfig::File {
+                dir: dir.to_path_buf(),
+            },
+            from_address: "noreply@example.com".to_string(),
+            to_address: "owner@example.com".to_string(),
+            send_acknowledgements: true,
+            response_time_days: 2,
+            owner_language: Language::default(),
+            branding: Branding {
+                site_name: "Example".to_string(),
+                site_url: "https://example.com".to_string(),
+            },
+            dkim: None,
+        }
+    }
+
+    /// Every message delivered into `new/`, with quoted-printable soft
+    /// line breaks joined so long values can be matched
+    fn written_messages(dir: &std::path::Path) -> Vec<String> {
+        std::fs::read_dir(dir.join("new"))
+            .unwrap()
+            .map(|entry| {
+                std::fs::read_to_string(entry.unwrap().path())
+                    .unwrap()
+                    .replace("=\r\n", "")
+            })
+            .collect()
+    }
+
+    #[test]
+    fn contact_flow_writes_notification_and_acknowledgement() {
+        let dir = std::env::temp_dir().join(format!("portfolio-contact-{}", Uuid::new_v4()));
+        let config = maildir_config(&dir);
+        let submission_id = Uuid::new_v4();
+
+        let runtime = tokio::runtime::Builder::new_current_thread()
+            .build()
+            .unwrap();
+        runtime.block_on(async {
+            send_contact_notification(
+                &config,
+                submission_id,
+                "Ada",
+                "ada@example.org",
+                Some("Hello"),
+                "Nice portfolio",
+            )
+            .await
+            .unwrap();
+            send_contact_acknowledgement(
+                &config,
+                submission_id,
+                Language::default(),
+                "Ada",
+                "ada@example.org",
+                "Nice portfolio",
+            )
+            .await
+            .unwrap();
+        });
+
+        let messages = written_messages(&dir);
+        assert_eq!(messages.len(), 2);
+        let thread_id = format!("<contact-{submission_id}@example.com>");
+
+        let notification = messages
+            .iter()
+            .find(|m| m.contains(&format!("Message-ID: {thread_id}")))
+            .expect("notification written");
+        assert!(notification.contains("To: owner@example.com"));
+        assert!(notification.contains("Reply-To: ada@example.org"));
+        assert!(notification.contains("Content-Type: multipart/alternative"));
+        assert!(notification.contains("Nice portfolio"));
+        assert!(notification.contains(&format!("https://example.com/admin/inbox/{submission_id}")));
+
+        let acknowledgement = messages
+            .iter()
+            .find(|m| m.contains(&format!("Message-ID: <ack-{submission_id}@example.com>")))
+            .expect("acknowledgement written");
+        assert!(acknowledgement.contains("To: ada@example.org"));
+        assert!(acknowledgement.contains(&format!("In-Reply-To: {thread_id}")));
+        assert!(acknowledgement.contains("Auto-Submitted: auto-replied"));
+        assert!(acknowledgement.contains("Nice portfolio"));
+
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+}
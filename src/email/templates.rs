@@ -0,0 +1,295 @@
+//! Compiled-in email templates
+//!
+//! Every template has a `text/plain` and a `text/html` variant per language
+//! under `templates/`. Text files start with a `Subject:` line. HTML files
+//! hold the body, then the footer after a `<!-- footer -->` marker, and are
+//! wrapped in `layout.html`. `{{name}}` placeholders are filled in a single
+//! pass (values are never re-expanded) and HTML-escaped in the HTML variant.
+
+use crate::i18n::Language;
+
+const LAYOUT_HTML: &str = include_str!("templates/layout.html");
+const FOOTER_MARKER: &str = "<!-- footer -->";
+
+/// Site name and URL used in every email
+#[derive(Debug, Clone)]
+pub struct Branding {
+    pub site_name: String,
+    pub site_url: String,
+}
+
+/// Named email templates
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EmailTemplate {
+    /// To the owner. Vars: name, email, subject, message, admin_url
+    ContactNotification,
+    /// To the visitor. Vars: name, message, message_quoted, response_days
+    ContactAcknowledgement,
+    /// Admin reply to the visitor. Vars: subject, body
+    ContactReply,
+    /// Export and erasure link for the visitor. Vars: link, expires_minutes
+    ContactDataLink,
+}
+
+impl EmailTemplate {
+    /// (text, html) sources for a language
+    fn sources(&self, language: Language) -> (&'static str, &'static str) {
+        match (self, language) {
+            (EmailTemplate::ContactNotification, Language::En) => (
+                include_str!("templates/contact_notification.en.txt"),
+                include_str!("templates/contact_notification.en.html"),
+            ),
+            (EmailTemplate::ContactNotification, Language::Fr) => (
+                include_str!("templates/contact_notification.fr.txt"),
+                include_str!("templates/contact_notification.fr.html"),
+            ),
+            (EmailTemplate::ContactAcknowledgement, Language::En) => (
+                include_str!("templates/contact_acknowledgement.en.txt"),
+                include_str!("templates/contact_acknowledgement.en.html"),
+            ),
+            (EmailTemplate::ContactAcknowledgement, Language::Fr) => (
+                include_str!("templates/contact_acknowledgement.fr.txt"),
+                include_str!("templates/contact_acknowledgement.fr.html"),
+            ),
+            (EmailTemplate::ContactDataLink, Language::En) => (
+                include_str!("templates/contact_data_link.en.txt"),
+                include_str!("templates/contact_data_link.en.html"),
+            ),
+            (EmailTemplate::ContactDataLink, Language::Fr) => (
+                include_str!("templates/contact_data_link.fr.txt"),
+                include_str!("templates/contact_data_link.fr.html"),
+            ),
+            // The reply body is written by the admin; only the signature
+            // is templated, and it has nothing to translate
+            (EmailTemplate::ContactReply, _) => (
+                include_str!("templates/contact_reply.txt"),
+                include_str!("templates/contact_reply.html"),
+            ),
+        }
+    }
+}
+
+/// A rendered email, ready for a `multipart/alternative` message
+#[derive(Debug, Clone)]
+pub struct RenderedEmail {
+    pub subject: String,
+    pub text: String,
+    pub html: String,
+}
+
+/// Render a template. `vars` are user-supplied values; branding values
+/// (`site_name`, `site_url`) are added automatically.
+pub fn render(
+    template: EmailTemplate,
+    language: Language,
+    branding: &Branding,
+    vars: &[(&str, &str)],
+) -> RenderedEmail {
+    let lookup = |name: &str| -> Option<&str> {
+        match name {
+            "site_name" => Some(&branding.site_name),
+            "site_url" => Some(&branding.site_url),
+            "lang" => Some(language.code()),
+            _ => vars.iter().find(|(k, _)| *k == name).map(|(_, v)| *v),
+        }
+    };
+
+    let (text_source, html_source) = template.sources(language);
+
+    // Text: "Subject: ..." line, blank line, body. Split before filling,
+    // so a value with a blank line cannot move the split.
+    let (subject, text) = match text_source.split_once("\n\n") {
+        Some((header, body)) => (
+            // Never let a value break out of the Subject header
+            fill(header.trim_start_matches("Subject:").trim(), |name| {
+                lookup(name).map(|value| value.replace(['\r', '\n'], " "))
+            }),
+            fill(body, |name| lookup(name).map(str::to_string)),
+        ),
+        None => (
+            String::new(),
+            fill(text_source, |name| lookup(name).map(str::to_string)),
+        ),
+    };
+
+    // HTML: escaped values, then wrapped in the layout
+    let html_body = fill(html_source, |name| lookup(name).map(escape_html));
+    let (content, footer) = html_body
+        .split_once(FOOTER_MARKER)
+        .unwrap_or((html_body.as_str(), ""));
+    let html = fill(LAYOUT_HTML, |name| match name {
+        "content" => Some(content.trim().to_string()),
+        "footer" => Some(footer.trim().to_string()),
+        "subject" => Some(escape_html(&subject)),
+        other => lookup(other).map(escape_html),
+    });
+
+    RenderedEmail { subject, text, html }
+}
+
+/// Replace `{{name}}` placeholders in one pass. Unknown names are left as is.
+fn fill(source: &str, value: impl Fn(&str) -> Option<String>) -> String {
+    let mut out = String::with_capacity(source.len());
+    let mut rest = source;
+
+    while let Some(start) = rest.find("{{") {
+        out.push_str(&rest[..start]);
+        let after = &rest[start + 2..];
+        match after.find("}}") {
+            Some(end) => {
+                let name = after[..end].trim();
+                match value(name) {
+                    Some(v) => out.push_str(&v),
+                    None => out.push_str(&rest[start..start + 2 + end + 2]),
+                }
+                rest = &after[end + 2..];
+            }
+            None => {
+                out.push_str(&rest[start..]);
+                rest = "";
+            }
+        }
+    }
+    out.push_str(rest);
+    out
+}
+
+/// Escape text for HTML element content and quoted attribute values
+pub fn escape_html(value: &str) -> String {
+    let mut escaped = String::with_capacity(value.len());
+    for c in value.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&#39;"),
+            _ => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+/// Prefix every line with "> " for plain-text quoting
+pub fn quote_text(message: &str) -> String {
+    message
+        .lines()
+        .map(|line| format!("> {}", line))
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn branding() -> Branding {
+        Branding {
+            site_name: "Portfolio".to_string(),
+            site_url: "https://example.com".to_string(),
+        }
+    }
+
+    #[test]
+    fn escapes_user_input_in_html_only() {
+        let email = render(
+            EmailTemplate::ContactNotification,
+            Language::En,
+            &branding(),
+            &[
+                ("name", "<script>alert(1)</script>"),
+                ("email", "a@example.com"),
+                ("subject", "Hi\r\nBcc: x@example.com"),
+                ("message", "Tom & \"Jerry\" {{site_url}}"),
+                ("admin_url", "https://example.com/admin/inbox/1"),
+            ],
+        );
+
+        assert!(!email.html.contains("<script>"));
+        assert!(email.html.contains("&lt;script&gt;alert(1)&lt;/script&gt;"));
+        assert!(email.html.contains("Tom &amp; &quot;Jerry&quot;"));
+        assert!(email.text.contains("<script>alert(1)</script>"));
+        // Values are not expanded a second time
+        assert!(email.text.contains("{{site_url}}"));
+        assert!(!email.subject.contains('\n'));
+        assert_eq!(email.subject, "[Portfolio] Hi  Bcc: x@example.com");
+    }
+
+    #[test]
+    fn subject_values_cannot_reach_the_body() {
+        let email = render(
+            EmailTemplate::ContactReply,
+            Language::En,
+            &branding(),
+            &[("subject", "Hi\n\nInjected body"), ("body", "Thanks!")],
+        );
+
+        assert_eq!(email.subject, "Hi  Injected body");
+        assert!(!email.text.contains("Injected"));
+        assert!(email.text.contains("Thanks!"));
+    }
+
+    #[test]
+    fn every_template_fills_all_placeholders() {
+        let vars = [
+            ("name", "Ada"),
+            ("email", "ada@example.com"),
+            ("subject", "Hello"),
+            ("message", "Line one\nLine two"),
+            ("message_quoted", "> Line one\n> Line two"),
+            ("admin_url", "https://example.com/admin/inbox/1"),
+            ("response_days", "2"),
+            ("body", "Thanks!"),
+            ("link", "https://example.com/privacy/data?token=1"),
+            ("expires_minutes", "60"),
+        ];
+        for template in [
+            EmailTemplate::ContactNotification,
+            EmailTemplate::ContactAcknowledgement,
+            EmailTemplate::ContactReply,
+            EmailTemplate::ContactDataLink,
+        ] {
+            for language in [Language::En, Language::Fr] {
+                let email = render(template, language, &branding(), &vars);
+                assert!(!email.subject.is_empty(), "{template:?} {language:?}");
+                assert!(!email.text.contains("{{"), "{template:?} {language:?}");
+                assert!(!email.html.contains("{{"), "{template:?} {language:?}");
+                assert!(!email.html.contains(FOOTER_MARKER));
+            }
+        }
+    }
+
+    #[test]
+    fn acknowledgement_quotes_the_message_escaped() {
+        let message = "<a href=\"https://spam.example\">Win</a>\nSecond line";
+        for language in [Language::En, Language::Fr] {
+            let email = render(
+                EmailTemplate::ContactAcknowledgement,
+                language,
+                &branding(),
+                &[
+                    ("name", "Ada"),
+                    ("response_days", "2"),
+                    ("message", message),
+                    ("message_quoted", &quote_text(message)),
+                ],
+            );
+            assert!(
+                email
+                    .text
+                    .contains("> <a href=\"https://spam.example\">Win</a>\n> Second line"),
+                "{language:?}"
+            );
+            assert!(
+                !email.html.contains("<a href=\"https://spam"),
+                "{language:?}"
+            );
+            assert!(
+                email
+                    .html
+                    .contains("&lt;a href=&quot;https://spam.example&quot;&gt;Win&lt;/a&gt;"),
+                "{language:?}"
+            );
+        }
+    }
+}
@@ -0,0 +1,275 @@
+//! Mail transports
+//!
+//! `MAIL_TRANSPORT` selects where messages go:
+//! - `smtp` (default): `SMTP_HOST` with `SMTP_SECURITY` = `starttls`
+//!   (default), `tls` (implicit TLS) or `plain` (no encryption, for a local
+//!   MailHog-style server). Credentials are used when `SMTP_USER` and
+//!   `SMTP_PASSWORD` are both set.
+//! - `file`: writes each message into a maildir at `MAIL_DIR`
+//!   (default `./mail`)
+//! - `stdout`: prints each message and logs its envelope
+
+use std::future::Future;
+use std::path::{Path, PathBuf};
+use std::pin::Pin;
+
+use lettre::{
+    transport::smtp::authentication::Credentials, AsyncSmtpTransport, AsyncTransport, Message,
+    Tokio1Executor,
+};
+use uuid::Uuid;
+
+use super::EmailError;
+
+/// Future returned by [`MailTransport::send`]
+pub type SendFuture<'a> = Pin<Box<dyn Future<Output = Result<(), EmailError>> + Send + 'a>>;
+
+/// Somewhere to deliver a built message
+pub trait MailTransport: Send + Sync {
+    fn send(&self, message: Message) -> SendFuture<'_>;
+}
+
+/// SMTP connection security
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SmtpSecurity {
+    /// Plain connection upgraded with STARTTLS
+    StartTls,
+    /// TLS from the first byte (SMTPS)
+    Tls,
+    /// No encryption; only for a local test server
+    Plain,
+}
+
+impl SmtpSecurity {
+    /// Parse `SMTP_SECURITY`
+    pub fn parse(value: &str) -> Option<Self> {
+        match value.to_lowercase().as_str() {
+            "starttls" => Some(SmtpSecurity::StartTls),
+            "tls" | "smtps" => Some(SmtpSecurity::Tls),
+            "plain" | "none" => Some(SmtpSecurity::Plain),
+            _ => None,
+        }
+    }
+
+    /// Conventional port for this security mode
+    pub fn default_port(&self) -> u16 {
+        match self {
+            SmtpSecurity::StartTls => 587,
+            SmtpSecurity::Tls => 465,
+            SmtpSecurity::Plain => 25,
+        }
+    }
+}
+
+/// Transport settings loaded from environment variables
+#[derive(Debug, Clone)]
+pub enum TransportConfig {
+    Smtp {
+        host: String,
+        port: u16,
+        security: SmtpSecurity,
+        /// (user, password)
+        credentials: Option<(String, String)>,
+    },
+    File {
+        dir: PathBuf,
+    },
+    Stdout,
+}
+
+impl TransportConfig {
+    /// Load transport settings from environment variables.
+    /// Returns None if SMTP is selected but `SMTP_HOST` is missing or
+    /// `SMTP_SECURITY` is not a known mode.
+    pub fn from_env() -> Option<Self> {
+        match std::env::var("MAIL_TRANSPORT").as_deref() {
+            Ok("file") => Some(TransportConfig::File {
+                dir: std::env::var("MAIL_DIR")
+                    .unwrap_or_else(|_| "mail".to_string())
+                    .into(),
+            }),
+            Ok("stdout") => Some(TransportConfig::Stdout),
+            Ok("smtp") | Err(_) => {
+                let security = match std::env::var("SMTP_SECURITY") {
+                    Ok(value) => match SmtpSecurity::parse(&value) {
+                        Some(security) => security,
+                        None => {
+                            tracing::error!(
+                                security = value,
+                                "Unknown SMTP_SECURITY - email disabled"
+                            );
+                            return None;
+                        }
+                    },
+                    Err(_) => SmtpSecurity::StartTls,
+                };
+                Some(TransportConfig::Smtp {
+                    host: std::env::var("SMTP_HOST").ok()?,
+                    port: std::env::var("SMTP_PORT")
+                        .ok()
+                        .and_then(|p| p.parse().ok())
+                        .unwrap_or(security.default_port()),
+                    security,
+                    credentials: std::env::var("SMTP_USER")
+                        .ok()
+                        .zip(std::env::var("SMTP_PASSWORD").ok()),
+                })
+            }
+            Ok(other) => {
+                tracing::warn!(transport = other, "Unknown MAIL_TRANSPORT - email disabled");
+                None
+            }
+        }
+    }
+
+    /// Short description for startup logs (no credentials)
+    pub fn describe(&self) -> String {
+        match self {
+            TransportConfig::Smtp {
+                host,
+                port,
+                security,
+                ..
+            } => format!("smtp {host}:{port} ({security:?})"),
+            TransportConfig::File { dir } => format!("maildir {}", dir.display()),
+            TransportConfig::Stdout => "stdout".to_string(),
+        }
+    }
+}
+
+/// Build the transport described by the configuration
+pub fn mail_transport(config: &TransportConfig) -> Result<Box<dyn MailTransport>, EmailError> {
+    Ok(match config {
+        TransportConfig::Smtp {
+            host,
+            port,
+            security,
+            credentials,
+        } => {
+            let builder = match security {
+                SmtpSecurity::StartTls => {
+                    AsyncSmtpTransport::<Tokio1Executor>::starttls_relay(host)?
+                }
+                SmtpSecurity::Tls => AsyncSmtpTransport::<Tokio1Executor>::relay(host)?,
+                SmtpSecurity::Plain => {
+                    AsyncSmtpTransport::<Tokio1Executor>::builder_dangerous(host)
+                }
+            };
+            let builder = builder.port(*port);
+            let builder = match credentials {
+                Some((user, password)) => {
+                    builder.credentials(Credentials::new(user.clone(), password.clone()))
+                }
+                None => builder,
+            };
+            Box::new(SmtpTransport(builder.build()))
+        }
+        TransportConfig::File { dir } => Box::new(MaildirTransport::new(dir)),
+        TransportConfig::Stdout => Box::new(StdoutTransport),
+    })
+}
+
+/// SMTP relay
+struct SmtpTransport(AsyncSmtpTransport<Tokio1Executor>);
+
+impl MailTransport for SmtpTransport {
+    fn send(&self, message: Message) -> SendFuture<'_> {
+        Box::pin(async move {
+            self.0.send(message).await?;
+            Ok(())
+        })
+    }
+}
+
+/// Maildir sink: each message is written to `tmp/` then moved into `new/`,
+/// so readers never see a partial file
+pub struct MaildirTransport {
+    dir: PathBuf,
+}
+
+impl MaildirTransport {
+    pub fn new(dir: impl AsRef<Path>) -> Self {
+        Self {
+            dir: dir.as_ref().to_path_buf(),
+        }
+    }
+}
+
+impl MailTransport for MaildirTransport {
+    fn send(&self, message: Message) -> SendFuture<'_> {
+        let dir = self.dir.clone();
+        Box::pin(async move {
+            let contents = message.formatted();
+            let name = format!(
+                "{}.{}.portfolio.eml",
+                chrono::Utc::now().timestamp(),
+                Uuid::new_v4()
+            );
+
+            let path = tokio::task::spawn_blocking(move || -> std::io::Result<PathBuf> {
+                let tmp = dir.join("tmp");
+                let new = dir.join("new");
+                std::fs::create_dir_all(&tmp)?;
+                std::fs::create_dir_all(&new)?;
+                std::fs::create_dir_all(dir.join("cur"))?;
+
+                std::fs::write(tmp.join(&name), contents)?;
+                std::fs::rename(tmp.join(&name), new.join(&name))?;
+                Ok(new.join(name))
+            })
+            .await??;
+
+            tracing::info!(path = %path.display(), "Email written to maildir");
+            Ok(())
+        })
+    }
+}
+
+/// Prints messages to stdout, for local development
+struct StdoutTransport;
+
+impl MailTransport for StdoutTransport {
+    fn send(&self, message: Message) -> SendFuture<'_> {
+        Box::pin(async move {
+            let envelope = message.envelope();
+            tracing::info!(
+                from = ?envelope.from().map(|a| a.to_string()),
+                to = ?envelope.to().iter().map(|a| a.to_string()).collect::<Vec<_>>(),
+                "Email printed to stdout"
+            );
+            println!("{}", String::from_utf8_lossy(&message.formatted()));
+            Ok(())
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn maildir_transport_writes_into_new() {
+        let dir = std::env::temp_dir().join(format!("portfolio-maildir-{}", Uuid::new_v4()));
+        let message = Message::builder()
+            .from("noreply@example.com".parse().unwrap())
+            .to("owner@example.com".parse().unwrap())
+            .subject("Maildir test")
+            .body("Hello".to_string())
+            .unwrap();
+
+        let runtime = tokio::runtime::Builder::new_current_thread()
+            .build()
+            .unwrap();
+        runtime
+            .block_on(MaildirTransport::new(&dir).send(message))
+            .unwrap();
+
+        let files: Vec<_> = std::fs::read_dir(dir.join("new")).unwrap().collect();
+        assert_eq!(files.len(), 1);
+        let contents = std::fs::read_to_string(files[0].as_ref().unwrap().path()).unwrap();
+        assert!(contents.contains("Subject: Maildir test"));
+        assert_eq!(std::fs::read_dir(dir.join("tmp")).unwrap().count(), 0);
+
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+}
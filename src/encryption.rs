@@ -0,0 +1,659 @@
+//! Envelope encryption of contact submissions at rest
+//!
+//! Name, email, subject and message are serialized together and sealed
+//! with XChaCha20-Poly1305 under a random data key made for that row. The
+//! data key is stored wrapped (encrypted) under a master key, next to the
+//! master key's ID. Both layers take the submission ID as associated data,
+//! so sealed columns cannot be swapped between rows. Replies sent from the
+//! admin inbox are sealed with the data key of their submission, since
+//! they usually quote it.
+//!
+//! Master keys come from `CONTACT_ENCRYPTION_KEYS`: comma-separated
+//! `id:hex` entries (32-byte keys), current key first. Rotating only
+//! re-wraps the data keys: put the new key in front, run
+//! `portfolio reencrypt-contacts`, then drop the old entry. The same
+//! command seals rows and replies stored before encryption was introduced,
+//! and gives every row a sender address index under the current hash key.
+//!
+//! Only the admin inbox, the outbox worker that emails a submission to its
+//! owner and its sender, and the sender's own data export ever decrypt.
+
+use std::sync::Arc;
+
+use argon2::password_hash::rand_core::{OsRng, RngCore};
+use chacha20poly1305::aead::{Aead, KeyInit, Payload};
+use chacha20poly1305::{XChaCha20Poly1305, XNonce};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use crate::data_request::email_index;
+use crate::db::{self, ContactReply, ContactSubmission};
+use crate::ip_hash::IpHasher;
+
+/// XChaCha20 nonce length, stored in front of each ciphertext
+const NONCE_LEN: usize = 24;
+
+/// Used in development when `CONTACT_ENCRYPTION_KEYS` is unset
+const DEVELOPMENT_KEY_ID: &str = "dev";
+const DEVELOPMENT_KEY_SEED: &str = "portfolio-development-contact-key";
+
+/// Rows loaded per re-encryption batch
+const REENCRYPT_BATCH_SIZE: i64 = 100;
+
+/// The personal data of a submission, only ever held decrypted in memory
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ContactPii {
+    pub name: String,
+    pub email: String,
+    pub subject: Option<String>,
+    pub message: String,
+}
+
+/// Subject and body of an admin reply
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ReplyContent {
+    pub subject: String,
+    pub body: String,
+}
+
+/// Sealed personal data, as stored in `contact_submissions`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SealedPii {
+    /// Master key the data key is wrapped under
+    pub key_id: String,
+    /// Nonce and encrypted data key
+    pub wrapped_dek: Vec<u8>,
+    /// Nonce and encrypted `ContactPii`
+    pub ciphertext: Vec<u8>,
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum EncryptionError {
+    #[error("Unknown encryption key {0}")]
+    UnknownKey(String),
+
+    #[error("Submission {0} has no sealed or plaintext data")]
+    Missing(Uuid),
+
+    #[error("Submission {0} failed to decrypt")]
+    Decrypt(Uuid),
+
+    #[error("Submission {0} is not sealed yet")]
+    NotSealed(Uuid),
+}
+
+#[derive(Clone)]
+struct MasterKey {
+    id: String,
+    cipher: XChaCha20Poly1305,
+    /// Fingerprint, to spot the development key
+    fingerprint: [u8; 32],
+}
+
+/// Master keys, shared through the Leptos context
+#[derive(Clone)]
+pub struct ContactCipher {
+    /// Current key first
+    keys: Arc<[MasterKey]>,
+}
+
+impl std::fmt::Debug for ContactCipher {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let ids: Vec<&str> = self.keys.iter().map(|key| key.id.as_str()).collect();
+        f.debug_struct("ContactCipher")
+            .field("key_ids", &ids)
+            .finish()
+    }
+}
+
+impl ContactCipher {
+    /// Parse `id:hex` entries, current key first
+    pub fn parse(value: &str) -> Result<Self, String> {
+        let mut keys: Vec<MasterKey> = Vec::new();
+        for entry in value.split(',').map(str::trim).filter(|e| !e.is_empty()) {
+            let (id, hex) = entry
+                .split_once(':')
+                .ok_or("entries must look like `2025-01:<64 hex digits>`")?;
+            let id_ok = !id.is_empty()
+                && id.len() <= 32
+                && id
+                    .chars()
+                    .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_');
+            if !id_ok {
+                return Err(format!("invalid key ID `{id}`"));
+            }
+            let key = decode_key(hex).ok_or_else(|| format!("key {id} is not 64 hex digits"))?;
+            if keys.iter().any(|k| k.id == id) {
+                return Err(format!("key {id} appears twice"));
+            }
+            keys.push(MasterKey::new(id, &key));
+        }
+        if keys.is_empty() {
+            return Err("no keys".to_string());
+        }
+        Ok(Self { keys: keys.into() })
+    }
+
+    /// Load `CONTACT_ENCRYPTION_KEYS`. Outside production the fixed
+    /// development key is used when it is unset, and stays readable (so
+    /// local data can be re-encrypted) when it is; production refuses to
+    /// start without keys, or with the development key.
+    pub fn from_env(production: bool) -> Result<Self, String> {
+        let value = std::env::var("CONTACT_ENCRYPTION_KEYS").unwrap_or_default();
+        let development = MasterKey::new(DEVELOPMENT_KEY_ID, &development_key());
+        if value.trim().is_empty() {
+            if production {
+                return Err("CONTACT_ENCRYPTION_KEYS must be set in production".to_string());
+            }
+            tracing::warn!("CONTACT_ENCRYPTION_KEYS not set - using the public development key");
+            return Ok(Self {
+                keys: vec![development].into(),
+            });
+        }
+
+        let cipher = Self::parse(&value)?;
+        let uses_development_key = cipher
+            .keys
+            .iter()
+            .any(|k| k.id == development.id || k.fingerprint == development.fingerprint);
+        if production && uses_development_key {
+            return Err("the development key cannot be used in production".to_string());
+        }
+        if production || uses_development_key {
+            return Ok(cipher);
+        }
+        let keys: Vec<MasterKey> = cipher.keys.iter().cloned().chain([development]).collect();
+        Ok(Self { keys: keys.into() })
+    }
+
+    /// ID of the key new rows are sealed under
+    pub fn current_key_id(&self) -> &str {
+        &self.keys[0].id
+    }
+
+    fn key(&self, id: &str) -> Result<&MasterKey, EncryptionError> {
+        self.keys
+            .iter()
+            .find(|key| key.id == id)
+            .ok_or_else(|| EncryptionError::UnknownKey(id.to_string()))
+    }
+
+    /// Seal the personal data of submission `id` under the current key
+    pub fn seal(&self, id: Uuid, pii: &ContactPii) -> SealedPii {
+        let mut dek = [0u8; 32];
+        OsRng.fill_bytes(&mut dek);
+        let plaintext = serde_json::to_vec(pii).expect("ContactPii serializes");
+        let ciphertext = encrypt(&data_cipher(&dek), &pii_aad(id), &plaintext);
+
+        let current = &self.keys[0];
+        SealedPii {
+            key_id: current.id.clone(),
+            wrapped_dek: encrypt(&current.cipher, &dek_aad(id, &current.id), &dek),
+            ciphertext,
+        }
+    }
+
+    /// Decrypt the personal data of a stored submission. Rows stored before
+    /// encryption are read from their plaintext columns.
+    pub fn open(&self, row: &ContactSubmission) -> Result<ContactPii, EncryptionError> {
+        match (&row.key_id, &row.wrapped_dek, &row.sealed_pii) {
+            (Some(key_id), Some(wrapped_dek), Some(ciphertext)) => {
+                let dek = self.unwrap_dek(row.id, key_id, wrapped_dek)?;
+                let plaintext = decrypt(&data_cipher(&dek), &pii_aad(row.id), ciphertext)
+                    .ok_or(EncryptionError::Decrypt(row.id))?;
+                serde_json::from_slice(&plaintext).map_err(|_| EncryptionError::Decrypt(row.id))
+            }
+            _ => Ok(ContactPii {
+                name: row.name.clone().ok_or(EncryptionError::Missing(row.id))?,
+                email: row.email.clone().ok_or(EncryptionError::Missing(row.id))?,
+                subject: row.subject.clone(),
+                message: row
+                    .message
+                    .clone()
+                    .ok_or(EncryptionError::Missing(row.id))?,
+            }),
+        }
+    }
+
+    /// Seal a reply to `submission` with the submission's data key. Rows
+    /// stored before encryption have none; seal them first.
+    pub fn seal_reply(
+        &self,
+        submission: &ContactSubmission,
+        reply_id: Uuid,
+        content: &ReplyContent,
+    ) -> Result<Vec<u8>, EncryptionError> {
+        let dek = self.submission_dek(submission)?;
+        let plaintext = serde_json::to_vec(content).expect("ReplyContent serializes");
+        Ok(encrypt(
+            &data_cipher(&dek),
+            &reply_aad(submission.id, reply_id),
+            &plaintext,
+        ))
+    }
+
+    /// Decrypt a stored reply to `submission`. Replies stored before
+    /// encryption are read from their plaintext columns.
+    pub fn open_reply(
+        &self,
+        submission: &ContactSubmission,
+        reply: &ContactReply,
+    ) -> Result<ReplyContent, EncryptionError> {
+        let Some(sealed) = &reply.sealed_content else {
+            return Ok(ReplyContent {
+                subject: reply.subject.clone().unwrap_or_default(),
+                body: reply
+                    .body
+                    .clone()
+                    .ok_or(EncryptionError::Missing(submission.id))?,
+            });
+        };
+        let dek = self.submission_dek(submission)?;
+        let plaintext = decrypt(
+            &data_cipher(&dek),
+            &reply_aad(submission.id, reply.id),
+            sealed,
+        )
+        .ok_or(EncryptionError::Decrypt(submission.id))?;
+        serde_json::from_slice(&plaintext).map_err(|_| EncryptionError::Decrypt(submission.id))
+    }
+
+    fn submission_dek(&self, row: &ContactSubmission) -> Result<[u8; 32], EncryptionError> {
+        match (&row.key_id, &row.wrapped_dek) {
+            (Some(key_id), Some(wrapped_dek)) => self.unwrap_dek(row.id, key_id, wrapped_dek),
+            _ => Err(EncryptionError::NotSealed(row.id)),
+        }
+    }
+
+    /// Wrap the data key of `sealed` under the current key. The data itself
+    /// is not touched.
+    pub fn rewrap(&self, id: Uuid, sealed: SealedPii) -> Result<SealedPii, EncryptionError> {
+        let dek = self.unwrap_dek(id, &sealed.key_id, &sealed.wrapped_dek)?;
+        let current = &self.keys[0];
+        Ok(SealedPii {
+            key_id: current.id.clone(),
+            wrapped_dek: encrypt(&current.cipher, &dek_aad(id, &current.id), &dek),
+            ciphertext: sealed.ciphertext,
+        })
+    }
+
+    fn unwrap_dek(
+        &self,
+        id: Uuid,
+        key_id: &str,
+        wrapped: &[u8],
+    ) -> Result<[u8; 32], EncryptionError> {
+        let key = self.key(key_id)?;
+        decrypt(&key.cipher, &dek_aad(id, key_id), wrapped)
+            .and_then(|dek| <[u8; 32]>::try_from(dek).ok())
+            .ok_or(EncryptionError::Decrypt(id))
+    }
+}
+
+impl MasterKey {
+    fn new(id: &str, key: &[u8; 32]) -> Self {
+        Self {
+            id: id.to_string(),
+            cipher: XChaCha20Poly1305::new(key.into()),
+            fingerprint: Sha256::digest(key).into(),
+        }
+    }
+}
+
+fn development_key() -> [u8; 32] {
+    Sha256::digest(DEVELOPMENT_KEY_SEED.as_bytes()).into()
+}
+
+fn decode_key(hex: &str) -> Option<[u8; 32]> {
+    let hex = hex.trim();
+    if hex.len() != 64 || !hex.is_ascii() {
+        return None;
+    }
+    let mut key = [0u8; 32];
+    for (i, byte) in key.iter_mut().enumerate() {
+        *byte = u8::from_str_radix(&hex[i * 2..i * 2 + 2], 16).ok()?;
+    }
+    Some(key)
+}
+
+fn data_cipher(dek: &[u8; 32]) -> XChaCha20Poly1305 {
+    XChaCha20Poly1305::new(dek.into())
+}
+
+fn pii_aad(id: Uuid) -> String {
+    format!("contact-pii:{id}")
+}
+
+fn reply_aad(submission_id: Uuid, reply_id: Uuid) -> String {
+    format!("contact-reply:{submission_id}:{reply_id}")
+}
+
+fn dek_aad(id: Uuid, key_id: &str) -> String {
+    format!("contact-dek:{id}:{key_id}")
+}
+
+/// Encrypt with a random nonce, returned in front of the ciphertext
+fn encrypt(cipher: &XChaCha20Poly1305, aad: &str, plaintext: &[u8]) -> Vec<u8> {
+    let mut nonce = [0u8; NONCE_LEN];
+    OsRng.fill_bytes(&mut nonce);
+    let ciphertext = cipher
+        .encrypt(
+            XNonce::from_slice(&nonce),
+            Payload {
+                msg: plaintext,
+                aad: aad.as_bytes(),
+            },
+        )
+        .expect("XChaCha20-Poly1305 encrypts any length we store");
+    [nonce.as_slice(), &ciphertext].concat()
+}
+
+fn decrypt(cipher: &XChaCha20Poly1305, aad: &str, sealed: &[u8]) -> Option<Vec<u8>> {
+    if sealed.len() < NONCE_LEN {
+        return None;
+    }
+    let (nonce, ciphertext) = sealed.split_at(NONCE_LEN);
+    cipher
+        .decrypt(
+            XNonce::from_slice(nonce),
+            Payload {
+                msg: ciphertext,
+                aad: aad.as_bytes(),
+            },
+        )
+        .ok()
+}
+
+/// Outcome of `reencrypt_contacts`
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ReencryptReport {
+    /// Plaintext rows sealed for the first time
+    pub sealed: u64,
+    /// Rows whose data key moved to the current key
+    pub rewrapped: u64,
+    /// Rows already under the current key whose email index was missing or
+    /// made with an older hash key
+    pub reindexed: u64,
+    /// Replies stored in plaintext, now sealed
+    pub replies_sealed: u64,
+    /// Rows left as they were (unknown key or corrupt data, logged)
+    pub failed: u64,
+}
+
+/// Seal plaintext submissions and replies, re-wrap every data key that is
+/// not under the current key, and index the sender address under the
+/// current hash key (see `crate::data_request`). Safe to run again; rows
+/// that fail are logged and kept.
+pub async fn reencrypt_contacts(
+    pool: &PgPool,
+    cipher: &ContactCipher,
+    hasher: &IpHasher,
+) -> Result<ReencryptReport, sqlx::Error> {
+    let mut report = ReencryptReport::default();
+    let mut after = Uuid::nil();
+
+    loop {
+        let rows = db::list_contacts_to_reencrypt(
+            pool,
+            cipher.current_key_id(),
+            hasher.current_version(),
+            after,
+            REENCRYPT_BATCH_SIZE,
+        )
+        .await?;
+        let Some(last) = rows.last() else {
+            return Ok(report);
+        };
+        after = last.id;
+
+        for row in rows {
+            let result = cipher.open(&row).and_then(|pii| {
+                let sealed = match (
+                    row.key_id.clone(),
+                    row.wrapped_dek.clone(),
+                    row.sealed_pii.clone(),
+                ) {
+                    (Some(key_id), Some(wrapped_dek), Some(ciphertext)) => {
+                        let sealed = SealedPii {
+                            key_id,
+                            wrapped_dek,
+                            ciphertext,
+                        };
+                        if sealed.key_id == cipher.current_key_id() {
+                            sealed
+                        } else {
+                            cipher.rewrap(row.id, sealed)?
+                        }
+                    }
+                    _ => cipher.seal(row.id, &pii),
+                };
+                Ok((sealed, email_index(hasher, &pii.email)))
+            });
+
+            match result {
+                Ok((sealed, index)) => {
+                    db::store_sealed_contact(pool, row.id, &sealed, &index).await?;
+                    if row.sealed_pii.is_none() {
+                        report.sealed += 1;
+                    } else if row.key_id.as_deref() != Some(cipher.current_key_id()) {
+                        report.rewrapped += 1;
+                    } else if row.email_index.as_deref() != Some(index.as_str()) {
+                        report.reindexed += 1;
+                    }
+                    let sealed_row = ContactSubmission {
+                        key_id: Some(sealed.key_id),
+                        wrapped_dek: Some(sealed.wrapped_dek),
+                        sealed_pii: Some(sealed.ciphertext),
+                        ..row
+                    };
+                    report.replies_sealed += seal_replies(pool, cipher, &sealed_row).await?;
+                }
+                Err(e) => {
+                    tracing::error!(%e, "Could not re-encrypt submission");
+                    report.failed += 1;
+                }
+            }
+        }
+    }
+}
+
+/// Seal the plaintext replies of a sealed submission. Returns how many
+/// were sealed; replies that fail are logged and kept.
+async fn seal_replies(
+    pool: &PgPool,
+    cipher: &ContactCipher,
+    submission: &ContactSubmission,
+) -> Result<u64, sqlx::Error> {
+    let mut sealed = 0;
+    for reply in db::list_contact_replies(pool, submission.id).await? {
+        if reply.sealed_content.is_some() {
+            continue;
+        }
+        let result = cipher
+            .open_reply(submission, &reply)
+            .and_then(|content| cipher.seal_reply(submission, reply.id, &content));
+        match result {
+            Ok(content) => {
+                db::store_sealed_reply(pool, reply.id, &content).await?;
+                sealed += 1;
+            }
+            Err(e) => tracing::error!(%e, reply_id = %reply.id, "Could not seal reply"),
+        }
+    }
+    Ok(sealed)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const KEY_1: &str = "1111111111111111111111111111111111111111111111111111111111111111";
+    const KEY_2: &str = "2222222222222222222222222222222222222222222222222222222222222222";
+
+    fn pii() -> ContactPii {
+        ContactPii {
+            name: "Ada Lovelace".to_string(),
+            email: "ada@example.com".to_string(),
+            subject: Some("Analytical engine".to_string()),
+            message: "Could we talk about the engine?".to_string(),
+        }
+    }
+
+    fn stored(id: Uuid, sealed: &SealedPii) -> ContactSubmission {
+        ContactSubmission {
+            id,
+            name: None,
+            email: None,
+            subject: None,
+            message: None,
+            key_id: Some(sealed.key_id.clone()),
+            wrapped_dek: Some(sealed.wrapped_dek.clone()),
+            sealed_pii: Some(sealed.ciphertext.clone()),
+            email_index: None,
+            honeypot_filled: Some(false),
+            ip_hash: None,
+            user_agent: None,
+            spam_score: None,
+            spam_rules: None,
+            message_hash: None,
+            status: "pending".to_string(),
+            read_at: None,
+            replied_at: None,
+            created_at: chrono::Utc::now(),
+            updated_at: chrono::Utc::now(),
+        }
+    }
+
+    #[test]
+    fn stored_columns_hold_no_plaintext() {
+        let cipher = ContactCipher::parse(&format!("k1:{KEY_1}")).unwrap();
+        let id = Uuid::new_v4();
+        let sealed = cipher.seal(id, &pii());
+
+        // Everything that is bound to the INSERT
+        let stored_bytes = [
+            sealed.key_id.as_bytes(),
+            &sealed.wrapped_dek,
+            &sealed.ciphertext,
+        ]
+        .concat();
+        for secret in ["Ada", "ada@example.com", "example", "Analytical", "engine"] {
+            assert!(
+                !stored_bytes
+                    .windows(secret.len())
+                    .any(|w| w == secret.as_bytes()),
+                "{secret} found in stored bytes"
+            );
+        }
+        // Fresh data key and nonces every time
+        assert_ne!(cipher.seal(id, &pii()), sealed);
+
+        assert_eq!(cipher.open(&stored(id, &sealed)).unwrap(), pii());
+    }
+
+    #[test]
+    fn sealed_rows_are_bound_to_their_id_and_key() {
+        let cipher = ContactCipher::parse(&format!("k1:{KEY_1}")).unwrap();
+        let id = Uuid::new_v4();
+        let sealed = cipher.seal(id, &pii());
+
+        // Copied onto another row
+        assert!(cipher.open(&stored(Uuid::new_v4(), &sealed)).is_err());
+
+        let mut tampered = sealed.clone();
+        *tampered.ciphertext.last_mut().unwrap() ^= 1;
+        assert!(cipher.open(&stored(id, &tampered)).is_err());
+
+        let other = ContactCipher::parse(&format!("k1:{KEY_2}")).unwrap();
+        assert!(other.open(&stored(id, &sealed)).is_err());
+    }
+
+    #[test]
+    fn rotation_rewraps_the_data_key() {
+        let old = ContactCipher::parse(&format!("k1:{KEY_1}")).unwrap();
+        let rotating = ContactCipher::parse(&format!("k2:{KEY_2}, k1:{KEY_1}")).unwrap();
+        let rotated = ContactCipher::parse(&format!("k2:{KEY_2}")).unwrap();
+        let id = Uuid::new_v4();
+
+        let sealed = old.seal(id, &pii());
+        assert!(rotated.open(&stored(id, &sealed)).is_err());
+
+        assert_eq!(rotating.current_key_id(), "k2");
+        let rewrapped = rotating.rewrap(id, sealed.clone()).unwrap();
+        assert_eq!(rewrapped.key_id, "k2");
+        assert_eq!(rewrapped.ciphertext, sealed.ciphertext);
+        assert_eq!(rotated.open(&stored(id, &rewrapped)).unwrap(), pii());
+    }
+
+    #[sqlx::test]
+    async fn replies_are_sealed_with_their_submission(pool: PgPool) {
+        use crate::db::{NewContactReply, NewContactSubmission};
+
+        let cipher = ContactCipher::parse(&format!("k1:{KEY_1}")).unwrap();
+        let hasher = IpHasher::parse("1:first-secret-value").unwrap();
+        let id = Uuid::new_v4();
+        let submission = NewContactSubmission {
+            id,
+            pii: cipher.seal(id, &pii()),
+            email_index: email_index(&hasher, &pii().email),
+            honeypot_filled: false,
+            ip_hash: None,
+            user_agent: None,
+            status: db::ContactStatus::Pending,
+            spam_score: None,
+            spam_rules: None,
+            message_hash: None,
+        };
+        db::insert_contact(&pool, submission, &[], None, None)
+            .await
+            .unwrap();
+        let row = db::get_contact(&pool, id).await.unwrap().unwrap();
+
+        let content = ReplyContent {
+            subject: "Re: Analytical engine".to_string(),
+            body: "Gladly.\n\n> Could we talk about the engine?".to_string(),
+        };
+        let reply_id = Uuid::new_v4();
+        let sealed_content = cipher.seal_reply(&row, reply_id, &content).unwrap();
+        let reply = NewContactReply {
+            id: reply_id,
+            submission_id: id,
+            sealed_content,
+            message_id: "<reply@example.com>".to_string(),
+            sent_by: "admin".to_string(),
+        };
+        db::insert_contact_reply(&pool, reply).await.unwrap();
+
+        let saved = &db::list_contact_replies(&pool, id).await.unwrap()[0];
+        assert_eq!((&saved.subject, &saved.body), (&None, &None));
+        let sealed = saved.sealed_content.as_deref().unwrap();
+        assert!(!sealed.windows(6).any(|w| w == b"engine"));
+        assert_eq!(cipher.open_reply(&row, saved).unwrap(), content);
+        // Bound to its submission
+        let other = Uuid::new_v4();
+        let other_row = stored(other, &cipher.seal(other, &pii()));
+        assert!(cipher.open_reply(&other_row, saved).is_err());
+
+        // A reply stored before encryption is sealed by reencrypt-contacts
+        sqlx::query(
+            "INSERT INTO contact_replies (submission_id, subject, body, message_id, sent_by)
+             VALUES ($1, 'Re: old', 'Plaintext reply', '<old@example.com>', 'admin')",
+        )
+        .bind(id)
+        .execute(&pool)
+        .await
+        .unwrap();
+        let report = reencrypt_contacts(&pool, &cipher, &hasher).await.unwrap();
+        assert_eq!((report.replies_sealed, report.failed), (1, 0));
+        let replies = db::list_contact_replies(&pool, id).await.unwrap();
+        assert!(replies
+            .iter()
+            .all(|r| r.sealed_content.is_some() && r.body.is_none()));
+        assert_eq!(
+            cipher.open_reply(&row, &replies[1]).unwrap().body,
+            "Plaintext reply"
+        );
+    }
+}
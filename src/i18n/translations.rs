@@ -69,7 +69,47 @@ pub struct Translations {
     pub contact_error_message: &'static str,
     pub contact_error_subject: &'static str,
     pub contact_error_rate_limit: &'static str,
+    pub contact_error_expired: &'static str,
     pub contact_error_generic: &'static str,
+    pub contact_privacy_link: &'static str,
+
+    // Privacy page
+    pub privacy_page_title: &'static str,
+    pub privacy_page_subtitle: &'static str,
+    pub privacy_contact_title: &'static str,
+    pub privacy_contact_text: &'static str,
+    pub privacy_retention_title: &'static str,
+    pub privacy_retention_text: &'static str,
+    pub privacy_retention_pending: &'static str,
+    pub privacy_retention_read: &'static str,
+    pub privacy_retention_replied: &'static str,
+    pub privacy_retention_spam: &'static str,
+    pub privacy_retention_quarantine: &'static str,
+    pub privacy_retention_days: &'static str,
+    pub privacy_retention_forever: &'static str,
+    pub privacy_analytics_title: &'static str,
+    pub privacy_analytics_text: &'static str,
+    pub privacy_rights_title: &'static str,
+    pub privacy_rights_text: &'static str,
+    pub privacy_request_button: &'static str,
+    pub privacy_requesting: &'static str,
+    pub privacy_request_sent: &'static str,
+    pub privacy_error_email: &'static str,
+    pub privacy_error_rate_limit: &'static str,
+
+    // Privacy page - Data link
+    pub privacy_data_title: &'static str,
+    pub privacy_data_loading: &'static str,
+    pub privacy_data_invalid: &'static str,
+    pub privacy_data_count: &'static str,
+    pub privacy_data_empty: &'static str,
+    pub privacy_data_no_subject: &'static str,
+    pub privacy_data_download: &'static str,
+    pub privacy_data_erase: &'static str,
+    pub privacy_data_erase_warning: &'static str,
+    pub privacy_data_erase_confirm: &'static str,
+    pub privacy_data_cancel: &'static str,
+    pub privacy_data_erased: &'static str,
 
     // CV Page - Hero & Summary
     pub cv_subtitle: &'static str,
@@ -260,7 +300,47 @@ impl Translations {
             contact_error_message: "Please enter a message (max 5000 characters).",
             contact_error_subject: "Subject is too long (max 500 characters).",
             contact_error_rate_limit: "Too many messages. Please try again later.",
+            contact_error_expired: "This form has expired. Please send your message again.",
             contact_error_generic: "An error occurred. Please try again.",
+            contact_privacy_link: "How your data is handled",
+
+            // Privacy page
+            privacy_page_title: "Privacy",
+            privacy_page_subtitle: "What this site keeps about you, and how to get it back or erase it",
+            privacy_contact_title: "Contact form",
+            privacy_contact_text: "When you write through the contact form, your name, email address, subject and message are stored encrypted, and only used to answer you. A keyed hash of your IP address and your browser's user agent are kept with them to fight spam. Nothing is shared with third parties.",
+            privacy_retention_title: "How long messages are kept",
+            privacy_retention_text: "Messages are deleted automatically, together with the replies sent to them, once they are older than:",
+            privacy_retention_pending: "Unread messages",
+            privacy_retention_read: "Read messages",
+            privacy_retention_replied: "Answered messages",
+            privacy_retention_spam: "Messages filed as spam",
+            privacy_retention_quarantine: "Messages caught by the bot trap",
+            privacy_retention_days: "days",
+            privacy_retention_forever: "kept until you erase them",
+            privacy_analytics_title: "Analytics",
+            privacy_analytics_text: "Visits are counted without cookies. A visitor is only recognised within one day, through a keyed hash of the IP address and user agent that changes daily, and raw IP addresses are never stored. Browsers that send Do Not Track or Global Privacy Control are not counted at all.",
+            privacy_rights_title: "Your messages",
+            privacy_rights_text: "Enter the address you wrote from. If messages from it are stored, a link will be emailed to it, valid for one hour, to download them or erase them.",
+            privacy_request_button: "Email me a link",
+            privacy_requesting: "Sending...",
+            privacy_request_sent: "If messages from this address are stored, a link is on its way. Check your inbox.",
+            privacy_error_email: "Please enter a valid email address.",
+            privacy_error_rate_limit: "Too many requests. Please try again later.",
+
+            // Privacy page - Data link
+            privacy_data_title: "Your messages",
+            privacy_data_loading: "Loading...",
+            privacy_data_invalid: "This link is invalid or has expired. You can ask for a new one on the privacy page.",
+            privacy_data_count: "Messages stored for your address:",
+            privacy_data_empty: "No messages are stored for your address.",
+            privacy_data_no_subject: "(no subject)",
+            privacy_data_download: "Download as JSON",
+            privacy_data_erase: "Erase my messages",
+            privacy_data_erase_warning: "This permanently deletes these messages and the replies sent to them.",
+            privacy_data_erase_confirm: "Yes, erase them",
+            privacy_data_cancel: "Cancel",
+            privacy_data_erased: "Your messages have been erased.",
 
             // CV Page - Hero & Summary
             cv_subtitle: "Project Manager: Infrastructure & Solutions",
@@ -443,7 +523,47 @@ impl Translations {
             contact_error_message: "Veuillez entrer un message (max 5000 caracteres).",
             contact_error_subject: "Le sujet est trop long (max 500 caracteres).",
             contact_error_rate_limit: "Trop de messages. Veuillez reessayer plus tard.",
+            contact_error_expired: "Ce formulaire a expire. Veuillez renvoyer votre message.",
             contact_error_generic: "Une erreur s'est produite. Veuillez reessayer.",
+            contact_privacy_link: "Comment vos données sont traitées",
+
+            // Privacy page
+            privacy_page_title: "Confidentialité",
+            privacy_page_subtitle: "Ce que ce site conserve à votre sujet, et comment le récupérer ou l'effacer",
+            privacy_contact_title: "Formulaire de contact",
+            privacy_contact_text: "Lorsque vous écrivez via le formulaire de contact, votre nom, votre adresse e-mail, le sujet et le message sont stockés chiffrés, et servent uniquement à vous répondre. Un hash à clé de votre adresse IP et l'agent utilisateur de votre navigateur sont conservés avec eux pour lutter contre le spam. Rien n'est partagé avec des tiers.",
+            privacy_retention_title: "Durée de conservation des messages",
+            privacy_retention_text: "Les messages sont supprimés automatiquement, avec les réponses qui y ont été envoyées, une fois plus anciens que :",
+            privacy_retention_pending: "Messages non lus",
+            privacy_retention_read: "Messages lus",
+            privacy_retention_replied: "Messages ayant reçu une réponse",
+            privacy_retention_spam: "Messages classés comme spam",
+            privacy_retention_quarantine: "Messages pris au piège anti-robots",
+            privacy_retention_days: "jours",
+            privacy_retention_forever: "conservés jusqu'à ce que vous les effaciez",
+            privacy_analytics_title: "Statistiques",
+            privacy_analytics_text: "Les visites sont comptées sans cookies. Un visiteur n'est reconnu que sur une journée, grâce à un hash à clé de l'adresse IP et de l'agent utilisateur qui change chaque jour, et les adresses IP ne sont jamais stockées. Les navigateurs qui envoient Do Not Track ou Global Privacy Control ne sont pas comptés du tout.",
+            privacy_rights_title: "Vos messages",
+            privacy_rights_text: "Saisissez l'adresse depuis laquelle vous avez écrit. Si des messages envoyés depuis celle-ci sont stockés, un lien valable une heure y sera envoyé pour les télécharger ou les effacer.",
+            privacy_request_button: "M'envoyer un lien",
+            privacy_requesting: "Envoi en cours...",
+            privacy_request_sent: "Si des messages de cette adresse sont stockés, un lien est en route. Consultez votre boîte de réception.",
+            privacy_error_email: "Veuillez entrer une adresse email valide.",
+            privacy_error_rate_limit: "Trop de demandes. Veuillez réessayer plus tard.",
+
+            // Privacy page - Data link
+            privacy_data_title: "Vos messages",
+            privacy_data_loading: "Chargement...",
+            privacy_data_invalid: "Ce lien est invalide ou a expiré. Vous pouvez en demander un nouveau sur la page de confidentialité.",
+            privacy_data_count: "Messages stockés pour votre adresse :",
+            privacy_data_empty: "Aucun message n'est stocké pour votre adresse.",
+            privacy_data_no_subject: "(sans sujet)",
+            privacy_data_download: "Télécharger en JSON",
+            privacy_data_erase: "Effacer mes messages",
+            privacy_data_erase_warning: "Cela supprime définitivement ces messages et les réponses qui y ont été envoyées.",
+            privacy_data_erase_confirm: "Oui, les effacer",
+            privacy_data_cancel: "Annuler",
+            privacy_data_erased: "Vos messages ont été effacés.",
 
             // CV Page - Hero & Summary
             cv_subtitle: "Chef de Projet : Infrastructure & Solutions",
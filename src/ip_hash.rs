@@ -0,0 +1,190 @@
+//! Keyed hashing of client IPs and other identifiers kept only as hashes
+//!
+//! Hashes are HMAC-SHA256 under a secret key, stored as `v{version}:{hex}`.
+//! The purpose (`"contact"`, a rate limit policy name, ...) is part of the
+//! MAC input, so the same IP hashes differently in each table.
+//!
+//! Keys come from `IP_HASH_KEYS`: comma-separated `version:secret` entries,
+//! current key first. To rotate, put a new version in front and keep the old
+//! entry until the longest rate limit window has passed; Postgres counters
+//! found under an old key are moved to the new one (see
+//! `crate::db::record_rate_limit_hit`). In production the server refuses to
+//! start without keys, or with a short or publicly known secret.
+
+use std::sync::Arc;
+
+use hmac::{Hmac, Mac};
+use sha2::Sha256;
+
+/// Shortest secret accepted in production
+pub const MIN_SECRET_BYTES: usize = 16;
+
+/// Key used in development when `IP_HASH_KEYS` is unset
+const DEVELOPMENT_SECRET: &str = "portfolio-development-ip-hash-key";
+
+/// Secrets that have appeared in the source (the old salt defaults)
+const PUBLIC_SECRETS: &[&str] = &[
+    DEVELOPMENT_SECRET,
+    "portfolio-contact-salt-2025",
+    "portfolio-admin-salt-2025",
+    "portfolio-analytics-salt-2025",
+    "change-me",
+];
+
+/// Refuse a production secret shorter than `MIN_SECRET_BYTES` or published
+/// in the source. Also used for the other signing keys.
+pub fn check_secret(secret: &[u8]) -> Result<(), String> {
+    if secret.len() < MIN_SECRET_BYTES {
+        return Err(format!("shorter than {MIN_SECRET_BYTES} bytes"));
+    }
+    if PUBLIC_SECRETS.iter().any(|s| s.as_bytes() == secret) {
+        return Err("a public default".to_string());
+    }
+    Ok(())
+}
+
+#[derive(Clone)]
+struct HashKey {
+    version: u32,
+    secret: Arc<[u8]>,
+}
+
+/// Versioned HMAC keys, shared through the Leptos context
+#[derive(Clone)]
+pub struct IpHasher {
+    /// Current key first
+    keys: Arc<[HashKey]>,
+}
+
+impl std::fmt::Debug for IpHasher {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let versions: Vec<u32> = self.keys.iter().map(|key| key.version).collect();
+        f.debug_struct("IpHasher").field("versions", &versions).finish()
+    }
+}
+
+impl IpHasher {
+    /// Parse `version:secret` entries, current key first
+    pub fn parse(value: &str) -> Result<Self, String> {
+        let mut keys: Vec<HashKey> = Vec::new();
+        for entry in value.split(',').map(str::trim).filter(|e| !e.is_empty()) {
+            let (version, secret) = entry
+                .split_once(':')
+                .and_then(|(version, secret)| Some((version.trim().parse().ok()?, secret)))
+                .ok_or("entries must look like `2:secret`")?;
+            if secret.is_empty() {
+                return Err(format!("key version {version} has an empty secret"));
+            }
+            if keys.iter().any(|key| key.version == version) {
+                return Err(format!("key version {version} appears twice"));
+            }
+            keys.push(HashKey {
+                version,
+                secret: secret.as_bytes().into(),
+            });
+        }
+        if keys.is_empty() {
+            return Err("no keys".to_string());
+        }
+        Ok(Self { keys: keys.into() })
+    }
+
+    /// Load `IP_HASH_KEYS`. Outside production a fixed development key is
+    /// used when it is unset; in production, keys must be set, at least
+    /// `MIN_SECRET_BYTES` long and not one of the defaults from the source.
+    pub fn from_env(production: bool) -> Result<Self, String> {
+        let value = std::env::var("IP_HASH_KEYS").unwrap_or_default();
+        if value.trim().is_empty() {
+            if production {
+                return Err("IP_HASH_KEYS must be set in production".to_string());
+            }
+            tracing::warn!("IP_HASH_KEYS not set - using the public development key");
+            return Self::parse(&format!("0:{DEVELOPMENT_SECRET}"));
+        }
+
+        let hasher = Self::parse(&value)?;
+        if production {
+            hasher.check_secrets()?;
+        }
+        Ok(hasher)
+    }
+
+    /// Refuse short secrets and the defaults published in the source
+    fn check_secrets(&self) -> Result<(), String> {
+        for key in self.keys.iter() {
+            check_secret(&key.secret)
+                .map_err(|e| format!("key version {} is {e}", key.version))?;
+        }
+        Ok(())
+    }
+
+    /// Version of the key new hashes are made with
+    pub fn current_version(&self) -> u32 {
+        self.keys[0].version
+    }
+
+    /// Hash `value` for `purpose` under the current key
+    pub fn hash(&self, purpose: &str, value: &str) -> String {
+        Self::hash_with(&self.keys[0], purpose, value)
+    }
+
+    /// Hash `value` for `purpose` under every key, current first, to find
+    /// records stored before a rotation
+    pub fn hashes(&self, purpose: &str, value: &str) -> Vec<String> {
+        self.keys
+            .iter()
+            .map(|key| Self::hash_with(key, purpose, value))
+            .collect()
+    }
+
+    fn hash_with(key: &HashKey, purpose: &str, value: &str) -> String {
+        let mut mac =
+            Hmac::<Sha256>::new_from_slice(&key.secret).expect("HMAC accepts any key length");
+        mac.update(purpose.as_bytes());
+        mac.update(&[0]);
+        mac.update(value.as_bytes());
+        let digest: String = mac
+            .finalize()
+            .into_bytes()
+            .iter()
+            .map(|b| format!("{:02x}", b))
+            .collect();
+        format!("v{}:{}", key.version, digest)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn hashes_are_versioned_and_rotate() {
+        let old = IpHasher::parse("1:first-secret-value").unwrap();
+        let rotated = IpHasher::parse("2:second-secret-value, 1:first-secret-value").unwrap();
+
+        let before = old.hash("contact", "192.0.2.1");
+        assert!(before.starts_with("v1:"));
+        assert_eq!(before.len(), 3 + 64);
+        assert_ne!(before, old.hash("admin_login", "192.0.2.1"));
+
+        assert_eq!(rotated.current_version(), 2);
+        let hashes = rotated.hashes("contact", "192.0.2.1");
+        assert!(hashes[0].starts_with("v2:"));
+        assert_eq!(hashes[1], before);
+
+        assert!(IpHasher::parse("").is_err());
+        assert!(IpHasher::parse("secret").is_err());
+        assert!(IpHasher::parse("1:a,1:b").is_err());
+    }
+
+    #[test]
+    fn production_refuses_weak_secrets() {
+        assert!(IpHasher::parse("1:portfolio-contact-salt-2025").unwrap().check_secrets().is_err());
+        assert!(IpHasher::parse("1:short").unwrap().check_secrets().is_err());
+        assert!(IpHasher::parse("2:a-long-enough-random-secret, 1:change-me")
+            .unwrap()
+            .check_secrets()
+            .is_err());
+        assert!(IpHasher::parse("2:a-long-enough-random-secret").unwrap().check_secrets().is_ok());
+    }
+}
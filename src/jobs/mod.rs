@@ -0,0 +1,485 @@
+//! Background maintenance jobs
+//!
+//! A single task runs periodic cleanup on a fixed interval: stale rate
+//! limit records, used form nonces, expired admin sessions, old outbox
+//! rows, contact submission retention, analytics rollups and raw analytics
+//! retention. The first run happens right after startup.
+//!
+//! A second task delivers the email outbox when email is configured.
+
+use chrono::{Duration as ChronoDuration, Utc};
+use sqlx::PgPool;
+use std::time::Duration;
+use tokio::task::JoinHandle;
+use tokio::time::MissedTickBehavior;
+use tracing::{info, warn};
+
+use crate::db::{self, ContactFilter, ContactStatus, OutboxEmail, OutboxKind};
+use crate::email::{send_contact_acknowledgement, send_contact_notification, EmailConfig};
+use crate::encryption::ContactCipher;
+use crate::i18n::Language;
+
+/// How often maintenance runs
+pub const MAINTENANCE_INTERVAL: Duration = Duration::from_secs(60 * 60);
+
+/// How often the outbox worker looks for due emails
+pub const OUTBOX_POLL_INTERVAL: Duration = Duration::from_secs(10);
+
+/// Emails claimed per outbox batch
+const OUTBOX_BATCH_SIZE: i64 = 10;
+
+/// Maintenance settings loaded from environment variables
+#[derive(Debug, Clone)]
+pub struct MaintenanceConfig {
+    /// Days of raw page views / events to keep (aggregates are kept forever)
+    pub analytics_retention_days: i64,
+    pub contact_retention: ContactRetention,
+}
+
+impl MaintenanceConfig {
+    /// Load settings from environment variables, falling back to defaults
+    pub fn from_env() -> Self {
+        Self {
+            analytics_retention_days: std::env::var("ANALYTICS_RETENTION_DAYS")
+                .ok()
+                .and_then(|d| d.parse().ok())
+                .filter(|d| (1..=MAX_RETENTION_DAYS).contains(d))
+                .unwrap_or(db::DEFAULT_ANALYTICS_RETENTION_DAYS),
+            contact_retention: ContactRetention::from_env(),
+        }
+    }
+}
+
+/// Longest retention accepted (contact and analytics), so the interval
+/// always fits the query and the date arithmetic cannot overflow
+const MAX_RETENTION_DAYS: i64 = 36_500;
+
+/// Days each kind of contact submission is kept after it was received;
+/// None keeps it until it is deleted by hand or by its sender.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ContactRetention {
+    pub pending: Option<i64>,
+    pub read: Option<i64>,
+    pub replied: Option<i64>,
+    pub spam: Option<i64>,
+    /// Honeypot hits
+    pub quarantine: Option<i64>,
+    /// Log what would be deleted instead of deleting it
+    pub dry_run: bool,
+}
+
+impl Default for ContactRetention {
+    fn default() -> Self {
+        Self {
+            pending: None,
+            read: Some(730),
+            replied: Some(730),
+            spam: Some(30),
+            quarantine: Some(30),
+            dry_run: false,
+        }
+    }
+}
+
+impl ContactRetention {
+    /// Load `CONTACT_RETENTION_{PENDING,READ,REPLIED,SPAM,QUARANTINE}_DAYS`
+    /// (a number of days, or `never`) and `CONTACT_RETENTION_DRY_RUN`.
+    /// Unset or invalid values keep the default.
+    pub fn from_env() -> Self {
+        let defaults = Self::default();
+        let days = |name: &str, default: Option<i64>| {
+            std::env::var(name)
+                .ok()
+                .and_then(|value| parse_retention_days(&value))
+                .unwrap_or(default)
+        };
+
+        Self {
+            pending: days("CONTACT_RETENTION_PENDING_DAYS", defaults.pending),
+            read: days("CONTACT_RETENTION_READ_DAYS", defaults.read),
+            replied: days("CONTACT_RETENTION_REPLIED_DAYS", defaults.replied),
+            spam: days("CONTACT_RETENTION_SPAM_DAYS", defaults.spam),
+            quarantine: days("CONTACT_RETENTION_QUARANTINE_DAYS", defaults.quarantine),
+            dry_run: std::env::var("CONTACT_RETENTION_DRY_RUN")
+                .map(|v| v == "true" || v == "1")
+                .unwrap_or(false),
+        }
+    }
+
+    /// (label, inbox filter, days) for every kind with a retention period
+    fn rules(&self) -> Vec<(&'static str, ContactFilter, i64)> {
+        let folder = |status: ContactStatus| ContactFilter {
+            status: Some(status),
+            quarantined: false,
+        };
+        [
+            ("pending", folder(ContactStatus::Pending), self.pending),
+            ("read", folder(ContactStatus::Read), self.read),
+            ("replied", folder(ContactStatus::Replied), self.replied),
+            ("spam", folder(ContactStatus::Spam), self.spam),
+            (
+                "quarantine",
+                ContactFilter {
+                    status: None,
+                    quarantined: true,
+                },
+                self.quarantine,
+            ),
+        ]
+        .into_iter()
+        .filter_map(|(label, filter, days)| Some((label, filter, days?)))
+        .collect()
+    }
+}
+
+/// `Some(None)` for `never`, `Some(Some(days))` for a valid number of days
+fn parse_retention_days(value: &str) -> Option<Option<i64>> {
+    let value = value.trim();
+    if value.eq_ignore_ascii_case("never") {
+        return Some(None);
+    }
+    value
+        .parse()
+        .ok()
+        .filter(|days| (1..=MAX_RETENTION_DAYS).contains(days))
+        .map(Some)
+}
+
+/// Spawn the maintenance loop
+pub fn spawn_maintenance(pool: PgPool, config: MaintenanceConfig) -> JoinHandle<()> {
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(MAINTENANCE_INTERVAL);
+        interval.set_missed_tick_behavior(MissedTickBehavior::Delay);
+
+        loop {
+            interval.tick().await;
+            run_maintenance(&pool, &config).await;
+        }
+    })
+}
+
+/// Run every maintenance step once. Failures are logged, never fatal.
+pub async fn run_maintenance(pool: &PgPool, config: &MaintenanceConfig) {
+    // Cleanup stale rate limit records (older than 24 hours)
+    match db::cleanup_rate_limits(pool).await {
+        Ok(deleted) if deleted > 0 => info!(deleted, "Cleaned up stale rate limit records"),
+        Ok(_) => {}
+        Err(e) => warn!(?e, "Failed to cleanup rate limits"),
+    }
+
+    match db::cleanup_acknowledgement_throttle(pool).await {
+        Ok(deleted) if deleted > 0 => info!(deleted, "Cleaned up acknowledgement throttle records"),
+        Ok(_) => {}
+        Err(e) => warn!(?e, "Failed to cleanup acknowledgement throttle"),
+    }
+
+    match db::cleanup_form_nonces(pool).await {
+        Ok(deleted) if deleted > 0 => info!(deleted, "Deleted expired contact form nonces"),
+        Ok(_) => {}
+        Err(e) => warn!(?e, "Failed to cleanup contact form nonces"),
+    }
+
+    match db::cleanup_outbox(pool).await {
+        Ok(deleted) if deleted > 0 => info!(deleted, "Deleted old sent outbox emails"),
+        Ok(_) => {}
+        Err(e) => warn!(?e, "Failed to cleanup email outbox"),
+    }
+
+    match db::cleanup_admin_sessions(pool).await {
+        Ok(deleted) if deleted > 0 => info!(deleted, "Deleted expired admin sessions"),
+        Ok(_) => {}
+        Err(e) => warn!(?e, "Failed to cleanup admin sessions"),
+    }
+
+    purge_expired_contacts(pool, &config.contact_retention).await;
+
+    // Only days still fully inside the retention window are rolled up, so a
+    // partially purged day never overwrites its aggregate
+    let retention_start =
+        Utc::now().date_naive() - ChronoDuration::days(config.analytics_retention_days);
+
+    match db::rollup_analytics(pool, retention_start).await {
+        Ok(written) => info!(written, "Rolled up analytics aggregates"),
+        Err(e) => {
+            // Never purge raw rows that could not be aggregated
+            warn!(?e, "Failed to roll up analytics - skipping retention");
+            return;
+        }
+    }
+
+    match db::cleanup_analytics(pool, retention_start).await {
+        Ok(deleted) if deleted > 0 => info!(deleted, "Purged raw analytics past retention"),
+        Ok(_) => {}
+        Err(e) => warn!(?e, "Failed to purge raw analytics"),
+    }
+}
+
+/// Delete contact submissions past their retention period, or only log how
+/// many would go in dry-run mode.
+async fn purge_expired_contacts(pool: &PgPool, retention: &ContactRetention) {
+    for (status, filter, days) in retention.rules() {
+        if retention.dry_run {
+            match db::count_expired_contacts(pool, &filter, days).await {
+                Ok(count) if count > 0 => {
+                    info!(
+                        status,
+                        days,
+                        count,
+                        "Contact retention (dry run): would delete submissions"
+                    )
+                }
+                Ok(_) => {}
+                Err(e) => warn!(?e, status, "Failed to count expired contact submissions"),
+            }
+            continue;
+        }
+
+        match db::purge_expired_contacts(pool, &filter, days).await {
+            Ok(deleted) if deleted > 0 => {
+                info!(status, days, deleted, "Deleted contact submissions past retention")
+            }
+            Ok(_) => {}
+            Err(e) => warn!(?e, status, "Failed to purge expired contact submissions"),
+        }
+    }
+}
+
+/// Spawn the outbox worker
+pub fn spawn_outbox_worker(
+    pool: PgPool,
+    config: EmailConfig,
+    cipher: ContactCipher,
+) -> JoinHandle<()> {
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(OUTBOX_POLL_INTERVAL);
+        interval.set_missed_tick_behavior(MissedTickBehavior::Delay);
+
+        loop {
+            interval.tick().await;
+            run_outbox(&pool, &config, &cipher).await;
+        }
+    })
+}
+
+/// Deliver every due outbox email, one batch at a time.
+pub async fn run_outbox(pool: &PgPool, config: &EmailConfig, cipher: &ContactCipher) {
+    loop {
+        let batch = match db::claim_outbox_emails(pool, OUTBOX_BATCH_SIZE).await {
+            Ok(batch) => batch,
+            Err(e) => {
+                warn!(?e, "Failed to claim outbox emails");
+                return;
+            }
+        };
+        let claimed = batch.len() as i64;
+
+        for email in batch {
+            let result = deliver_outbox_email(pool, config, cipher, &email).await;
+            let recorded = match result {
+                Ok(()) => db::mark_outbox_sent(pool, email.id).await.map(|_| ()),
+                // Retrying cannot help, so skip the backoff
+                Err(DeliveryError::Permanent(error)) => {
+                    db::mark_outbox_dead(pool, email.id, &error).await.map(|_| {
+                        warn!(id = %email.id, kind = %email.kind, %error, "Outbox email dead")
+                    })
+                }
+                Err(DeliveryError::Transient(error)) => {
+                    db::mark_outbox_failed(pool, email.id, &error)
+                        .await
+                        .map(|status| {
+                            warn!(
+                                id = %email.id,
+                                kind = %email.kind,
+                                attempts = email.attempts,
+                                %status,
+                                %error,
+                                "Outbox email failed"
+                            )
+                        })
+                }
+            };
+            if let Err(e) = recorded {
+                // The lease expires and the email is retried
+                warn!(?e, id = %email.id, "Failed to record outbox result");
+            }
+        }
+
+        if claimed < OUTBOX_BATCH_SIZE {
+            return;
+        }
+    }
+}
+
+/// Why an outbox email could not be delivered
+enum DeliveryError {
+    /// May succeed on a later attempt (SMTP, database, decryption)
+    Transient(String),
+    /// Never will: the email is marked dead right away
+    Permanent(String),
+}
+
+impl DeliveryError {
+    /// Classify a send error. An address that does not parse, or a message
+    /// lettre refuses to build, fails the same way on every attempt.
+    fn from_send(e: Box<dyn std::error::Error + Send + Sync>) -> Self {
+        if e.is::<lettre::address::AddressError>() || e.is::<lettre::error::Error>() {
+            DeliveryError::Permanent(e.to_string())
+        } else {
+            DeliveryError::Transient(e.to_string())
+        }
+    }
+}
+
+/// Render and send one outbox email from its (decrypted) submission
+async fn deliver_outbox_email(
+    pool: &PgPool,
+    config: &EmailConfig,
+    cipher: &ContactCipher,
+    email: &OutboxEmail,
+) -> Result<(), DeliveryError> {
+    let kind = OutboxKind::parse(&email.kind)
+        .ok_or_else(|| DeliveryError::Permanent(format!("Unknown kind {}", email.kind)))?;
+    let submission = db::get_contact(pool, email.submission_id)
+        .await
+        .map_err(|e| DeliveryError::Transient(e.to_string()))?
+        .ok_or_else(|| DeliveryError::Permanent("Submission no longer exists".to_string()))?;
+    let pii = cipher
+        .open(&submission)
+        .map_err(|e| DeliveryError::Transient(e.to_string()))?;
+
+    match kind {
+        OutboxKind::ContactNotification => {
+            send_contact_notification(
+                config,
+                submission.id,
+                &pii.name,
+                &pii.email,
+                pii.subject.as_deref(),
+                &pii.message,
+            )
+            .await
+        }
+        OutboxKind::ContactAcknowledgement => {
+            send_contact_acknowledgement(
+                config,
+                submission.id,
+                Language::from_code(&email.language),
+                &pii.name,
+                &pii.email,
+                &pii.message,
+            )
+            .await
+        }
+    }
+    .map_err(DeliveryError::from_send)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn bad_addresses_are_permanent_failures() {
+        let address = "not an address"
+            .parse::<lettre::message::Mailbox>()
+            .unwrap_err();
+        assert!(matches!(
+            DeliveryError::from_send(address.into()),
+            DeliveryError::Permanent(_)
+        ));
+        let io = std::io::Error::new(std::io::ErrorKind::TimedOut, "timed out");
+        assert!(matches!(
+            DeliveryError::from_send(io.into()),
+            DeliveryError::Transient(_)
+        ));
+    }
+
+    #[test]
+    fn retention_days_parse() {
+        assert_eq!(parse_retention_days("30"), Some(Some(30)));
+        assert_eq!(parse_retention_days(" Never "), Some(None));
+        assert_eq!(parse_retention_days("0"), None);
+        assert_eq!(parse_retention_days("99999999"), None);
+        assert_eq!(parse_retention_days("two years"), None);
+
+        let retention = ContactRetention {
+            read: None,
+            ..ContactRetention::default()
+        };
+        let statuses: Vec<&str> = retention
+            .rules()
+            .iter()
+            .map(|(status, _, _)| *status)
+            .collect();
+        assert_eq!(statuses, ["replied", "spam", "quarantine"]);
+    }
+
+    /// Legacy plaintext submission received `age_days` ago
+    async fn submission(
+        pool: &PgPool,
+        status: &str,
+        quarantined: bool,
+        age_days: i32,
+    ) -> uuid::Uuid {
+        let (id,): (uuid::Uuid,) = sqlx::query_as(
+            r#"
+            INSERT INTO contact_submissions
+                (name, email, message, status, honeypot_filled, created_at)
+            VALUES ('Ada', 'ada@example.com', 'Hello', $1, $2, NOW() - make_interval(days => $3))
+            RETURNING id
+            "#,
+        )
+        .bind(status)
+        .bind(quarantined)
+        .bind(age_days)
+        .fetch_one(pool)
+        .await
+        .unwrap();
+        id
+    }
+
+    async fn remaining(pool: &PgPool) -> Vec<uuid::Uuid> {
+        let rows: Vec<(uuid::Uuid,)> =
+            sqlx::query_as("SELECT id FROM contact_submissions ORDER BY id")
+                .fetch_all(pool)
+                .await
+                .unwrap();
+        rows.into_iter().map(|(id,)| id).collect()
+    }
+
+    #[sqlx::test]
+    async fn expired_contacts_are_purged(pool: PgPool) {
+        // Past their retention period
+        submission(&pool, "read", false, 800).await;
+        submission(&pool, "spam", false, 40).await;
+        submission(&pool, "pending", true, 40).await;
+        let mut kept = vec![
+            submission(&pool, "read", false, 10).await,
+            submission(&pool, "spam", false, 20).await,
+            // Pending is kept forever by default
+            submission(&pool, "pending", false, 1000).await,
+        ];
+        kept.sort();
+        let retention = ContactRetention::default();
+
+        let read = ContactFilter {
+            status: Some(ContactStatus::Read),
+            quarantined: false,
+        };
+        assert_eq!(
+            db::count_expired_contacts(&pool, &read, 730).await.unwrap(),
+            1
+        );
+
+        // A dry run only counts
+        let dry_run = ContactRetention {
+            dry_run: true,
+            ..retention
+        };
+        purge_expired_contacts(&pool, &dry_run).await;
+        assert_eq!(remaining(&pool).await.len(), 6);
+
+        purge_expired_contacts(&pool, &retention).await;
+        assert_eq!(remaining(&pool).await, kept);
+    }
+}
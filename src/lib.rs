@@ -1,15 +1,36 @@
+#[cfg(feature = "ssr")]
+pub mod admin;
+pub mod analytics;
 pub mod app;
 pub mod blog;
+#[cfg(feature = "ssr")]
+pub mod client_ip;
 pub mod components;
+#[cfg(feature = "ssr")]
+pub mod data_request;
 pub mod db;
 #[cfg(feature = "ssr")]
 pub mod email;
 #[cfg(feature = "ssr")]
+pub mod encryption;
+#[cfg(feature = "ssr")]
 pub mod github;
 pub mod i18n;
+#[cfg(feature = "ssr")]
+pub mod ip_hash;
+#[cfg(feature = "ssr")]
+pub mod jobs;
 pub mod pages;
+#[cfg(any(feature = "ssr", feature = "hydrate"))]
+pub mod pow;
+#[cfg(feature = "ssr")]
+pub mod rate_limit;
 pub mod server_fns;
+#[cfg(feature = "ssr")]
+pub mod spam;
 pub mod themes;
+#[cfg(feature = "ssr")]
+pub mod user_agent;
 
 #[cfg(feature = "hydrate")]
 #[wasm_bindgen::prelude::wasm_bindgen]
@@ -4,16 +4,40 @@
 async fn main() {
     use axum::http::{header, HeaderName, HeaderValue, StatusCode};
     use axum::Router;
+    use leptos::config::Env;
     use leptos::prelude::*;
     use leptos_axum::{generate_route_list, LeptosRoutes};
+    use portfolio::admin::{hash_password, AdminAuthConfig};
+    use portfolio::analytics::geoip::GeoIp;
     use portfolio::app::*;
+    use portfolio::client_ip::TrustedProxies;
+    use portfolio::data_request::DataLinkKey;
     use portfolio::db;
+    use portfolio::email::EmailConfig;
+    use portfolio::encryption::{reencrypt_contacts, ContactCipher};
+    use portfolio::ip_hash::IpHasher;
+    use portfolio::jobs::{spawn_maintenance, spawn_outbox_worker, MaintenanceConfig};
+    use portfolio::rate_limit::RateLimits;
+    use portfolio::spam::FormTokenKey;
     use std::net::SocketAddr;
     use std::time::Duration;
     use tower::{timeout::TimeoutLayer, ServiceBuilder};
     use tower_http::set_header::SetResponseHeaderLayer;
     use tracing::{info, warn};
 
+    // `portfolio hash-password`: read a password on stdin and print the
+    // argon2 hash to use as ADMIN_PASSWORD_HASH
+    if std::env::args().nth(1).as_deref() == Some("hash-password") {
+        let mut password = String::new();
+        std::io::stdin()
+            .read_line(&mut password)
+            .expect("Failed to read password from stdin");
+        let hash = hash_password(password.trim_end_matches(['\r', '\n']))
+            .expect("Failed to hash password");
+        println!("{hash}");
+        return;
+    }
+
     // Load environment variables from .env file
     dotenvy::dotenv().ok();
 
@@ -26,6 +50,18 @@ async fn main() {
         )
         .init();
 
+    let conf = get_configuration(None).unwrap();
+    let production = conf.leptos_options.env == Env::PROD;
+
+    // Master keys for contact submissions; production refuses the development key
+    let contact_cipher = ContactCipher::from_env(production)
+        .unwrap_or_else(|e| panic!("Invalid CONTACT_ENCRYPTION_KEYS: {e}"));
+
+    // Key for IP and session hashes; production refuses missing or public keys
+    let ip_hasher = IpHasher::from_env(production)
+        .unwrap_or_else(|e| panic!("Invalid IP_HASH_KEYS: {e}"));
+    info!(version = ip_hasher.current_version(), "IP hash key loaded");
+
     // Initialize database pool
     let pool = db::create_pool()
         .await
@@ -36,14 +72,68 @@ async fn main() {
         .await
         .expect("Failed to run database migrations");
 
-    // Cleanup stale rate limit records (older than 24 hours)
-    match db::cleanup_rate_limits(&pool).await {
-        Ok(deleted) if deleted > 0 => info!(deleted, "Cleaned up stale rate limit records"),
-        Ok(_) => {}
-        Err(e) => warn!(?e, "Failed to cleanup rate limits"),
+    info!("Database connected and migrations applied");
+
+    // `portfolio reencrypt-contacts`: seal plaintext submissions and replies,
+    // re-wrap data keys under the current CONTACT_ENCRYPTION_KEYS entry and
+    // index sender addresses under the current IP_HASH_KEYS entry, then exit
+    if std::env::args().nth(1).as_deref() == Some("reencrypt-contacts") {
+        let report = reencrypt_contacts(&pool, &contact_cipher, &ip_hasher)
+            .await
+            .expect("Failed to re-encrypt contact submissions");
+        info!(
+            sealed = report.sealed,
+            rewrapped = report.rewrapped,
+            reindexed = report.reindexed,
+            replies_sealed = report.replies_sealed,
+            failed = report.failed,
+            key_id = contact_cipher.current_key_id(),
+            "Contact submissions re-encrypted"
+        );
+        if report.failed > 0 {
+            std::process::exit(1);
+        }
+        return;
+    }
+    match db::count_contacts_to_reencrypt(
+        &pool,
+        contact_cipher.current_key_id(),
+        ip_hasher.current_version(),
+    )
+    .await
+    {
+        Ok(0) => {}
+        Ok(count) => warn!(
+            count,
+            "Contact submissions not under the current encryption or hash key - run `portfolio reencrypt-contacts`"
+        ),
+        Err(e) => warn!(?e, "Failed to count contact submissions to re-encrypt"),
     }
 
-    info!("Database connected and migrations applied");
+    // Periodic cleanup: rate limits, contact and analytics retention, rollups
+    let maintenance = MaintenanceConfig::from_env();
+    if maintenance.contact_retention.dry_run {
+        info!("CONTACT_RETENTION_DRY_RUN set - expired contact submissions are only counted");
+    }
+    spawn_maintenance(pool.clone(), maintenance);
+
+    // Email settings, loaded once; refuse to start with a DKIM key that
+    // does not parse
+    let email_config = EmailConfig::from_env()
+        .unwrap_or_else(|e| panic!("Invalid DKIM configuration: {e}"));
+
+    // Deliver queued contact emails
+    match &email_config {
+        Some(config) => {
+            info!(
+                transport = %config.transport.describe(),
+                dkim = config.dkim.is_some(),
+                "Email enabled"
+            );
+            spawn_outbox_worker(pool.clone(), config.clone(), contact_cipher.clone());
+        }
+        None => info!("Email not configured - outbox worker not started"),
+    }
 
     // Create shared HTTP client for external API calls (GitHub)
     let http_client = reqwest::Client::builder()
@@ -52,15 +142,50 @@ async fn main() {
         .build()
         .expect("Failed to build HTTP client");
 
-    let conf = get_configuration(None).unwrap();
+    // Optional local GeoIP database for analytics country codes
+    let geoip = GeoIp::from_env();
+
+    // Admin login (disabled unless credentials are configured)
+    let admin_auth = AdminAuthConfig::from_env();
+    match &admin_auth {
+        Some(config) => info!(totp = config.totp_enabled(), "Admin login enabled"),
+        None => info!("Admin login not configured - /admin is unavailable"),
+    }
+
     let addr = conf.leptos_options.site_addr;
     let leptos_options = conf.leptos_options;
+    // Signing key for contact form time-trap tokens
+    let form_token_key = FormTokenKey::from_env(production)
+        .unwrap_or_else(|e| panic!("Invalid FORM_TOKEN_SECRET: {e}"));
+    // Signing key for the links visitors use to export or erase their data
+    let data_link_key = DataLinkKey::from_env(production)
+        .unwrap_or_else(|e| panic!("Invalid DATA_LINK_SECRET: {e}"));
+
+    // Proxies allowed to report the client IP in forwarding headers
+    let trusted_proxies = TrustedProxies::from_env()
+        .unwrap_or_else(|entry| panic!("Invalid TRUSTED_PROXIES entry: {entry}"));
+    if trusted_proxies.is_empty() {
+        info!("TRUSTED_PROXIES not set - forwarding headers are ignored");
+    }
+
+    // Per-server-function rate limits (Postgres and in-memory stores)
+    let rate_limits = RateLimits::new(pool.clone(), ip_hasher.clone());
+
     // Generate the list of routes in your Leptos App
     let routes = generate_route_list(App);
 
     // Clone resources for the context closure
     let pool_for_context = pool.clone();
     let http_client_for_context = http_client.clone();
+    let geoip_for_context = geoip.clone();
+    let admin_auth_for_context = admin_auth.clone();
+    let form_token_key_for_context = form_token_key.clone();
+    let data_link_key_for_context = data_link_key.clone();
+    let rate_limits_for_context = rate_limits.clone();
+    let trusted_proxies_for_context = trusted_proxies.clone();
+    let ip_hasher_for_context = ip_hasher.clone();
+    let contact_cipher_for_context = contact_cipher.clone();
+    let email_config_for_context = email_config.clone();
 
     let app = Router::new()
         .leptos_routes_with_context(
@@ -71,6 +196,28 @@ async fn main() {
                 provide_context(pool_for_context.clone());
                 // Provide shared HTTP client for external API calls
                 provide_context(http_client_for_context.clone());
+                // Provide GeoIP resolver (no-op when no database is configured)
+                provide_context(geoip_for_context.clone());
+                // Provide the contact form token key
+                provide_context(form_token_key_for_context.clone());
+                // Provide the data link key
+                provide_context(data_link_key_for_context.clone());
+                // Provide rate limiters
+                provide_context(rate_limits_for_context.clone());
+                // Provide trusted proxies for client IP resolution
+                provide_context(trusted_proxies_for_context.clone());
+                // Provide the IP hash keys
+                provide_context(ip_hasher_for_context.clone());
+                // Provide the contact submission encryption keys
+                provide_context(contact_cipher_for_context.clone());
+                // Provide email settings (only when configured)
+                if let Some(config) = email_config_for_context.clone() {
+                    provide_context(config);
+                }
+                // Provide admin credentials (only when configured)
+                if let Some(config) = admin_auth_for_context.clone() {
+                    provide_context(config);
+                }
             },
             {
                 let leptos_options = leptos_options.clone();
@@ -0,0 +1,193 @@
+use leptos::prelude::*;
+use leptos_meta::{Meta, Title};
+use leptos_router::hooks::use_query_map;
+
+use super::AdminNav;
+use crate::components::{BarChart, LineChart};
+use crate::server_fns::{
+    get_analytics_dashboard, AnalyticsDashboard, PageStats, DASHBOARD_RANGES,
+    DEFAULT_DASHBOARD_DAYS,
+};
+
+/// Analytics dashboard over the daily rollups (`/admin/analytics?days=N`)
+#[component]
+pub fn AdminAnalyticsPage() -> impl IntoView {
+    let query = use_query_map();
+    let days = Memo::new(move |_| {
+        query
+            .read()
+            .get("days")
+            .and_then(|d| d.parse::<u32>().ok())
+            .filter(|d| DASHBOARD_RANGES.contains(d))
+            .unwrap_or(DEFAULT_DASHBOARD_DAYS)
+    });
+
+    let dashboard = Resource::new(move || days.get(), get_analytics_dashboard);
+
+    view! {
+        <Title text="Analytics - Admin"/>
+        <Meta name="robots" content="noindex, nofollow"/>
+
+        <div class="admin-page">
+            <AdminNav/>
+            <header class="admin-header">
+                <h1>"Analytics"</h1>
+                <nav class="filter-tabs admin-range" aria-label="Date range">
+                    {DASHBOARD_RANGES
+                        .iter()
+                        .map(|range| {
+                            let range = *range;
+                            view! {
+                                <a
+                                    href=format!("/admin/analytics?days={range}")
+                                    class="filter-tab"
+                                    class:active=move || days.get() == range
+                                >
+                                    {format!("{range} days")}
+                                </a>
+                            }
+                        })
+                        .collect_view()}
+                </nav>
+            </header>
+
+            <Suspense fallback=|| view! {
+                <div class="admin-loading">
+                    <span class="loading-spinner"></span>
+                    <span>"Loading analytics…"</span>
+                </div>
+            }>
+                {move || {
+                    dashboard.get().map(|result| match result {
+                        Ok(data) => view! { <Dashboard data=data/> }.into_any(),
+                        Err(_) => view! {
+                            <div class="admin-error">"Could not load analytics."</div>
+                        }.into_any(),
+                    })
+                }}
+            </Suspense>
+        </div>
+    }
+}
+
+/// Dashboard body for a loaded period
+#[component]
+fn Dashboard(data: AnalyticsDashboard) -> impl IntoView {
+    let avg_time = data
+        .avg_time_on_page_seconds
+        .map(format_duration)
+        .unwrap_or_else(|| "–".to_string());
+    let avg_scroll = data
+        .avg_scroll_depth_percent
+        .map(|p| format!("{p}%"))
+        .unwrap_or_else(|| "–".to_string());
+
+    view! {
+        <section class="admin-stats">
+            <div class="admin-stat">
+                <span class="admin-stat-value">{data.total_views}</span>
+                <span class="admin-stat-label">{format!("Views ({} days)", data.days)}</span>
+            </div>
+            <div class="admin-stat">
+                <span class="admin-stat-value">{avg_time}</span>
+                <span class="admin-stat-label">"Avg. time on page"</span>
+            </div>
+            <div class="admin-stat">
+                <span class="admin-stat-value">{avg_scroll}</span>
+                <span class="admin-stat-label">"Avg. scroll depth"</span>
+            </div>
+        </section>
+
+        <section class="admin-card admin-card-wide">
+            <h2>"Views over time"</h2>
+            <LineChart points=data.daily_views label="Daily page views"/>
+        </section>
+
+        <div class="admin-grid">
+            <section class="admin-card">
+                <h2>"Top pages"</h2>
+                <PageTable pages=data.top_pages/>
+            </section>
+            <section class="admin-card">
+                <h2>"Top blog posts"</h2>
+                <PageTable pages=data.top_posts/>
+            </section>
+            <section class="admin-card">
+                <h2>"Top referrers"</h2>
+                <BarChart items=data.referrers label="Top referrers"/>
+            </section>
+            <section class="admin-card">
+                <h2>"Devices"</h2>
+                <BarChart items=data.devices label="Views by device type"/>
+            </section>
+            <section class="admin-card">
+                <h2>"Browsers"</h2>
+                <BarChart items=data.browsers label="Views by browser"/>
+            </section>
+            <section class="admin-card">
+                <h2>"Operating systems"</h2>
+                <BarChart items=data.operating_systems label="Views by operating system"/>
+            </section>
+            <section class="admin-card">
+                <h2>"Countries"</h2>
+                <BarChart items=data.countries label="Views by country"/>
+            </section>
+            <section class="admin-card">
+                <h2>"Events"</h2>
+                <BarChart items=data.events label="Custom event counts"/>
+            </section>
+        </div>
+    }
+}
+
+/// Table of pages with views and engagement
+#[component]
+fn PageTable(pages: Vec<PageStats>) -> impl IntoView {
+    if pages.is_empty() {
+        return view! { <p class="chart-empty">"No data for this period."</p> }.into_any();
+    }
+
+    view! {
+        <table class="admin-table">
+            <thead>
+                <tr>
+                    <th>"Page"</th>
+                    <th class="numeric">"Views"</th>
+                    <th class="numeric">"Avg. time"</th>
+                    <th class="numeric">"Avg. scroll"</th>
+                </tr>
+            </thead>
+            <tbody>
+                {pages
+                    .into_iter()
+                    .map(|page| {
+                        view! {
+                            <tr>
+                                <td>
+                                    <a href=page.path.clone() title=page.path>{page.label}</a>
+                                </td>
+                                <td class="numeric">{page.views}</td>
+                                <td class="numeric">
+                                    {page.avg_time_seconds.map(format_duration).unwrap_or_default()}
+                                </td>
+                                <td class="numeric">
+                                    {page.avg_scroll_percent.map(|p| format!("{p}%")).unwrap_or_default()}
+                                </td>
+                            </tr>
+                        }
+                    })
+                    .collect_view()}
+            </tbody>
+        </table>
+    }
+    .into_any()
+}
+
+/// Format seconds as "1m 05s"
+fn format_duration(seconds: i64) -> String {
+    if seconds < 60 {
+        format!("{seconds}s")
+    } else {
+        format!("{}m {:02}s", seconds / 60, seconds % 60)
+    }
+}
@@ -0,0 +1,457 @@
+use leptos::prelude::*;
+use leptos_meta::{Meta, Title};
+use leptos_router::hooks::{use_params_map, use_query_map};
+use leptos_router::location::Url;
+
+use super::AdminNav;
+use crate::server_fns::{
+    get_contact_submission, list_contact_submissions, reply_to_contact, set_contact_status,
+    ContactDetail, InboxFolder, InboxPage, InboxQuery, MAX_REPLY_LENGTH, MAX_SEARCH_LENGTH,
+    MAX_SEARCH_ROWS,
+};
+
+/// Build an inbox URL for a folder, search and page
+fn inbox_href(folder: InboxFolder, search: &Option<String>, page: i64) -> String {
+    let mut href = format!("/admin/inbox?folder={}", folder.code());
+    if let Some(search) = search {
+        href.push_str("&q=");
+        href.push_str(&Url::escape(search));
+    }
+    if page > 1 {
+        href.push_str(&format!("&page={page}"));
+    }
+    href
+}
+
+// ============================================================================
+// INBOX LIST
+// ============================================================================
+
+/// Contact submissions list (`/admin/inbox?folder=pending&q=...&page=N`)
+#[component]
+pub fn AdminInboxPage() -> impl IntoView {
+    let query_map = use_query_map();
+    let query = Memo::new(move |_| {
+        let params = query_map.read();
+        InboxQuery {
+            folder: params
+                .get("folder")
+                .and_then(|f| InboxFolder::from_code(&f))
+                .unwrap_or_default(),
+            search: params.get("q").filter(|q| !q.trim().is_empty()),
+            page: params.get("page").and_then(|p| p.parse().ok()).unwrap_or(1),
+        }
+    });
+
+    let inbox = Resource::new(move || query.get(), list_contact_submissions);
+
+    view! {
+        <Title text="Inbox - Admin"/>
+        <Meta name="robots" content="noindex, nofollow"/>
+
+        <div class="admin-page">
+            <AdminNav/>
+            <header class="admin-header">
+                <h1>"Inbox"</h1>
+                <form class="admin-search" method="get" action="/admin/inbox">
+                    <input type="hidden" name="folder" value=move || query.get().folder.code()/>
+                    <input
+                        type="search"
+                        name="q"
+                        placeholder="Search name, email, message…"
+                        maxlength=MAX_SEARCH_LENGTH
+                        title=format!("Searches the newest {MAX_SEARCH_ROWS} submissions of the folder")
+                        prop:value=move || query.get().search.unwrap_or_default()
+                    />
+                    <button type="submit">"Search"</button>
+                </form>
+            </header>
+
+            <Suspense fallback=|| view! {
+                <div class="admin-loading">
+                    <span class="loading-spinner"></span>
+                    <span>"Loading inbox…"</span>
+                </div>
+            }>
+                {move || {
+                    inbox.get().map(|result| match result {
+                        Ok(data) => view! { <InboxList data=data query=query.get()/> }.into_any(),
+                        Err(e) => view! {
+                            <div class="admin-error">
+                                "Could not load the inbox: " {server_error_message(&e)}
+                            </div>
+                        }.into_any(),
+                    })
+                }}
+            </Suspense>
+        </div>
+    }
+}
+
+/// Folder tabs, submission rows and pagination
+#[component]
+fn InboxList(data: InboxPage, query: InboxQuery) -> impl IntoView {
+    let tabs = InboxFolder::all()
+        .iter()
+        .map(|folder| {
+            let folder = *folder;
+            view! {
+                <a
+                    href=inbox_href(folder, &query.search, 1)
+                    class="filter-tab"
+                    class:active=query.folder == folder
+                >
+                    {format!("{} ({})", folder.label(), data.counts.for_folder(folder))}
+                </a>
+            }
+        })
+        .collect_view();
+
+    let rows = if data.submissions.is_empty() {
+        view! { <p class="chart-empty">"No submissions."</p> }.into_any()
+    } else {
+        view! {
+            <table class="admin-table inbox-table">
+                <thead>
+                    <tr>
+                        <th>"From"</th>
+                        <th>"Message"</th>
+                        <th>"Status"</th>
+                        <th>"Received"</th>
+                    </tr>
+                </thead>
+                <tbody>
+                    {data
+                        .submissions
+                        .into_iter()
+                        .map(|s| {
+                            let href = format!("/admin/inbox/{}", s.id);
+                            view! {
+                                <tr class:unread=s.status == "pending">
+                                    <td>
+                                        <a href=href.clone()>{s.name}</a>
+                                        <div class="inbox-email">{s.email}</div>
+                                    </td>
+                                    <td>
+                                        <a href=href>
+                                            {s.subject.map(|subject| view! {
+                                                <strong>{subject}</strong>
+                                                " – "
+                                            })}
+                                            {s.preview}
+                                        </a>
+                                    </td>
+                                    <td>
+                                        <span class=format!("status-badge status-{}", s.status)>
+                                            {s.status.clone()}
+                                        </span>
+                                    </td>
+                                    <td class="inbox-date">{s.created_at}</td>
+                                </tr>
+                            }
+                        })
+                        .collect_view()}
+                </tbody>
+            </table>
+        }
+        .into_any()
+    };
+
+    let pagination = (data.page_count > 1).then(|| {
+        view! {
+            <nav class="pagination" aria-label="Inbox pages">
+                {(data.page > 1).then(|| view! {
+                    <a class="pagination-btn" href=inbox_href(query.folder, &query.search, data.page - 1)>
+                        "← Newer"
+                    </a>
+                })}
+                <span class="pagination-info">{data.page} " / " {data.page_count}</span>
+                {(data.page < data.page_count).then(|| view! {
+                    <a class="pagination-btn" href=inbox_href(query.folder, &query.search, data.page + 1)>
+                        "Older →"
+                    </a>
+                })}
+            </nav>
+        }
+    });
+
+    view! {
+        <nav class="filter-tabs" aria-label="Inbox folders">{tabs}</nav>
+        {(query.folder == InboxFolder::Quarantine).then(|| view! {
+            <p class="admin-note">
+                "Submissions that filled the honeypot field. No notification was sent for these."
+            </p>
+        })}
+        <section class="admin-card">{rows}</section>
+        {pagination}
+    }
+}
+
+// ============================================================================
+// SUBMISSION DETAIL
+// ============================================================================
+
+/// Single submission with status actions (`/admin/inbox/:id`)
+#[component]
+pub fn AdminContactPage() -> impl IntoView {
+    let params = use_params_map();
+    let id = Memo::new(move |_| params.read().get("id").unwrap_or_default());
+
+    let submission = Resource::new(move || id.get(), get_contact_submission);
+
+    let status_action = Action::new(move |status: &String| {
+        let status = status.clone();
+        async move { set_contact_status(id.get_untracked(), status).await }
+    });
+
+    let reply_action = Action::new(move |(subject, body): &(String, String)| {
+        let (subject, body) = (subject.clone(), body.clone());
+        async move { reply_to_contact(id.get_untracked(), subject, body).await }
+    });
+
+    // Show the updated row once a status change or reply completes
+    Effect::new(move |_| {
+        if let Some(Ok(detail)) = status_action.value().get() {
+            submission.set(Some(Ok(detail)));
+        }
+    });
+    Effect::new(move |_| {
+        if let Some(Ok(detail)) = reply_action.value().get() {
+            submission.set(Some(Ok(detail)));
+        }
+    });
+
+    view! {
+        <Title text="Submission - Admin"/>
+        <Meta name="robots" content="noindex, nofollow"/>
+
+        <div class="admin-page">
+            <AdminNav/>
+            <a href="/admin/inbox" class="admin-back-link">"← Inbox"</a>
+
+            <Suspense fallback=|| view! {
+                <div class="admin-loading">
+                    <span class="loading-spinner"></span>
+                    <span>"Loading submission…"</span>
+                </div>
+            }>
+                {move || {
+                    submission.get().map(|result| match result {
+                        Ok(detail) => view! {
+                            <ContactView detail=detail status_action=status_action reply_action=reply_action/>
+                        }.into_any(),
+                        Err(_) => view! {
+                            <div class="admin-error">"Submission not found."</div>
+                        }.into_any(),
+                    })
+                }}
+            </Suspense>
+        </div>
+    }
+}
+
+/// Submission body, metadata and status buttons
+#[component]
+fn ContactView(
+    detail: ContactDetail,
+    status_action: Action<String, Result<ContactDetail, ServerFnError>>,
+    reply_action: Action<(String, String), Result<ContactDetail, ServerFnError>>,
+) -> impl IntoView {
+    let pending = status_action.pending();
+    let current_status = detail.status.clone();
+
+    let status_button = move |status: &'static str, label: &'static str| {
+        let is_current = current_status == status;
+        view! {
+            <button
+                class="admin-button"
+                class:active=is_current
+                disabled=move || is_current || pending.get()
+                on:click=move |_| {
+                    status_action.dispatch(status.to_string());
+                }
+            >
+                {label}
+            </button>
+        }
+    };
+
+    view! {
+        <article class="admin-card contact-detail">
+            <header class="contact-detail-header">
+                <h1>{detail.subject.clone().unwrap_or_else(|| "(no subject)".to_string())}</h1>
+                <span class=format!("status-badge status-{}", detail.status)>
+                    {detail.status.clone()}
+                </span>
+                {detail.quarantined.then(|| view! {
+                    <span class="status-badge status-quarantine">"honeypot"</span>
+                })}
+            </header>
+
+            <dl class="contact-meta">
+                <dt>"From"</dt>
+                <dd>
+                    {detail.name.clone()} " <"
+                    <a href=format!("mailto:{}", detail.email)>{detail.email.clone()}</a>
+                    ">"
+                </dd>
+                <dt>"Received"</dt>
+                <dd>{detail.created_at.clone()}</dd>
+                <dt>"Read"</dt>
+                <dd>{detail.read_at.clone().unwrap_or_else(|| "–".to_string())}</dd>
+                <dt>"Replied"</dt>
+                <dd>{detail.replied_at.clone().unwrap_or_else(|| "–".to_string())}</dd>
+                <dt>"Client"</dt>
+                <dd title=detail.user_agent.clone().unwrap_or_default()>
+                    {detail.client.clone().unwrap_or_else(|| "Unknown".to_string())}
+                </dd>
+                {detail.spam_score.map(|score| {
+                    let rules = detail
+                        .spam_rules
+                        .iter()
+                        .map(|(rule, points)| format!("{rule} +{points}"))
+                        .collect::<Vec<_>>()
+                        .join(", ");
+                    view! {
+                        <dt>"Spam score"</dt>
+                        <dd>
+                            {score}
+                            {(!rules.is_empty()).then(|| view! {
+                                <span class="spam-rules">" (" {rules} ")"</span>
+                            })}
+                        </dd>
+                    }
+                })}
+            </dl>
+
+            <pre class="contact-message">{detail.message.clone()}</pre>
+
+            <div class="contact-actions">
+                {status_button("read", "Mark read")}
+                {status_button("replied", "Mark replied")}
+                {status_button("spam", "Mark spam")}
+                {status_button("pending", "Mark unread")}
+            </div>
+            {move || {
+                status_action.value().get().and_then(|r| r.err()).map(|_| view! {
+                    <p class="admin-error">"Could not update the status."</p>
+                })
+            }}
+        </article>
+
+        {(!detail.emails.is_empty()).then(|| view! {
+            <section class="admin-card contact-emails">
+                <h2>"Emails"</h2>
+                <ul>
+                    {detail.emails.iter().map(|email| view! {
+                        <li>
+                            <span class=format!("status-badge status-{}", email.status)>
+                                {email.status.clone()}
+                            </span>
+                            " " {email.kind.clone()}
+                            {format!(" · {} attempt(s)", email.attempts)}
+                            {email.last_error.clone().map(|error| view! {
+                                <div class="outbox-error">{error}</div>
+                            })}
+                        </li>
+                    }).collect_view()}
+                </ul>
+            </section>
+        })}
+
+        {(!detail.replies.is_empty()).then(|| view! {
+            <section class="admin-card contact-replies">
+                <h2>"Replies"</h2>
+                {detail.replies.iter().map(|reply| view! {
+                    <div class="contact-reply">
+                        <div class="contact-reply-meta">
+                            <strong>{reply.subject.clone()}</strong>
+                            <span>{format!("{} · {}", reply.sent_by, reply.sent_at)}</span>
+                        </div>
+                        <pre class="contact-message">{reply.body.clone()}</pre>
+                    </div>
+                }).collect_view()}
+            </section>
+        })}
+
+        {(!detail.quarantined).then(|| view! { <ReplyForm detail=detail.clone() reply_action=reply_action/> })}
+    }
+}
+
+/// Compose and send a reply by email
+#[component]
+fn ReplyForm(
+    detail: ContactDetail,
+    reply_action: Action<(String, String), Result<ContactDetail, ServerFnError>>,
+) -> impl IntoView {
+    let subject = RwSignal::new(match &detail.subject {
+        Some(s) if s.starts_with("Re:") => s.clone(),
+        Some(s) => format!("Re: {s}"),
+        None => "Re: Your message".to_string(),
+    });
+
+    // Start with the original message quoted below the reply
+    let quoted: String = detail
+        .message
+        .lines()
+        .map(|line| format!("> {line}\n"))
+        .collect();
+    let body = RwSignal::new(format!(
+        "\n\nOn {}, {} wrote:\n{}",
+        detail.created_at, detail.name, quoted
+    ));
+
+    let pending = reply_action.pending();
+
+    let on_submit = move |ev: leptos::ev::SubmitEvent| {
+        ev.prevent_default();
+        reply_action.dispatch((subject.get(), body.get()));
+    };
+
+    view! {
+        <section class="admin-card contact-reply-form">
+            <h2>{format!("Reply to {}", detail.email)}</h2>
+            <form class="contact-form" on:submit=on_submit>
+                <div class="form-group">
+                    <label for="reply-subject">"Subject"</label>
+                    <input
+                        type="text"
+                        id="reply-subject"
+                        required
+                        maxlength="500"
+                        bind:value=subject
+                        disabled=move || pending.get()
+                    />
+                </div>
+                <div class="form-group">
+                    <label for="reply-body">"Message"</label>
+                    <textarea
+                        id="reply-body"
+                        required
+                        maxlength=MAX_REPLY_LENGTH
+                        rows="12"
+                        bind:value=body
+                        disabled=move || pending.get()
+                    />
+                </div>
+                {move || reply_action.value().get().map(|result| match result {
+                    Ok(_) => view! { <div class="form-message success">"Reply sent."</div> }.into_any(),
+                    Err(e) => view! {
+                        <div class="form-message error">{server_error_message(&e)}</div>
+                    }.into_any(),
+                })}
+                <button type="submit" class="btn btn-primary" disabled=move || pending.get()>
+                    {move || if pending.get() { "Sending…" } else { "Send reply" }}
+                </button>
+            </form>
+        </section>
+    }
+}
+
+/// Message from a server function error, without the error kind prefix
+fn server_error_message(error: &ServerFnError) -> String {
+    match error {
+        ServerFnError::ServerError(message) => message.clone(),
+        other => other.to_string(),
+    }
+}
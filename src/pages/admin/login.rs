@@ -0,0 +1,145 @@
+use leptos::prelude::*;
+use leptos_meta::{Meta, Title};
+use leptos_router::hooks::{use_navigate, use_query_map};
+
+use crate::server_fns::{admin_login, get_admin_session_status, AdminLoginResult};
+
+/// Where to go after login: `?next=` if it is an admin page
+fn login_target(next: Option<String>) -> String {
+    next.filter(|n| n.starts_with("/admin/") && !n.starts_with("/admin/login"))
+        .unwrap_or_else(|| "/admin/analytics".to_string())
+}
+
+/// Admin login form (`/admin/login?next=/admin/...`)
+#[component]
+pub fn AdminLoginPage() -> impl IntoView {
+    let query = use_query_map();
+    let navigate = use_navigate();
+
+    let status = Resource::new(|| (), |_| get_admin_session_status());
+
+    let username = RwSignal::new(String::new());
+    let password = RwSignal::new(String::new());
+    let totp_code = RwSignal::new(String::new());
+    let error = RwSignal::new(Option::<String>::None);
+
+    let login_action = Action::new(move |(username, password, totp_code): &(String, String, String)| {
+        let (username, password, totp_code) = (username.clone(), password.clone(), totp_code.clone());
+        async move { admin_login(username, password, totp_code).await }
+    });
+    let is_submitting = login_action.pending();
+
+    // The admin guard re-checks the session when it mounts
+    let go_to_target = move || {
+        navigate(
+            &login_target(query.get_untracked().get("next")),
+            Default::default(),
+        )
+    };
+
+    let go_on_success = go_to_target.clone();
+    Effect::new(move || match login_action.value().get() {
+        Some(Ok(AdminLoginResult { success: true, .. })) => go_on_success(),
+        Some(Ok(AdminLoginResult { message, .. })) => {
+            password.set(String::new());
+            totp_code.set(String::new());
+            error.set(message);
+        }
+        Some(Err(_)) => error.set(Some("Login failed.".to_string())),
+        None => {}
+    });
+
+    // Already logged in: skip the form
+    Effect::new(move || {
+        if let Some(Ok(s)) = status.get() {
+            if s.username.is_some() {
+                go_to_target();
+            }
+        }
+    });
+
+    let on_submit = move |ev: leptos::ev::SubmitEvent| {
+        ev.prevent_default();
+        error.set(None);
+        login_action.dispatch((username.get(), password.get(), totp_code.get()));
+    };
+
+    view! {
+        <Title text="Login - Admin"/>
+        <Meta name="robots" content="noindex, nofollow"/>
+
+        <div class="admin-page admin-login">
+            <section class="admin-card">
+                <h1>"Admin login"</h1>
+                <Suspense fallback=|| ()>
+                    {move || status.get().map(|status| {
+                        let Ok(status) = status else {
+                            return view! {
+                                <div class="admin-error">"Could not reach the server."</div>
+                            }.into_any();
+                        };
+                        if !status.login_enabled {
+                            return view! {
+                                <p class="admin-note">
+                                    "Admin login is not configured. Set ADMIN_USERNAME and ADMIN_PASSWORD_HASH."
+                                </p>
+                            }.into_any();
+                        }
+                        view! {
+                            <form class="contact-form" on:submit=on_submit>
+                                <div class="form-group">
+                                    <label for="admin-username">"Username"</label>
+                                    <input
+                                        type="text"
+                                        id="admin-username"
+                                        autocomplete="username"
+                                        required
+                                        bind:value=username
+                                        disabled=move || is_submitting.get()
+                                    />
+                                </div>
+                                <div class="form-group">
+                                    <label for="admin-password">"Password"</label>
+                                    <input
+                                        type="password"
+                                        id="admin-password"
+                                        autocomplete="current-password"
+                                        required
+                                        bind:value=password
+                                        disabled=move || is_submitting.get()
+                                    />
+                                </div>
+                                {status.totp_enabled.then(|| view! {
+                                    <div class="form-group">
+                                        <label for="admin-totp">"Authentication code"</label>
+                                        <input
+                                            type="text"
+                                            id="admin-totp"
+                                            inputmode="numeric"
+                                            autocomplete="one-time-code"
+                                            pattern="[0-9]{6}"
+                                            maxlength="6"
+                                            required
+                                            bind:value=totp_code
+                                            disabled=move || is_submitting.get()
+                                        />
+                                    </div>
+                                })}
+                                {move || error.get().map(|message| view! {
+                                    <div class="form-message error">{message}</div>
+                                })}
+                                <button
+                                    type="submit"
+                                    class="btn btn-primary submit-btn"
+                                    disabled=move || is_submitting.get()
+                                >
+                                    {move || if is_submitting.get() { "Signing in…" } else { "Sign in" }}
+                                </button>
+                            </form>
+                        }.into_any()
+                    })}
+                </Suspense>
+            </section>
+        </div>
+    }
+}
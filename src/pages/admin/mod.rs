@@ -0,0 +1,68 @@
+//! Owner-only admin pages (English only, not linked from the public nav)
+
+mod analytics;
+mod inbox;
+mod login;
+mod outbox;
+
+use leptos::prelude::*;
+use leptos_router::components::{Outlet, Redirect};
+use leptos_router::hooks::{use_location, use_navigate};
+use leptos_router::location::Url;
+
+use crate::server_fns::{admin_logout, get_admin_session_status};
+
+pub use analytics::AdminAnalyticsPage;
+pub use inbox::{AdminContactPage, AdminInboxPage};
+pub use login::AdminLoginPage;
+pub use outbox::AdminOutboxPage;
+
+/// Route guard for everything under `/admin` (except the login page).
+/// Server functions check the session themselves; this only keeps logged
+/// out visitors from seeing empty admin pages.
+#[component]
+pub fn AdminGuard() -> impl IntoView {
+    let location = use_location();
+    let status = Resource::new(|| (), |_| get_admin_session_status());
+
+    view! {
+        <Suspense fallback=|| view! {
+            <div class="admin-page admin-loading">
+                <span class="loading-spinner"></span>
+            </div>
+        }>
+            {move || status.get().map(|status| {
+                if status.is_ok_and(|s| s.username.is_some()) {
+                    view! { <Outlet/> }.into_any()
+                } else {
+                    let next = Url::escape(&location.pathname.get_untracked());
+                    view! { <Redirect path=format!("/admin/login?next={next}")/> }.into_any()
+                }
+            })}
+        </Suspense>
+    }
+}
+
+/// Links between the admin pages
+#[component]
+fn AdminNav() -> impl IntoView {
+    let navigate = use_navigate();
+    let logout_action = Action::new(|_: &()| admin_logout());
+
+    Effect::new(move || {
+        if logout_action.value().get().is_some() {
+            navigate("/admin/login", Default::default());
+        }
+    });
+
+    view! {
+        <nav class="admin-nav" aria-label="Admin">
+            <a href="/admin/analytics">"Analytics"</a>
+            <a href="/admin/inbox">"Inbox"</a>
+            <a href="/admin/outbox">"Outbox"</a>
+            <button class="admin-logout" on:click=move |_| { logout_action.dispatch(()); }>
+                "Log out"
+            </button>
+        </nav>
+    }
+}
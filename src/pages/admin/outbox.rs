@@ -0,0 +1,165 @@
+use leptos::prelude::*;
+use leptos_meta::{Meta, Title};
+use leptos_router::hooks::use_query_map;
+
+use super::AdminNav;
+use crate::server_fns::{list_email_outbox, retry_outbox_email, OutboxFolder, OutboxPage};
+
+/// Queued contact emails and their delivery attempts
+/// (`/admin/outbox?status=dead`)
+#[component]
+pub fn AdminOutboxPage() -> impl IntoView {
+    let query_map = use_query_map();
+    let folder = Memo::new(move |_| {
+        query_map
+            .read()
+            .get("status")
+            .and_then(|s| OutboxFolder::from_code(&s))
+            .unwrap_or_default()
+    });
+
+    let retry_action = Action::new(|id: &String| retry_outbox_email(id.clone()));
+
+    // Reload after every retry so the row moves back to pending
+    let outbox = Resource::new(
+        move || (folder.get(), retry_action.version().get()),
+        |(folder, _)| list_email_outbox(folder),
+    );
+
+    view! {
+        <Title text="Outbox - Admin"/>
+        <Meta name="robots" content="noindex, nofollow"/>
+
+        <div class="admin-page">
+            <AdminNav/>
+            <header class="admin-header">
+                <h1>"Email outbox"</h1>
+            </header>
+
+            <Suspense fallback=|| view! {
+                <div class="admin-loading">
+                    <span class="loading-spinner"></span>
+                    <span>"Loading outbox…"</span>
+                </div>
+            }>
+                {move || {
+                    outbox.get().map(|result| match result {
+                        Ok(data) => view! {
+                            <OutboxList data=data folder=folder.get() retry_action=retry_action/>
+                        }.into_any(),
+                        Err(_) => view! {
+                            <div class="admin-error">"Could not load the outbox."</div>
+                        }.into_any(),
+                    })
+                }}
+            </Suspense>
+        </div>
+    }
+}
+
+/// Status tabs and email rows
+#[component]
+fn OutboxList(
+    data: OutboxPage,
+    folder: OutboxFolder,
+    retry_action: Action<String, Result<(), ServerFnError>>,
+) -> impl IntoView {
+    let pending = retry_action.pending();
+
+    let tabs = OutboxFolder::all()
+        .iter()
+        .map(|tab| {
+            let tab = *tab;
+            view! {
+                <a
+                    href=format!("/admin/outbox?status={}", tab.code())
+                    class="filter-tab"
+                    class:active=folder == tab
+                >
+                    {format!("{} ({})", tab.label(), data.stats.for_folder(tab))}
+                </a>
+            }
+        })
+        .collect_view();
+
+    let rows = if data.entries.is_empty() {
+        view! { <p class="chart-empty">"No emails."</p> }.into_any()
+    } else {
+        view! {
+            <table class="admin-table outbox-table">
+                <thead>
+                    <tr>
+                        <th>"Email"</th>
+                        <th>"Status"</th>
+                        <th>"Attempts"</th>
+                        <th>"Last error"</th>
+                        <th>"Queued"</th>
+                        <th></th>
+                    </tr>
+                </thead>
+                <tbody>
+                    {data
+                        .entries
+                        .into_iter()
+                        .map(|entry| {
+                            let id = entry.id.clone();
+                            let is_dead = entry.status == "dead";
+                            view! {
+                                <tr>
+                                    <td>
+                                        <a href=format!("/admin/inbox/{}", entry.submission_id)>
+                                            {entry.kind}
+                                        </a>
+                                    </td>
+                                    <td>
+                                        <span class=format!("status-badge status-{}", entry.status)>
+                                            {entry.status.clone()}
+                                        </span>
+                                        {entry.next_attempt_at.map(|at| view! {
+                                            <div class="inbox-date">"next: " {at}</div>
+                                        })}
+                                        {entry.sent_at.map(|at| view! {
+                                            <div class="inbox-date">{at}</div>
+                                        })}
+                                    </td>
+                                    <td>{entry.attempts}</td>
+                                    <td class="outbox-error">{entry.last_error.unwrap_or_default()}</td>
+                                    <td class="inbox-date">{entry.created_at}</td>
+                                    <td>
+                                        {is_dead.then(|| view! {
+                                            <button
+                                                class="admin-button"
+                                                disabled=move || pending.get()
+                                                on:click=move |_| {
+                                                    retry_action.dispatch(id.clone());
+                                                }
+                                            >
+                                                "Retry"
+                                            </button>
+                                        })}
+                                    </td>
+                                </tr>
+                            }
+                        })
+                        .collect_view()}
+                </tbody>
+            </table>
+        }
+        .into_any()
+    };
+
+    view! {
+        <nav class="filter-tabs" aria-label="Outbox status">{tabs}</nav>
+        {(data.stats.retrying > 0).then(|| view! {
+            <p class="admin-note">
+                {format!("{} pending email(s) failed at least once and will be retried.", data.stats.retrying)}
+            </p>
+        })}
+        {move || {
+            retry_action.value().get().and_then(|r| r.err()).map(|_| view! {
+                <p class="admin-error">"Could not queue the email again."</p>
+            })
+        }}
+        <section class="admin-card">{rows}</section>
+    }
+}
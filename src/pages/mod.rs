@@ -1,9 +1,16 @@
 mod about;
+mod admin;
 mod blog;
 mod cv;
+mod privacy;
 mod projects;
 
 pub use about::AboutPage;
+pub use admin::{
+    AdminAnalyticsPage, AdminContactPage, AdminGuard, AdminInboxPage, AdminLoginPage,
+    AdminOutboxPage,
+};
 pub use blog::{BlogPage, BlogPostPage, BlogTagPage};
 pub use cv::CvPage;
+pub use privacy::{PrivacyDataPage, PrivacyPage};
 pub use projects::ProjectsPage;
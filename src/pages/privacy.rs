@@ -0,0 +1,284 @@
+//! Privacy notice and visitors' access to their contact form data
+//!
+//! `/privacy` explains what is stored and asks for a data link (see
+//! `crate::data_request`); `/privacy/data?token=...` is where that link
+//! leads, to download or erase the messages sent from the address.
+
+use leptos::prelude::*;
+use leptos_meta::{Meta, Title};
+use leptos_router::hooks::use_query_map;
+
+use crate::i18n::{use_i18n, I18nContext};
+use crate::server_fns::{
+    erase_contact_data, get_contact_data, get_contact_retention, request_data_link,
+    ContactDataExport, ContactRetentionPolicy,
+};
+
+/// Privacy notice with the data link request form
+#[component]
+pub fn PrivacyPage() -> impl IntoView {
+    let i18n = use_i18n();
+
+    view! {
+        <Title text="Privacy - Kovács Krisztián Géza"/>
+
+        <div class="about-page privacy-page">
+            <header class="page-header">
+                <h1>{move || i18n.t().privacy_page_title}</h1>
+                <p class="page-subtitle">{move || i18n.t().privacy_page_subtitle}</p>
+            </header>
+
+            <section class="privacy-section">
+                <h2>{move || i18n.t().privacy_contact_title}</h2>
+                <p>{move || i18n.t().privacy_contact_text}</p>
+            </section>
+
+            <section class="privacy-section">
+                <h2>{move || i18n.t().privacy_retention_title}</h2>
+                <p>{move || i18n.t().privacy_retention_text}</p>
+                <RetentionList i18n=i18n/>
+            </section>
+
+            <section class="privacy-section">
+                <h2>{move || i18n.t().privacy_analytics_title}</h2>
+                <p>{move || i18n.t().privacy_analytics_text}</p>
+            </section>
+
+            <section class="privacy-section">
+                <h2>{move || i18n.t().privacy_rights_title}</h2>
+                <p>{move || i18n.t().privacy_rights_text}</p>
+                <DataRequestForm i18n=i18n/>
+            </section>
+        </div>
+    }
+}
+
+/// Configured retention period of each kind of submission
+#[component]
+fn RetentionList(i18n: I18nContext) -> impl IntoView {
+    let retention = Resource::new(|| (), |_| get_contact_retention());
+
+    let row = move |label: fn(&crate::i18n::Translations) -> &'static str, days: Option<i64>| {
+        view! {
+            <li>
+                <span>{move || label(&i18n.t())}</span>
+                ": "
+                <strong>
+                    {move || match days {
+                        Some(days) => format!("{} {}", days, i18n.t().privacy_retention_days),
+                        None => i18n.t().privacy_retention_forever.to_string(),
+                    }}
+                </strong>
+            </li>
+        }
+    };
+
+    view! {
+        <Suspense fallback=|| ()>
+            {move || retention.get().and_then(Result::ok).map(|policy: ContactRetentionPolicy| view! {
+                <ul class="privacy-data-list">
+                    {row(|t| t.privacy_retention_pending, policy.pending)}
+                    {row(|t| t.privacy_retention_read, policy.read)}
+                    {row(|t| t.privacy_retention_replied, policy.replied)}
+                    {row(|t| t.privacy_retention_spam, policy.spam)}
+                    {row(|t| t.privacy_retention_quarantine, policy.quarantine)}
+                </ul>
+            })}
+        </Suspense>
+    }
+}
+
+/// Email field that asks for a data link
+#[component]
+fn DataRequestForm(i18n: I18nContext) -> impl IntoView {
+    let email = RwSignal::new(String::new());
+    let request_action = Action::new(move |email: &String| {
+        request_data_link(email.clone(), i18n.language.get_untracked())
+    });
+    let pending = request_action.pending();
+
+    let on_submit = move |ev: leptos::ev::SubmitEvent| {
+        ev.prevent_default();
+        request_action.dispatch(email.get_untracked());
+    };
+
+    let result = move || {
+        request_action.value().get().map(|result| {
+            let t = i18n.t();
+            let (success, msg) = match result {
+                Ok(res) => (res.success, match res.message_key.as_str() {
+                    "privacy_request_sent" => t.privacy_request_sent,
+                    "privacy_error_email" => t.privacy_error_email,
+                    "privacy_error_rate_limit" => t.privacy_error_rate_limit,
+                    _ => t.contact_error_generic,
+                }),
+                Err(_) => (false, t.contact_error_generic),
+            };
+            let class = if success { "form-message success" } else { "form-message error" };
+            view! { <div class=class>{msg}</div> }
+        })
+    };
+
+    view! {
+        <form class="contact-form privacy-form" on:submit=on_submit>
+            <div class="form-group">
+                <label for="privacy-email">{move || i18n.t().contact_email}</label>
+                <input
+                    type="email"
+                    id="privacy-email"
+                    name="email"
+                    required
+                    maxlength="255"
+                    bind:value=email
+                    disabled=move || pending.get()
+                />
+            </div>
+            {result}
+            <button type="submit" class="btn btn-primary submit-btn" disabled=move || pending.get()>
+                {move || {
+                    if pending.get() {
+                        i18n.t().privacy_requesting
+                    } else {
+                        i18n.t().privacy_request_button
+                    }
+                }}
+            </button>
+        </form>
+    }
+}
+
+/// Messages reachable through a data link, with download and erase
+#[component]
+pub fn PrivacyDataPage() -> impl IntoView {
+    let i18n = use_i18n();
+    let query_map = use_query_map();
+    let token = Memo::new(move |_| query_map.read().get("token").unwrap_or_default());
+
+    let erase_action = Action::new(|token: &String| erase_contact_data(token.clone()));
+    let data = Resource::new(move || token.get(), get_contact_data);
+
+    view! {
+        <Title text="Privacy - Kovács Krisztián Géza"/>
+        // The link is a credential; keep it out of search engines
+        <Meta name="robots" content="noindex, nofollow"/>
+
+        <div class="about-page privacy-page">
+            <header class="page-header">
+                <h1>{move || i18n.t().privacy_data_title}</h1>
+            </header>
+
+            <section class="privacy-section">
+                {move || match erase_action.value().get() {
+                    Some(Ok(Some(_))) => view! {
+                        <div class="form-message success">{i18n.t().privacy_data_erased}</div>
+                    }.into_any(),
+                    Some(Ok(None)) => view! {
+                        <div class="form-message error">{i18n.t().privacy_data_invalid}</div>
+                    }.into_any(),
+                    Some(Err(_)) => view! {
+                        <div class="form-message error">{i18n.t().contact_error_generic}</div>
+                    }.into_any(),
+                    None => view! {
+                        <Suspense fallback=move || view! { <p>{i18n.t().privacy_data_loading}</p> }>
+                            {move || data.get().map(|result| match result {
+                                Ok(Some(export)) => view! {
+                                    <ContactData export=export token=token.get() erase_action=erase_action i18n=i18n/>
+                                }.into_any(),
+                                Ok(None) => view! {
+                                    <div class="form-message error">{i18n.t().privacy_data_invalid}</div>
+                                }.into_any(),
+                                Err(_) => view! {
+                                    <div class="form-message error">{i18n.t().contact_error_generic}</div>
+                                }.into_any(),
+                            })}
+                        </Suspense>
+                    }.into_any(),
+                }}
+            </section>
+        </div>
+    }
+}
+
+/// Submission list and the download / erase buttons
+#[component]
+fn ContactData(
+    export: ContactDataExport,
+    token: String,
+    erase_action: Action<String, Result<Option<i32>, ServerFnError>>,
+    i18n: I18nContext,
+) -> impl IntoView {
+    if export.submissions.is_empty() {
+        return view! { <p>{move || i18n.t().privacy_data_empty}</p> }.into_any();
+    }
+
+    let confirming = RwSignal::new(false);
+    let pending = erase_action.pending();
+    let download_href = json_data_url(&export);
+    let rows = export
+        .submissions
+        .iter()
+        .map(|submission| {
+            let date = submission.created_at.get(..10).unwrap_or_default().to_string();
+            let subject = submission.subject.clone();
+            view! {
+                <li>
+                    <span class="privacy-data-date">{date}</span>
+                    " "
+                    {move || subject.clone().unwrap_or_else(|| i18n.t().privacy_data_no_subject.to_string())}
+                </li>
+            }
+        })
+        .collect_view();
+    let count = export.submissions.len();
+
+    view! {
+        <p>{move || i18n.t().privacy_data_count} " " <strong>{count}</strong></p>
+        <ul class="privacy-data-list">{rows}</ul>
+
+        <div class="privacy-data-actions">
+            <a class="btn btn-primary" href=download_href download="contact-data.json">
+                {move || i18n.t().privacy_data_download}
+            </a>
+            <Show
+                when=move || confirming.get()
+                fallback=move || view! {
+                    <button class="btn btn-secondary" on:click=move |_| confirming.set(true)>
+                        {move || i18n.t().privacy_data_erase}
+                    </button>
+                }
+            >
+                <p class="form-message error">{move || i18n.t().privacy_data_erase_warning}</p>
+                <button
+                    class="btn btn-primary"
+                    disabled=move || pending.get()
+                    on:click={
+                        let token = token.clone();
+                        move |_| {
+                            erase_action.dispatch(token.clone());
+                        }
+                    }
+                >
+                    {move || i18n.t().privacy_data_erase_confirm}
+                </button>
+                <button class="btn btn-secondary" on:click=move |_| confirming.set(false)>
+                    {move || i18n.t().privacy_data_cancel}
+                </button>
+            </Show>
+        </div>
+    }
+    .into_any()
+}
+
+/// `data:` URL of the export as pretty-printed JSON, for the download link
+fn json_data_url(export: &ContactDataExport) -> String {
+    let json = serde_json::to_string_pretty(&export.submissions).unwrap_or_default();
+    let mut url = String::from("data:application/json;charset=utf-8,");
+    for byte in json.bytes() {
+        if byte.is_ascii_alphanumeric() || b"-_.~".contains(&byte) {
+            url.push(byte as char);
+        } else {
+            url.push_str(&format!("%{:02X}", byte));
+        }
+    }
+    url
+}
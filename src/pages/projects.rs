@@ -1,5 +1,6 @@
 use leptos::prelude::*;
 
+use crate::analytics::use_track_event;
 use crate::i18n::{use_i18n, I18nContext};
 use crate::server_fns::{get_github_repos, GithubRepoDisplay};
 
@@ -321,11 +322,22 @@ fn GithubRepoCard(repo: GithubRepoDisplay, i18n: I18nContext) -> impl IntoView {
     let html_url = repo.html_url.clone();
     let html_url_footer = repo.html_url.clone();
 
+    // Outbound click tracking (links open in a new tab)
+    let tracker = use_track_event();
+    let repo_name = repo.name.clone();
+    let track_click = move |_| {
+        tracker.track(
+            "github_repo_click",
+            "outbound",
+            Some(serde_json::json!({ "repo": repo_name.clone() })),
+        );
+    };
+
     view! {
         <article class="github-card">
             <div class="github-card-header">
                 <h3 class="repo-name">
-                    <a href=html_url target="_blank" rel="noopener noreferrer">
+                    <a href=html_url target="_blank" rel="noopener noreferrer" on:click=track_click.clone()>
                         {repo.name.clone()}
                     </a>
                 </h3>
@@ -373,7 +385,7 @@ fn GithubRepoCard(repo: GithubRepoDisplay, i18n: I18nContext) -> impl IntoView {
                         </span>
                     }
                 })}
-                <a href=html_url_footer target="_blank" rel="noopener noreferrer" class="github-link">
+                <a href=html_url_footer target="_blank" rel="noopener noreferrer" class="github-link" on:click=track_click>
                     {move || i18n.t().github_view_on_github}
                     <span>" →"</span>
                 </a>
@@ -0,0 +1,117 @@
+//! Hashcash-style proof of work for the contact form
+//!
+//! The challenge is the signed form token (see `spam::FormTokenKey`), which
+//! also carries the difficulty. A solution is a counter such that
+//! SHA-256(`{token}:{counter}`) starts with at least `difficulty` zero bits,
+//! about 2^difficulty hashes of work for the client and one for the server.
+//!
+//! `CONTACT_POW_DIFFICULTY` sets the base difficulty (0, the default,
+//! disables the challenge). It rises by one bit each time recent contact
+//! attempts double past `POW_PRESSURE_STEP`, up to `MAX_POW_DIFFICULTY`.
+
+use sha2::{Digest, Sha256};
+
+/// Hardest challenge ever issued (~16M hashes)
+pub const MAX_POW_DIFFICULTY: u32 = 24;
+
+/// Extra bits added at most under load
+pub const MAX_POW_EXTRA_BITS: u32 = 6;
+
+/// Recent contact attempts before the difficulty starts rising
+pub const POW_PRESSURE_STEP: i64 = 10;
+
+fn hash(token: &str, counter: u64) -> [u8; 32] {
+    Sha256::digest(format!("{token}:{counter}").as_bytes()).into()
+}
+
+fn leading_zero_bits(hash: &[u8]) -> u32 {
+    let mut bits = 0;
+    for byte in hash {
+        bits += byte.leading_zeros();
+        if *byte != 0 {
+            break;
+        }
+    }
+    bits
+}
+
+/// True if `counter` solves the challenge
+pub fn is_solution(token: &str, counter: u64, difficulty: u32) -> bool {
+    difficulty == 0 || leading_zero_bits(&hash(token, counter)) >= difficulty
+}
+
+/// Try `count` counters starting at `from`; the client calls this in slices
+/// so the page stays responsive
+pub fn search(token: &str, difficulty: u32, from: u64, count: u64) -> Option<u64> {
+    (from..from.saturating_add(count)).find(|counter| is_solution(token, *counter, difficulty))
+}
+
+/// Proof-of-work settings loaded from environment variables
+#[cfg(feature = "ssr")]
+#[derive(Debug, Clone, Copy)]
+pub struct PowConfig {
+    pub base_difficulty: u32,
+}
+
+#[cfg(feature = "ssr")]
+impl PowConfig {
+    /// Load settings from environment variables (disabled by default)
+    pub fn from_env() -> Self {
+        Self {
+            base_difficulty: std::env::var("CONTACT_POW_DIFFICULTY")
+                .ok()
+                .and_then(|d| d.parse().ok())
+                .unwrap_or(0)
+                .min(MAX_POW_DIFFICULTY),
+        }
+    }
+
+    /// Difficulty for a new challenge, given the contact attempts recorded
+    /// in the current rate limit window
+    pub fn difficulty(&self, recent_attempts: i64) -> u32 {
+        if self.base_difficulty == 0 {
+            return 0;
+        }
+        let extra = match recent_attempts / POW_PRESSURE_STEP {
+            0 => 0,
+            steps => (steps.ilog2() + 1).min(MAX_POW_EXTRA_BITS),
+        };
+        (self.base_difficulty + extra).min(MAX_POW_DIFFICULTY)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn finds_and_checks_solutions() {
+        let token = "1000.8.00112233445566778899aabbccddeeff.abcd";
+        let counter = search(token, 8, 0, 1 << 16).expect("8 bits are found quickly");
+        assert!(is_solution(token, counter, 8));
+        assert!(leading_zero_bits(&hash(token, counter)) >= 8);
+        assert!((0..counter).all(|c| !is_solution(token, c, 8)));
+        assert!(is_solution(token, 12345, 0));
+    }
+
+    #[cfg(feature = "ssr")]
+    #[test]
+    fn difficulty_rises_with_pressure() {
+        let config = PowConfig {
+            base_difficulty: 16,
+        };
+        assert_eq!(config.difficulty(0), 16);
+        assert_eq!(config.difficulty(9), 16);
+        assert_eq!(config.difficulty(10), 17);
+        assert_eq!(config.difficulty(40), 19);
+        assert_eq!(config.difficulty(1_000_000), 22);
+        assert_eq!(PowConfig { base_difficulty: 0 }.difficulty(1_000), 0);
+        assert_eq!(
+            PowConfig {
+                base_difficulty: 23
+            }
+            .difficulty(1_000),
+            MAX_POW_DIFFICULTY
+        );
+    }
+}
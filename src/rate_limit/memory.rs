@@ -0,0 +1,123 @@
+//! In-memory token buckets
+//!
+//! Each (policy, key) bucket holds up to `max_requests` tokens and refills
+//! at `max_requests` per window. Keys arrive already hashed, so raw
+//! identifiers are never kept. Counters reset on restart and are not shared
+//! between instances.
+
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use super::{Decision, HitFuture, RateLimiter, RatePolicy};
+
+/// Buckets kept before full (idle) ones are dropped
+const MAX_BUCKETS: usize = 10_000;
+
+#[derive(Debug, Clone, Copy)]
+struct Bucket {
+    tokens: f64,
+    updated: Instant,
+    /// When the bucket will be full again (and can be forgotten)
+    full_at: Instant,
+}
+
+/// Token bucket limiter held in process memory
+pub struct MemoryRateLimiter {
+    buckets: Mutex<HashMap<(&'static str, String), Bucket>>,
+}
+
+impl Default for MemoryRateLimiter {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl MemoryRateLimiter {
+    pub fn new() -> Self {
+        Self {
+            buckets: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Take a token at `now`
+    fn take(&self, policy: &RatePolicy, key: &str, now: Instant) -> Decision {
+        let capacity = f64::from(policy.max_requests.max(1));
+        let per_second = capacity / policy.window.as_secs_f64().max(1.0);
+        let refill = |bucket: &Bucket| {
+            let elapsed = now.saturating_duration_since(bucket.updated).as_secs_f64();
+            (bucket.tokens + elapsed * per_second).min(capacity)
+        };
+
+        let mut buckets = self.buckets.lock().unwrap_or_else(|e| e.into_inner());
+        if buckets.len() >= MAX_BUCKETS {
+            buckets.retain(|_, bucket| bucket.full_at > now);
+        }
+
+        let bucket = buckets
+            .entry((policy.name, key.to_string()))
+            .or_insert(Bucket {
+                tokens: capacity,
+                updated: now,
+                full_at: now,
+            });
+        bucket.tokens = refill(bucket);
+        bucket.updated = now;
+
+        let decision = if bucket.tokens >= 1.0 {
+            bucket.tokens -= 1.0;
+            Decision::Allowed
+        } else {
+            Decision::Limited {
+                retry_after: Duration::from_secs_f64((1.0 - bucket.tokens) / per_second),
+            }
+        };
+        bucket.full_at = now + Duration::from_secs_f64((capacity - bucket.tokens) / per_second);
+        decision
+    }
+}
+
+impl RateLimiter for MemoryRateLimiter {
+    fn hit<'a>(&'a self, policy: &'a RatePolicy, keys: &'a [String]) -> HitFuture<'a> {
+        let key = keys.first().map(String::as_str).unwrap_or_default();
+        let decision = self.take(policy, key, Instant::now());
+        Box::pin(async move { Ok(decision) })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rate_limit::Store;
+
+    const POLICY: RatePolicy = RatePolicy {
+        name: "test",
+        max_requests: 3,
+        window: Duration::from_secs(60),
+        store: Store::Memory,
+        reject_with_status: true,
+    };
+
+    #[test]
+    fn bucket_allows_a_burst_then_refills() {
+        let limiter = MemoryRateLimiter::new();
+        let start = Instant::now();
+
+        for _ in 0..3 {
+            assert!(limiter.take(&POLICY, "a", start).is_allowed());
+        }
+        let limited = limiter.take(&POLICY, "a", start);
+        assert_eq!(limited.retry_after_secs(), Some(20));
+
+        // Other keys have their own bucket
+        assert!(limiter.take(&POLICY, "b", start).is_allowed());
+
+        // One token every 20 seconds
+        assert!(limiter
+            .take(&POLICY, "a", start + Duration::from_secs(20))
+            .is_allowed());
+        assert!(!limiter
+            .take(&POLICY, "a", start + Duration::from_secs(21))
+            .is_allowed());
+    }
+}
@@ -0,0 +1,196 @@
+//! Rate limiting for server functions
+//!
+//! Every limited server function has a [`RatePolicy`]: a request budget per
+//! window, and the store that keeps count.
+//! - [`Store::Postgres`]: fixed window, one atomic upsert per request into
+//!   `rate_limits`. Survives restarts and is shared by every instance; for
+//!   low-volume, abuse-prone actions (contact form, admin login).
+//! - [`Store::Memory`]: token bucket in process memory, for high-volume
+//!   requests where a database write per request is the cost being limited
+//!   (analytics beacons, GitHub refreshes).
+//!
+//! Limited requests get a `Retry-After` header, and a 429 status when the
+//! policy's client does not read the response body. Callers pass the
+//! client IP resolved by `crate::client_ip`; counters only ever see its
+//! keyed hash (see `crate::ip_hash`), made with the policy name as purpose.
+
+mod memory;
+mod postgres;
+
+pub use memory::MemoryRateLimiter;
+pub use postgres::PgRateLimiter;
+
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::time::Duration;
+
+use axum::http::{header, HeaderValue, StatusCode};
+use leptos::prelude::use_context;
+use leptos_axum::ResponseOptions;
+use sqlx::PgPool;
+
+use crate::ip_hash::IpHasher;
+
+/// Future returned by [`RateLimiter::hit`]
+pub type HitFuture<'a> = Pin<Box<dyn Future<Output = Result<Decision, sqlx::Error>> + Send + 'a>>;
+
+/// Somewhere to count requests
+pub trait RateLimiter: Send + Sync {
+    /// Count one request and decide whether it may proceed. `keys` is the
+    /// client's hash under each configured key, current first; stores that
+    /// outlive a key rotation carry older counters over to the current key.
+    fn hit<'a>(&'a self, policy: &'a RatePolicy, keys: &'a [String]) -> HitFuture<'a>;
+}
+
+/// Where a policy's counters live
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Store {
+    Postgres,
+    Memory,
+}
+
+/// Request budget for one server function or route
+#[derive(Debug, Clone, Copy)]
+pub struct RatePolicy {
+    /// Unique name, stored with each counter
+    pub name: &'static str,
+    /// Requests allowed per window (also the token bucket's burst size)
+    pub max_requests: u32,
+    pub window: Duration,
+    pub store: Store,
+    /// Answer limited requests with 429 Too Many Requests. Off for server
+    /// functions that report the limit in their result, which the client
+    /// would otherwise fail to read.
+    pub reject_with_status: bool,
+}
+
+/// Contact form submissions per IP hash
+pub const CONTACT_POLICY: RatePolicy = RatePolicy {
+    name: "contact",
+    max_requests: 3,
+    window: Duration::from_secs(60 * 60),
+    store: Store::Postgres,
+    reject_with_status: false,
+};
+
+/// Admin login attempts (failed or successful) per IP hash
+pub const ADMIN_LOGIN_POLICY: RatePolicy = RatePolicy {
+    name: "admin_login",
+    max_requests: 5,
+    window: Duration::from_secs(15 * 60),
+    store: Store::Postgres,
+    reject_with_status: false,
+};
+
+/// Data link requests per IP hash
+pub const DATA_REQUEST_POLICY: RatePolicy = RatePolicy {
+    name: "data_request",
+    max_requests: 5,
+    window: Duration::from_secs(60 * 60),
+    store: Store::Postgres,
+    reject_with_status: false,
+};
+
+/// Data link emails per recipient address hash, so the form cannot be used
+/// to flood someone's inbox
+pub const DATA_REQUEST_EMAIL_POLICY: RatePolicy = RatePolicy {
+    name: "data_request_email",
+    max_requests: 3,
+    window: Duration::from_secs(24 * 60 * 60),
+    store: Store::Postgres,
+    reject_with_status: false,
+};
+
+/// Page views, engagement and event batches per visitor IP
+pub const ANALYTICS_POLICY: RatePolicy = RatePolicy {
+    name: "analytics",
+    max_requests: 120,
+    window: Duration::from_secs(60),
+    store: Store::Memory,
+    reject_with_status: true,
+};
+
+/// GitHub API refreshes, for the whole site
+pub const GITHUB_REFRESH_POLICY: RatePolicy = RatePolicy {
+    name: "github_refresh",
+    max_requests: 1,
+    window: Duration::from_secs(5 * 60),
+    store: Store::Memory,
+    reject_with_status: true,
+};
+
+/// Outcome of a rate limit check
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Decision {
+    Allowed,
+    Limited { retry_after: Duration },
+}
+
+impl Decision {
+    pub fn is_allowed(&self) -> bool {
+        matches!(self, Decision::Allowed)
+    }
+
+    /// `Retry-After` value in whole seconds (at least 1)
+    pub fn retry_after_secs(&self) -> Option<u64> {
+        match self {
+            Decision::Allowed => None,
+            Decision::Limited { retry_after } => {
+                Some(retry_after.as_secs_f64().ceil().max(1.0) as u64)
+            }
+        }
+    }
+}
+
+/// The limiter for each store, shared through the Leptos context
+#[derive(Clone)]
+pub struct RateLimits {
+    postgres: Arc<dyn RateLimiter>,
+    memory: Arc<dyn RateLimiter>,
+    hasher: IpHasher,
+}
+
+impl RateLimits {
+    pub fn new(pool: PgPool, hasher: IpHasher) -> Self {
+        Self {
+            postgres: Arc::new(PgRateLimiter::new(pool)),
+            memory: Arc::new(MemoryRateLimiter::new()),
+            hasher,
+        }
+    }
+
+    /// Count a request from `client` (an IP, or a fixed name for site-wide
+    /// policies) without touching the response
+    pub async fn hit(&self, policy: &RatePolicy, client: &str) -> Result<Decision, sqlx::Error> {
+        match policy.store {
+            Store::Postgres => {
+                let keys = self.hasher.hashes(policy.name, client);
+                self.postgres.hit(policy, &keys).await
+            }
+            // Memory counters start over on restart, which a key rotation
+            // needs anyway, so only the current key matters
+            Store::Memory => {
+                let keys = [self.hasher.hash(policy.name, client)];
+                self.memory.hit(policy, &keys).await
+            }
+        }
+    }
+
+    /// Count a request from a server function. When it is limited, add
+    /// `Retry-After` (and a 429 status, if the policy asks for it) to the
+    /// response.
+    pub async fn check(&self, policy: &RatePolicy, client: &str) -> Result<Decision, sqlx::Error> {
+        let response = use_context::<ResponseOptions>();
+        let decision = self.hit(policy, client).await?;
+
+        if let (Some(response), Some(secs)) = (response, decision.retry_after_secs()) {
+            tracing::info!(policy = policy.name, retry_after = secs, "Rate limited");
+            response.insert_header(header::RETRY_AFTER, HeaderValue::from(secs));
+            if policy.reject_with_status {
+                response.set_status(StatusCode::TOO_MANY_REQUESTS);
+            }
+        }
+        Ok(decision)
+    }
+}
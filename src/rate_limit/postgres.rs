@@ -0,0 +1,35 @@
+//! Postgres-backed fixed windows
+
+use std::time::Duration;
+
+use sqlx::PgPool;
+
+use super::{Decision, HitFuture, RateLimiter, RatePolicy};
+use crate::db::record_rate_limit_hit;
+
+/// Counts requests in the `rate_limits` table
+pub struct PgRateLimiter {
+    pool: PgPool,
+}
+
+impl PgRateLimiter {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+}
+
+impl RateLimiter for PgRateLimiter {
+    fn hit<'a>(&'a self, policy: &'a RatePolicy, keys: &'a [String]) -> HitFuture<'a> {
+        Box::pin(async move {
+            let (key, previous) = keys.split_first().expect("at least the current key");
+            let hit = record_rate_limit_hit(&self.pool, policy, key, previous).await?;
+            Ok(if hit.hits <= policy.max_requests as i32 {
+                Decision::Allowed
+            } else {
+                Decision::Limited {
+                    retry_after: Duration::from_secs_f64(hit.resets_in),
+                }
+            })
+        })
+    }
+}
@@ -0,0 +1,174 @@
+//! Admin analytics dashboard server function
+//!
+//! Reads only the daily rollup tables, so the dashboard keeps working after
+//! raw page views have been purged by the maintenance job.
+
+use leptos::prelude::*;
+use serde::{Deserialize, Serialize};
+
+/// Date ranges offered by the dashboard (in days)
+pub const DASHBOARD_RANGES: &[u32] = &[7, 30, 90, 365];
+
+/// Default dashboard range (in days)
+pub const DEFAULT_DASHBOARD_DAYS: u32 = 30;
+
+/// A labelled value for charts and tables
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ChartPoint {
+    pub label: String,
+    pub value: i64,
+}
+
+/// Views and engagement for one page
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PageStats {
+    pub path: String,
+    /// Display name (post title for blog posts, otherwise the path)
+    pub label: String,
+    pub views: i64,
+    pub avg_time_seconds: Option<i64>,
+    pub avg_scroll_percent: Option<i64>,
+}
+
+/// Everything shown on the analytics dashboard
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AnalyticsDashboard {
+    pub days: u32,
+    pub total_views: i64,
+    pub daily_views: Vec<ChartPoint>,
+    pub top_pages: Vec<PageStats>,
+    pub top_posts: Vec<PageStats>,
+    pub referrers: Vec<ChartPoint>,
+    pub devices: Vec<ChartPoint>,
+    pub browsers: Vec<ChartPoint>,
+    pub operating_systems: Vec<ChartPoint>,
+    pub countries: Vec<ChartPoint>,
+    pub events: Vec<ChartPoint>,
+    pub avg_time_on_page_seconds: Option<i64>,
+    pub avg_scroll_depth_percent: Option<i64>,
+}
+
+/// Fetch the analytics dashboard for the last `days` days (owner only)
+#[server]
+pub async fn get_analytics_dashboard(days: u32) -> Result<AnalyticsDashboard, ServerFnError> {
+    use crate::blog::BlogPost;
+    use crate::db::{
+        daily_views_since, dimension_counts_since, engagement_totals_since,
+        top_page_rollups_since, PageRollup, RollupDimension,
+    };
+    use chrono::{Duration, Utc};
+
+    const TOP_LIMIT: i64 = 10;
+
+    let pool = expect_context::<sqlx::PgPool>();
+    crate::admin::require_admin(&pool).await?;
+
+    let days = days.clamp(1, 365);
+    let today = Utc::now().date_naive();
+    let since = today - Duration::days(i64::from(days) - 1);
+
+    let db_error = |e: sqlx::Error| {
+        tracing::error!(?e, "Failed to load analytics dashboard");
+        ServerFnError::new("Failed to load analytics")
+    };
+
+    // Fill in days without views so the chart has a continuous x axis
+    let daily = daily_views_since(&pool, since).await.map_err(db_error)?;
+    let daily_views: Vec<ChartPoint> = since
+        .iter_days()
+        .take_while(|day| *day <= today)
+        .map(|day| ChartPoint {
+            label: day.format("%b %d").to_string(),
+            value: daily
+                .iter()
+                .find(|d| d.day == day)
+                .map(|d| d.views)
+                .unwrap_or(0),
+        })
+        .collect();
+    let total_views = daily.iter().map(|d| d.views).sum();
+
+    let average = |total: i64, samples: i64| (samples > 0).then(|| total / samples);
+    let page_stats = |row: PageRollup, label: String| PageStats {
+        avg_time_seconds: average(row.total_time_seconds, row.time_samples),
+        avg_scroll_percent: average(row.total_scroll_percent, row.scroll_samples),
+        path: row.path,
+        label,
+        views: row.views,
+    };
+
+    let top_pages = top_page_rollups_since(&pool, since, None, TOP_LIMIT)
+        .await
+        .map_err(db_error)?
+        .into_iter()
+        .map(|row| {
+            let label = row.path.clone();
+            page_stats(row, label)
+        })
+        .collect();
+
+    // Blog posts are the /blog/<slug> paths that match a known post
+    let top_posts = top_page_rollups_since(&pool, since, Some("/blog/"), TOP_LIMIT * 2)
+        .await
+        .map_err(db_error)?
+        .into_iter()
+        .filter_map(|row| {
+            let slug = row.path.trim_start_matches("/blog/").trim_end_matches('/');
+            let title = BlogPost::find_by_slug(slug)?.title_en.to_string();
+            Some(page_stats(row, title))
+        })
+        .take(TOP_LIMIT as usize)
+        .collect();
+
+    let dimension = |dimension: RollupDimension, blank: &'static str| {
+        let pool = pool.clone();
+        async move {
+            dimension_counts_since(&pool, dimension, since, TOP_LIMIT)
+                .await
+                .map_err(db_error)
+                .map(|rows| {
+                    rows.into_iter()
+                        .map(|row| ChartPoint {
+                            label: if row.name.is_empty() {
+                                blank.to_string()
+                            } else {
+                                row.name
+                            },
+                            value: row.count,
+                        })
+                        .collect::<Vec<_>>()
+                })
+        }
+    };
+
+    let referrers = dimension(RollupDimension::Referrer, "(direct)").await?;
+    let devices = dimension(RollupDimension::DeviceType, "unknown").await?;
+    let browsers = dimension(RollupDimension::Browser, "Other").await?;
+    let operating_systems = dimension(RollupDimension::OperatingSystem, "Other").await?;
+    let countries = dimension(RollupDimension::Country, "Unknown").await?;
+    let events = dimension(RollupDimension::Event, "(unnamed)").await?;
+
+    let engagement = engagement_totals_since(&pool, since).await.map_err(db_error)?;
+
+    Ok(AnalyticsDashboard {
+        days,
+        total_views,
+        daily_views,
+        top_pages,
+        top_posts,
+        referrers,
+        devices,
+        browsers,
+        operating_systems,
+        countries,
+        events,
+        avg_time_on_page_seconds: average(
+            engagement.total_time_seconds,
+            engagement.time_samples,
+        ),
+        avg_scroll_depth_percent: average(
+            engagement.total_scroll_percent,
+            engagement.scroll_samples,
+        ),
+    })
+}
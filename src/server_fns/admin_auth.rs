@@ -0,0 +1,179 @@
+//! Admin login / logout server functions
+
+use leptos::prelude::*;
+use serde::{Deserialize, Serialize};
+
+/// Login state shown by the admin route guard and login page
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AdminSessionStatus {
+    /// Logged-in admin username, if any
+    pub username: Option<String>,
+    /// Whether login is configured on this server
+    pub login_enabled: bool,
+    /// Whether a TOTP code is required at login
+    pub totp_enabled: bool,
+}
+
+/// Admin login result
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AdminLoginResult {
+    pub success: bool,
+    pub message: Option<String>,
+}
+
+/// Current admin login state
+#[server]
+pub async fn get_admin_session_status() -> Result<AdminSessionStatus, ServerFnError> {
+    use crate::admin::{current_admin, AdminAuthConfig};
+
+    let config = use_context::<AdminAuthConfig>();
+    let pool = expect_context::<sqlx::PgPool>();
+
+    Ok(AdminSessionStatus {
+        username: if config.is_some() {
+            current_admin(&pool).await?
+        } else {
+            None
+        },
+        login_enabled: config.is_some(),
+        totp_enabled: config.as_ref().is_some_and(|c| c.totp_enabled()),
+    })
+}
+
+/// Log in and set the admin session cookie
+#[server]
+pub async fn admin_login(
+    username: String,
+    password: String,
+    totp_code: String,
+) -> Result<AdminLoginResult, ServerFnError> {
+    use crate::admin::{hash_session_token, new_session_token, session_cookie, AdminAuthConfig};
+    use crate::client_ip::TrustedProxies;
+    use crate::ip_hash::IpHasher;
+    use crate::db::{claim_totp_step, insert_admin_session, NewAdminSession};
+    use crate::rate_limit::{RateLimits, ADMIN_LOGIN_POLICY};
+    use axum::http::{header, request::Parts, HeaderValue};
+    use leptos_axum::{extract, ResponseOptions};
+
+    let failure = |message: &str| {
+        Ok(AdminLoginResult {
+            success: false,
+            message: Some(message.to_string()),
+        })
+    };
+
+    let Some(config) = use_context::<AdminAuthConfig>() else {
+        return failure("Admin login is not configured.");
+    };
+
+    let pool = expect_context::<sqlx::PgPool>();
+    let response = expect_context::<ResponseOptions>();
+    let rate_limits = expect_context::<RateLimits>();
+    let trusted_proxies = expect_context::<TrustedProxies>();
+    let parts: Parts = extract().await?;
+    let ip = trusted_proxies.client_ip(&parts).to_string();
+
+    // Every attempt counts, so guessing is capped per window
+    match rate_limits.check(&ADMIN_LOGIN_POLICY, &ip).await {
+        Ok(decision) if decision.is_allowed() => {}
+        Ok(_) => {
+            tracing::warn!("Admin login rate limited");
+            return failure("Too many login attempts. Try again later.");
+        }
+        Err(e) => {
+            tracing::error!(?e, "Login rate limit check failed");
+            return Err(ServerFnError::new("Database error"));
+        }
+    }
+
+    if username.len() > 255 || password.len() > 1024 || totp_code.len() > 16 {
+        return failure("Invalid credentials.");
+    }
+
+    // argon2 is deliberately slow, keep it off the async workers
+    let verify_config = config.clone();
+    let now = chrono::Utc::now().timestamp().max(0) as u64;
+    let verified = tokio::task::spawn_blocking(move || {
+        verify_config.verify(&username, &password, &totp_code, now)
+    })
+    .await
+    .map_err(|e| {
+        tracing::error!(?e, "Password verification task failed");
+        ServerFnError::new("Login failed")
+    })?;
+
+    let Some(totp_step) = verified else {
+        tracing::warn!("Failed admin login attempt");
+        return failure("Invalid credentials.");
+    };
+
+    // A TOTP code only logs in once
+    if let Some(step) = totp_step {
+        match claim_totp_step(&pool, &config.username, step).await {
+            Ok(true) => {}
+            Ok(false) => {
+                tracing::warn!("Admin login with a TOTP code already used");
+                return failure("Invalid credentials.");
+            }
+            Err(e) => {
+                tracing::error!(?e, "TOTP step check failed");
+                return Err(ServerFnError::new("Database error"));
+            }
+        }
+    }
+
+    let token = new_session_token();
+    let session = NewAdminSession {
+        token_hash: hash_session_token(&token),
+        username: config.username.clone(),
+        ip_hash: Some(expect_context::<IpHasher>().hash("admin_session", &ip)),
+        user_agent: parts
+            .headers
+            .get("user-agent")
+            .and_then(|v| v.to_str().ok())
+            .map(|s| s.chars().take(512).collect()),
+        expires_at: chrono::Utc::now() + config.session_ttl,
+    };
+    if let Err(e) = insert_admin_session(&pool, session).await {
+        tracing::error!(?e, "Failed to create admin session");
+        return Err(ServerFnError::new("Login failed"));
+    }
+
+    let cookie = session_cookie(&token, config.session_ttl.num_seconds());
+    response.append_header(
+        header::SET_COOKIE,
+        HeaderValue::from_str(&cookie).map_err(|_| ServerFnError::new("Login failed"))?,
+    );
+
+    tracing::info!(username = %config.username, "Admin logged in");
+    Ok(AdminLoginResult {
+        success: true,
+        message: None,
+    })
+}
+
+/// Log out: delete the session and clear the cookie
+#[server]
+pub async fn admin_logout() -> Result<(), ServerFnError> {
+    use crate::admin::{clear_session_cookie, hash_session_token, session_token};
+    use crate::db::delete_admin_session;
+    use axum::http::{header, request::Parts, HeaderValue};
+    use leptos_axum::{extract, ResponseOptions};
+
+    let pool = expect_context::<sqlx::PgPool>();
+    let response = expect_context::<ResponseOptions>();
+    let parts: Parts = extract().await?;
+    if let Some(token) = session_token(&parts.headers) {
+        if let Err(e) = delete_admin_session(&pool, &hash_session_token(&token)).await {
+            tracing::error!(?e, "Failed to delete admin session");
+        }
+    }
+
+    response.append_header(
+        header::SET_COOKIE,
+        HeaderValue::from_str(&clear_session_cookie())
+            .map_err(|_| ServerFnError::new("Logout failed"))?,
+    );
+
+    Ok(())
+}
@@ -0,0 +1,557 @@
+//! Admin inbox server functions
+//!
+//! List, search, triage and reply to contact form submissions (admin only).
+//! This is where submissions are decrypted (see `crate::encryption`); search
+//! runs over the decrypted folder, since the database only holds ciphertext,
+//! and only looks at its newest `MAX_SEARCH_ROWS` submissions.
+
+use leptos::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use super::OutboxEntry;
+
+/// Submissions per inbox page
+pub const INBOX_PAGE_SIZE: i64 = 25;
+
+/// Newest submissions of a folder that a search decrypts, at most
+pub const MAX_SEARCH_ROWS: i64 = 2_000;
+
+/// Longest search string accepted, in characters
+pub const MAX_SEARCH_LENGTH: usize = 200;
+
+/// Submissions decrypted per search batch
+#[cfg(feature = "ssr")]
+const SEARCH_BATCH_SIZE: i64 = 200;
+
+/// Inbox folder: a status, everything, or the honeypot quarantine
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum InboxFolder {
+    #[default]
+    All,
+    Pending,
+    Read,
+    Replied,
+    Spam,
+    Quarantine,
+}
+
+impl InboxFolder {
+    pub fn all() -> &'static [InboxFolder] {
+        &[
+            InboxFolder::All,
+            InboxFolder::Pending,
+            InboxFolder::Read,
+            InboxFolder::Replied,
+            InboxFolder::Spam,
+            InboxFolder::Quarantine,
+        ]
+    }
+
+    /// Value used in the `?folder=` query parameter
+    pub fn code(&self) -> &'static str {
+        match self {
+            InboxFolder::All => "all",
+            InboxFolder::Pending => "pending",
+            InboxFolder::Read => "read",
+            InboxFolder::Replied => "replied",
+            InboxFolder::Spam => "spam",
+            InboxFolder::Quarantine => "quarantine",
+        }
+    }
+
+    pub fn from_code(code: &str) -> Option<Self> {
+        Self::all().iter().copied().find(|f| f.code() == code)
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            InboxFolder::All => "All",
+            InboxFolder::Pending => "Pending",
+            InboxFolder::Read => "Read",
+            InboxFolder::Replied => "Replied",
+            InboxFolder::Spam => "Spam",
+            InboxFolder::Quarantine => "Quarantine",
+        }
+    }
+}
+
+/// Inbox list request
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct InboxQuery {
+    pub folder: InboxFolder,
+    pub search: Option<String>,
+    /// 1-based page number
+    pub page: i64,
+}
+
+/// Submission row in the inbox list
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ContactSummary {
+    pub id: String,
+    pub name: String,
+    pub email: String,
+    pub subject: Option<String>,
+    pub preview: String,
+    pub status: String,
+    pub created_at: String,
+}
+
+/// Folder sizes shown next to the folder tabs
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct InboxCounts {
+    pub pending: i64,
+    pub read: i64,
+    pub replied: i64,
+    pub spam: i64,
+    pub quarantine: i64,
+}
+
+impl InboxCounts {
+    pub fn for_folder(&self, folder: InboxFolder) -> i64 {
+        match folder {
+            InboxFolder::All => self.pending + self.read + self.replied + self.spam,
+            InboxFolder::Pending => self.pending,
+            InboxFolder::Read => self.read,
+            InboxFolder::Replied => self.replied,
+            InboxFolder::Spam => self.spam,
+            InboxFolder::Quarantine => self.quarantine,
+        }
+    }
+}
+
+/// One page of the inbox
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct InboxPage {
+    pub submissions: Vec<ContactSummary>,
+    pub total: i64,
+    pub page: i64,
+    pub page_count: i64,
+    pub counts: InboxCounts,
+}
+
+/// Full submission for the detail view
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ContactDetail {
+    pub id: String,
+    pub name: String,
+    pub email: String,
+    pub subject: Option<String>,
+    pub message: String,
+    pub status: String,
+    pub quarantined: bool,
+    /// Classified user agent, e.g. "Firefox on Linux (desktop)"
+    pub client: Option<String>,
+    pub user_agent: Option<String>,
+    pub spam_score: Option<i32>,
+    /// (rule, points), highest first
+    pub spam_rules: Vec<(String, i32)>,
+    pub created_at: String,
+    pub read_at: Option<String>,
+    pub replied_at: Option<String>,
+    pub replies: Vec<ContactReplyDetail>,
+    /// Queued notification / acknowledgement emails
+    pub emails: Vec<OutboxEntry>,
+}
+
+/// Reply sent from the admin inbox
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ContactReplyDetail {
+    pub subject: String,
+    pub body: String,
+    pub sent_by: String,
+    pub sent_at: String,
+}
+
+/// Maximum reply body length, in characters
+pub const MAX_REPLY_LENGTH: usize = 20_000;
+
+#[cfg(feature = "ssr")]
+const DATE_FORMAT: &str = "%Y-%m-%d %H:%M UTC";
+
+#[cfg(feature = "ssr")]
+impl ContactDetail {
+    /// Detail view of a stored submission and its decrypted data
+    fn new(row: crate::db::ContactSubmission, pii: crate::encryption::ContactPii) -> Self {
+        let client = row
+            .user_agent
+            .as_deref()
+            .map(|ua| crate::user_agent::classify(ua).summary());
+        let mut spam_rules: Vec<(String, i32)> = row
+            .spam_rules
+            .as_ref()
+            .and_then(|rules| rules.as_object())
+            .map(|rules| {
+                rules
+                    .iter()
+                    .filter_map(|(rule, points)| Some((rule.clone(), points.as_i64()? as i32)))
+                    .collect()
+            })
+            .unwrap_or_default();
+        spam_rules.sort_by_key(|(_, points)| std::cmp::Reverse(*points));
+
+        ContactDetail {
+            id: row.id.to_string(),
+            name: pii.name,
+            email: pii.email,
+            subject: pii.subject,
+            message: pii.message,
+            status: row.status,
+            quarantined: row.honeypot_filled.unwrap_or(false),
+            client,
+            user_agent: row.user_agent,
+            spam_score: row.spam_score,
+            spam_rules,
+            created_at: row.created_at.format(DATE_FORMAT).to_string(),
+            read_at: row.read_at.map(|t| t.format(DATE_FORMAT).to_string()),
+            replied_at: row.replied_at.map(|t| t.format(DATE_FORMAT).to_string()),
+            replies: Vec::new(),
+            emails: Vec::new(),
+        }
+    }
+}
+
+#[cfg(feature = "ssr")]
+impl ContactReplyDetail {
+    /// Detail view of a stored reply and its decrypted content
+    fn new(row: crate::db::ContactReply, content: crate::encryption::ReplyContent) -> Self {
+        ContactReplyDetail {
+            subject: content.subject,
+            body: content.body,
+            sent_by: row.sent_by,
+            sent_at: row.sent_at.format(DATE_FORMAT).to_string(),
+        }
+    }
+}
+
+/// Load a submission with its replies
+#[cfg(feature = "ssr")]
+async fn load_contact_detail(
+    pool: &sqlx::PgPool,
+    id: uuid::Uuid,
+) -> Result<ContactDetail, ServerFnError> {
+    use crate::db::{get_contact, list_contact_replies, list_submission_outbox_emails};
+    use crate::encryption::ContactCipher;
+
+    let row = get_contact(pool, id)
+        .await
+        .map_err(inbox_db_error)?
+        .ok_or_else(|| ServerFnError::new("Submission not found"))?;
+    let cipher = expect_context::<ContactCipher>();
+    let pii = cipher.open(&row).map_err(inbox_decrypt_error)?;
+    let replies = list_contact_replies(pool, id)
+        .await
+        .map_err(inbox_db_error)?
+        .into_iter()
+        .map(|reply| {
+            let content = cipher.open_reply(&row, &reply)?;
+            Ok(ContactReplyDetail::new(reply, content))
+        })
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(inbox_decrypt_error)?;
+    let emails = list_submission_outbox_emails(pool, id)
+        .await
+        .map_err(inbox_db_error)?;
+
+    Ok(ContactDetail {
+        replies,
+        emails: emails.into_iter().map(OutboxEntry::from).collect(),
+        ..ContactDetail::new(row, pii)
+    })
+}
+
+#[cfg(feature = "ssr")]
+fn inbox_db_error(e: sqlx::Error) -> ServerFnError {
+    tracing::error!(?e, "Admin inbox query failed");
+    ServerFnError::new("Database error")
+}
+
+#[cfg(feature = "ssr")]
+fn inbox_decrypt_error(e: crate::encryption::EncryptionError) -> ServerFnError {
+    tracing::error!(%e, "Admin inbox decryption failed");
+    ServerFnError::new("Submission could not be decrypted")
+}
+
+/// Decrypted data for an inbox row. Rows that fail to decrypt (their key
+/// is no longer configured) are still listed, with a placeholder.
+#[cfg(feature = "ssr")]
+fn open_for_list(
+    cipher: &crate::encryption::ContactCipher,
+    row: &crate::db::ContactSubmission,
+) -> crate::encryption::ContactPii {
+    cipher.open(row).unwrap_or_else(|e| {
+        tracing::error!(%e, "Admin inbox decryption failed");
+        crate::encryption::ContactPii {
+            name: "(could not decrypt)".to_string(),
+            email: String::new(),
+            subject: None,
+            message: String::new(),
+        }
+    })
+}
+
+/// Case-insensitive match of a lowercase search string
+#[cfg(feature = "ssr")]
+fn pii_matches(pii: &crate::encryption::ContactPii, search: &str) -> bool {
+    [&pii.name, &pii.email, &pii.message]
+        .into_iter()
+        .chain(pii.subject.as_ref())
+        .any(|field| field.to_lowercase().contains(search))
+}
+
+#[cfg(feature = "ssr")]
+fn parse_contact_id(id: &str) -> Result<uuid::Uuid, ServerFnError> {
+    uuid::Uuid::parse_str(id).map_err(|_| ServerFnError::new("Invalid submission ID"))
+}
+
+/// List submissions in a folder, optionally filtered by a search string
+#[server]
+pub async fn list_contact_submissions(query: InboxQuery) -> Result<InboxPage, ServerFnError> {
+    use crate::db::{contact_counts, count_contacts, list_contacts, ContactFilter, ContactStatus};
+    use crate::encryption::ContactCipher;
+
+    let pool = expect_context::<sqlx::PgPool>();
+    crate::admin::require_admin(&pool).await?;
+    let cipher = expect_context::<ContactCipher>();
+
+    let filter = ContactFilter {
+        status: match query.folder {
+            InboxFolder::Pending => Some(ContactStatus::Pending),
+            InboxFolder::Read => Some(ContactStatus::Read),
+            InboxFolder::Replied => Some(ContactStatus::Replied),
+            InboxFolder::Spam => Some(ContactStatus::Spam),
+            InboxFolder::All | InboxFolder::Quarantine => None,
+        },
+        quarantined: query.folder == InboxFolder::Quarantine,
+    };
+    let search = query
+        .search
+        .as_deref()
+        .map(str::trim)
+        .filter(|s| !s.is_empty())
+        .map(str::to_lowercase);
+    if search
+        .as_ref()
+        .is_some_and(|s| s.chars().count() > MAX_SEARCH_LENGTH)
+    {
+        return Err(ServerFnError::new("Search is too long"));
+    }
+    let page_count = |total: i64| ((total + INBOX_PAGE_SIZE - 1) / INBOX_PAGE_SIZE).max(1);
+
+    let (rows, total, page) = match search {
+        // Decrypt the folder newest first, a batch at a time, until the
+        // requested page and one more match are found. `total` is then a
+        // lower bound, which still links to the next page.
+        Some(search) => {
+            // No page past the searched rows can have matches
+            let last_page = MAX_SEARCH_ROWS / INBOX_PAGE_SIZE + 1;
+            let wanted = query.page.clamp(1, last_page) * INBOX_PAGE_SIZE + 1;
+            let mut matching = Vec::new();
+            let mut offset = 0;
+            while (matching.len() as i64) < wanted && offset < MAX_SEARCH_ROWS {
+                let batch = list_contacts(&pool, &filter, SEARCH_BATCH_SIZE, offset)
+                    .await
+                    .map_err(inbox_db_error)?;
+                let last_batch = (batch.len() as i64) < SEARCH_BATCH_SIZE;
+                offset += SEARCH_BATCH_SIZE;
+                matching.extend(
+                    batch
+                        .into_iter()
+                        .map(|row| {
+                            let pii = open_for_list(&cipher, &row);
+                            (row, pii)
+                        })
+                        .filter(|(_, pii)| pii_matches(pii, &search)),
+                );
+                if last_batch {
+                    break;
+                }
+            }
+            let total = matching.len() as i64;
+            let page = query.page.clamp(1, page_count(total));
+            let rows = matching
+                .into_iter()
+                .skip(((page - 1) * INBOX_PAGE_SIZE) as usize)
+                .take(INBOX_PAGE_SIZE as usize)
+                .collect::<Vec<_>>();
+            (rows, total, page)
+        }
+        None => {
+            let total = count_contacts(&pool, &filter).await.map_err(inbox_db_error)?;
+            let page = query.page.clamp(1, page_count(total));
+            let rows = list_contacts(&pool, &filter, INBOX_PAGE_SIZE, (page - 1) * INBOX_PAGE_SIZE)
+                .await
+                .map_err(inbox_db_error)?
+                .into_iter()
+                .map(|row| {
+                    let pii = open_for_list(&cipher, &row);
+                    (row, pii)
+                })
+                .collect();
+            (rows, total, page)
+        }
+    };
+    let counts = contact_counts(&pool).await.map_err(inbox_db_error)?;
+
+    let submissions = rows
+        .into_iter()
+        .map(|(row, pii)| ContactSummary {
+            id: row.id.to_string(),
+            name: pii.name,
+            email: pii.email,
+            subject: pii.subject,
+            preview: pii.message.chars().take(120).collect(),
+            status: row.status,
+            created_at: row.created_at.format(DATE_FORMAT).to_string(),
+        })
+        .collect();
+
+    Ok(InboxPage {
+        submissions,
+        total,
+        page,
+        page_count: page_count(total),
+        counts: InboxCounts {
+            pending: counts.pending,
+            read: counts.read,
+            replied: counts.replied,
+            spam: counts.spam,
+            quarantine: counts.quarantined,
+        },
+    })
+}
+
+/// Get a single submission
+#[server]
+pub async fn get_contact_submission(id: String) -> Result<ContactDetail, ServerFnError> {
+    let pool = expect_context::<sqlx::PgPool>();
+    crate::admin::require_admin(&pool).await?;
+    let id = parse_contact_id(&id)?;
+
+    load_contact_detail(&pool, id).await
+}
+
+/// Mark a submission pending, read, replied or spam
+#[server]
+pub async fn set_contact_status(id: String, status: String) -> Result<ContactDetail, ServerFnError> {
+    use crate::db::{update_contact_status, ContactStatus};
+
+    let pool = expect_context::<sqlx::PgPool>();
+    crate::admin::require_admin(&pool).await?;
+    let id = parse_contact_id(&id)?;
+    let status = ContactStatus::parse(&status).ok_or_else(|| ServerFnError::new("Invalid status"))?;
+
+    update_contact_status(&pool, id, status)
+        .await
+        .map_err(inbox_db_error)?
+        .ok_or_else(|| ServerFnError::new("Submission not found"))?;
+
+    load_contact_detail(&pool, id).await
+}
+
+/// Email a reply to the submitter (threaded under the notification) and
+/// mark the submission replied
+#[server]
+pub async fn reply_to_contact(
+    id: String,
+    subject: String,
+    body: String,
+) -> Result<ContactDetail, ServerFnError> {
+    use crate::data_request::email_index;
+    use crate::db::{get_contact, insert_contact_reply, store_sealed_contact, NewContactReply};
+    use crate::email::{send_contact_reply, ContactReplyEmail, EmailConfig};
+    use crate::encryption::{ContactCipher, ReplyContent};
+    use crate::ip_hash::IpHasher;
+
+    let pool = expect_context::<sqlx::PgPool>();
+    let admin = crate::admin::require_admin(&pool).await?;
+    let id = parse_contact_id(&id)?;
+
+    // Security: reject newlines in the subject to prevent header injection
+    let subject = subject.trim();
+    let body = body.trim();
+    if subject.is_empty() || subject.len() > 500 || subject.contains(['\r', '\n']) {
+        return Err(ServerFnError::new("Invalid subject"));
+    }
+    if body.is_empty() {
+        return Err(ServerFnError::new("Reply must not be empty"));
+    }
+    if body.chars().count() > MAX_REPLY_LENGTH {
+        return Err(ServerFnError::new(format!(
+            "Reply must not be longer than {MAX_REPLY_LENGTH} characters"
+        )));
+    }
+
+    let submission = get_contact(&pool, id)
+        .await
+        .map_err(inbox_db_error)?
+        .ok_or_else(|| ServerFnError::new("Submission not found"))?;
+    if submission.honeypot_filled.unwrap_or(false) {
+        return Err(ServerFnError::new("Quarantined submissions cannot be replied to"));
+    }
+    let cipher = expect_context::<ContactCipher>();
+    let pii = cipher.open(&submission).map_err(inbox_decrypt_error)?;
+
+    // The reply is sealed with the submission's data key, so a row stored
+    // before encryption is sealed first
+    let submission = if submission.sealed_pii.is_none() {
+        let sealed = cipher.seal(id, &pii);
+        let index = email_index(&expect_context::<IpHasher>(), &pii.email);
+        store_sealed_contact(&pool, id, &sealed, &index)
+            .await
+            .map_err(inbox_db_error)?;
+        crate::db::ContactSubmission {
+            key_id: Some(sealed.key_id),
+            wrapped_dek: Some(sealed.wrapped_dek),
+            sealed_pii: Some(sealed.ciphertext),
+            ..submission
+        }
+    } else {
+        submission
+    };
+
+    let config =
+        use_context::<EmailConfig>().ok_or_else(|| ServerFnError::new("Email is not configured"))?;
+
+    let reply_id = uuid::Uuid::new_v4();
+    let message_id = send_contact_reply(
+        &config,
+        ContactReplyEmail {
+            submission_id: id,
+            reply_id,
+            to: &pii.email,
+            subject,
+            body,
+        },
+    )
+    .await
+    .map_err(|e| {
+        tracing::error!(?e, "Failed to send contact reply");
+        ServerFnError::new("Failed to send reply")
+    })?;
+
+    // The email is already out: a failure here only loses the record
+    let content = ReplyContent {
+        subject: subject.to_string(),
+        body: body.to_string(),
+    };
+    let sealed_content = cipher
+        .seal_reply(&submission, reply_id, &content)
+        .map_err(inbox_decrypt_error)?;
+    insert_contact_reply(
+        &pool,
+        NewContactReply {
+            id: reply_id,
+            submission_id: id,
+            sealed_content,
+            message_id,
+            sent_by: admin,
+        },
+    )
+    .await
+    .map_err(|e| {
+        tracing::error!(?e, %reply_id, "Reply sent but could not be stored");
+        ServerFnError::new("Reply sent, but could not be saved")
+    })?;
+
+    load_contact_detail(&pool, id).await
+}
@@ -0,0 +1,176 @@
+//! Admin email outbox server functions
+//!
+//! Delivery status of queued contact emails, and manual retry of dead ones
+//! (admin only).
+
+use leptos::prelude::*;
+use serde::{Deserialize, Serialize};
+
+/// Emails listed on the outbox page
+pub const OUTBOX_PAGE_SIZE: i64 = 100;
+
+/// Outbox filter tab
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum OutboxFolder {
+    #[default]
+    All,
+    Pending,
+    Sent,
+    Dead,
+}
+
+impl OutboxFolder {
+    pub fn all() -> &'static [OutboxFolder] {
+        &[
+            OutboxFolder::All,
+            OutboxFolder::Pending,
+            OutboxFolder::Sent,
+            OutboxFolder::Dead,
+        ]
+    }
+
+    /// Value used in the `?status=` query parameter
+    pub fn code(&self) -> &'static str {
+        match self {
+            OutboxFolder::All => "all",
+            OutboxFolder::Pending => "pending",
+            OutboxFolder::Sent => "sent",
+            OutboxFolder::Dead => "dead",
+        }
+    }
+
+    pub fn from_code(code: &str) -> Option<Self> {
+        Self::all().iter().copied().find(|f| f.code() == code)
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            OutboxFolder::All => "All",
+            OutboxFolder::Pending => "Pending",
+            OutboxFolder::Sent => "Sent",
+            OutboxFolder::Dead => "Dead",
+        }
+    }
+}
+
+/// Queued email as shown in the admin area
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct OutboxEntry {
+    pub id: String,
+    /// "Notification" or "Acknowledgement"
+    pub kind: String,
+    pub submission_id: String,
+    pub status: String,
+    pub attempts: i32,
+    pub last_error: Option<String>,
+    /// Next attempt, for pending emails
+    pub next_attempt_at: Option<String>,
+    pub created_at: String,
+    pub sent_at: Option<String>,
+}
+
+/// Outbox size per status
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct OutboxStats {
+    pub pending: i64,
+    pub retrying: i64,
+    pub sent: i64,
+    pub dead: i64,
+}
+
+impl OutboxStats {
+    pub fn for_folder(&self, folder: OutboxFolder) -> i64 {
+        match folder {
+            OutboxFolder::All => self.pending + self.sent + self.dead,
+            OutboxFolder::Pending => self.pending,
+            OutboxFolder::Sent => self.sent,
+            OutboxFolder::Dead => self.dead,
+        }
+    }
+}
+
+/// Outbox page data
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct OutboxPage {
+    pub entries: Vec<OutboxEntry>,
+    pub stats: OutboxStats,
+}
+
+#[cfg(feature = "ssr")]
+const DATE_FORMAT: &str = "%Y-%m-%d %H:%M UTC";
+
+#[cfg(feature = "ssr")]
+impl From<crate::db::OutboxEmail> for OutboxEntry {
+    fn from(row: crate::db::OutboxEmail) -> Self {
+        use crate::db::OutboxKind;
+
+        let kind = match OutboxKind::parse(&row.kind) {
+            Some(OutboxKind::ContactNotification) => "Notification".to_string(),
+            Some(OutboxKind::ContactAcknowledgement) => "Acknowledgement".to_string(),
+            None => row.kind,
+        };
+        OutboxEntry {
+            id: row.id.to_string(),
+            kind,
+            submission_id: row.submission_id.to_string(),
+            next_attempt_at: (row.status == "pending")
+                .then(|| row.next_attempt_at.format(DATE_FORMAT).to_string()),
+            status: row.status,
+            attempts: row.attempts,
+            last_error: row.last_error,
+            created_at: row.created_at.format(DATE_FORMAT).to_string(),
+            sent_at: row.sent_at.map(|t| t.format(DATE_FORMAT).to_string()),
+        }
+    }
+}
+
+#[cfg(feature = "ssr")]
+fn outbox_db_error(e: sqlx::Error) -> ServerFnError {
+    tracing::error!(?e, "Admin outbox query failed");
+    ServerFnError::new("Database error")
+}
+
+/// List outbox emails, newest first
+#[server]
+pub async fn list_email_outbox(folder: OutboxFolder) -> Result<OutboxPage, ServerFnError> {
+    use crate::db::{list_outbox_emails, outbox_counts};
+
+    let pool = expect_context::<sqlx::PgPool>();
+    crate::admin::require_admin(&pool).await?;
+
+    let status = match folder {
+        OutboxFolder::All => None,
+        other => Some(other.code()),
+    };
+    let rows = list_outbox_emails(&pool, status, OUTBOX_PAGE_SIZE)
+        .await
+        .map_err(outbox_db_error)?;
+    let counts = outbox_counts(&pool).await.map_err(outbox_db_error)?;
+
+    Ok(OutboxPage {
+        entries: rows.into_iter().map(OutboxEntry::from).collect(),
+        stats: OutboxStats {
+            pending: counts.pending,
+            retrying: counts.retrying,
+            sent: counts.sent,
+            dead: counts.dead,
+        },
+    })
+}
+
+/// Queue a dead email again
+#[server]
+pub async fn retry_outbox_email(id: String) -> Result<(), ServerFnError> {
+    let pool = expect_context::<sqlx::PgPool>();
+    crate::admin::require_admin(&pool).await?;
+    let id = uuid::Uuid::parse_str(&id).map_err(|_| ServerFnError::new("Invalid email ID"))?;
+
+    if crate::db::retry_outbox_email(&pool, id)
+        .await
+        .map_err(outbox_db_error)?
+    {
+        Ok(())
+    } else {
+        Err(ServerFnError::new("Only dead emails can be retried"))
+    }
+}
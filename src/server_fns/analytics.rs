@@ -0,0 +1,261 @@
+//! Analytics server functions
+//!
+//! Page view, engagement and custom event ingestion. Honors Do-Not-Track and
+//! Global Privacy Control.
+
+use leptos::prelude::*;
+use leptos::server_fn::codec::Json;
+use serde::{Deserialize, Serialize};
+
+/// Page view reported by the client after a navigation
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PageViewInput {
+    pub path: String,
+    pub title: Option<String>,
+    pub referrer: Option<String>,
+}
+
+/// Engagement beacon for a previously recorded page view
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EngagementInput {
+    pub page_view_id: String,
+    pub time_on_page_seconds: i32,
+    pub scroll_depth_percent: i32,
+}
+
+/// Upper bound for time on page (longer visits are idle tabs)
+pub const MAX_TIME_ON_PAGE_SECONDS: i32 = 4 * 60 * 60;
+
+/// Custom event sent by the client (see `analytics::EventTracker`)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TrackEventInput {
+    pub name: String,
+    pub category: Option<String>,
+    /// JSON-encoded event payload (size-capped on the server)
+    pub data: Option<String>,
+    pub page_path: Option<String>,
+}
+
+/// Record a page view.
+/// Returns the page view ID, or None if nothing was stored (opt-out, bot or invalid path).
+#[server]
+pub async fn record_page_view(input: PageViewInput) -> Result<Option<String>, ServerFnError> {
+    use crate::analytics::geoip::GeoIp;
+    use crate::analytics::privacy::{
+        referrer_domain, sanitize_referrer, session_hash, tracking_opted_out,
+    };
+    use crate::client_ip::TrustedProxies;
+    use crate::db::{insert_page_view, NewPageView};
+    use crate::ip_hash::IpHasher;
+    use crate::rate_limit::{RateLimits, ANALYTICS_POLICY};
+    use crate::user_agent;
+    use axum::http::request::Parts;
+    use chrono::Utc;
+    use leptos_axum::extract;
+
+    let rate_limits = expect_context::<RateLimits>();
+    let trusted_proxies = expect_context::<TrustedProxies>();
+    let ip_hasher = expect_context::<IpHasher>();
+    let parts: Parts = extract().await?;
+
+    // Respect DNT / Sec-GPC: store nothing at all
+    if tracking_opted_out(&parts.headers) {
+        return Ok(None);
+    }
+
+    // Validate path (must be a local absolute path, no query string)
+    let path = input.path.split(['?', '#']).next().unwrap_or_default();
+    if !path.starts_with('/') || path.starts_with("//") || path.len() > 2048 {
+        return Ok(None);
+    }
+
+    // Admin pages are not part of the public site
+    if path == "/admin" || path.starts_with("/admin/") {
+        return Ok(None);
+    }
+
+    let user_agent = parts
+        .headers
+        .get("user-agent")
+        .and_then(|v| v.to_str().ok())
+        .unwrap_or_default();
+    let own_host = parts.headers.get("host").and_then(|v| v.to_str().ok());
+
+    // Crawlers and HTTP libraries are not visitors
+    let device = user_agent::classify(user_agent);
+    if device.is_bot {
+        return Ok(None);
+    }
+
+    // Resolve the country before the IP is hashed; the IP itself is never stored
+    let ip = trusted_proxies.client_ip(&parts).to_string();
+    if !rate_limits.check(&ANALYTICS_POLICY, &ip).await?.is_allowed() {
+        return Ok(None);
+    }
+    let country_code = expect_context::<GeoIp>().country_code(&ip);
+
+    let referrer = input.referrer.as_deref().and_then(sanitize_referrer);
+    let referrer_domain = referrer
+        .as_deref()
+        .and_then(|r| referrer_domain(r, own_host));
+    // Internal navigation is not a referral
+    let referrer = referrer.filter(|_| referrer_domain.is_some());
+
+    let view = NewPageView {
+        path: path.to_string(),
+        title: input
+            .title
+            .map(|t| t.trim().chars().take(500).collect::<String>())
+            .filter(|t| !t.is_empty()),
+        session_hash: Some(session_hash(&ip_hasher, &ip, user_agent, Utc::now().date_naive())),
+        referrer,
+        referrer_domain,
+        device_type: Some(device.device_type.as_str().to_string()),
+        browser_family: Some(device.browser_family.to_string()),
+        os_family: Some(device.os_family.to_string()),
+        country_code,
+    };
+
+    let pool = expect_context::<sqlx::PgPool>();
+
+    match insert_page_view(&pool, view).await {
+        Ok(id) => Ok(Some(id.to_string())),
+        Err(e) => {
+            tracing::error!(?e, "Failed to record page view");
+            Err(ServerFnError::new("Database error"))
+        }
+    }
+}
+
+/// Store time on page and scroll depth for a page view.
+/// Called with `navigator.sendBeacon`, so the response is never read.
+#[server(input = Json)]
+pub async fn record_engagement(input: EngagementInput) -> Result<(), ServerFnError> {
+    use crate::analytics::privacy::session_hash;
+    use crate::client_ip::TrustedProxies;
+    use crate::db::update_page_view_engagement;
+    use crate::ip_hash::IpHasher;
+    use crate::rate_limit::{RateLimits, ANALYTICS_POLICY};
+    use axum::http::request::Parts;
+    use chrono::Utc;
+    use leptos_axum::extract;
+
+    let Ok(page_view_id) = uuid::Uuid::parse_str(&input.page_view_id) else {
+        return Ok(());
+    };
+
+    let rate_limits = expect_context::<RateLimits>();
+    let trusted_proxies = expect_context::<TrustedProxies>();
+    let ip_hasher = expect_context::<IpHasher>();
+    let parts: Parts = extract().await?;
+    let ip = trusted_proxies.client_ip(&parts).to_string();
+    if !rate_limits.check(&ANALYTICS_POLICY, &ip).await?.is_allowed() {
+        return Ok(());
+    }
+    let user_agent = parts
+        .headers
+        .get("user-agent")
+        .and_then(|v| v.to_str().ok())
+        .unwrap_or_default();
+    let session = session_hash(&ip_hasher, &ip, user_agent, Utc::now().date_naive());
+
+    let pool = expect_context::<sqlx::PgPool>();
+
+    if let Err(e) = update_page_view_engagement(
+        &pool,
+        page_view_id,
+        &session,
+        input.time_on_page_seconds.clamp(0, MAX_TIME_ON_PAGE_SECONDS),
+        input.scroll_depth_percent.clamp(0, 100),
+    )
+    .await
+    {
+        tracing::error!(?e, "Failed to record engagement");
+        return Err(ServerFnError::new("Database error"));
+    }
+
+    Ok(())
+}
+
+/// Record a batch of custom events.
+/// Events with unknown names or oversized payloads are dropped silently.
+#[server(input = Json)]
+pub async fn track_events(events: Vec<TrackEventInput>) -> Result<(), ServerFnError> {
+    use crate::analytics::privacy::{session_hash, tracking_opted_out};
+    use crate::analytics::{
+        ALLOWED_EVENTS, ALLOWED_EVENT_CATEGORIES, MAX_EVENTS_PER_BATCH, MAX_EVENT_DATA_BYTES,
+    };
+    use crate::client_ip::TrustedProxies;
+    use crate::db::{insert_analytics_events, NewAnalyticsEvent};
+    use crate::ip_hash::IpHasher;
+    use crate::rate_limit::{RateLimits, ANALYTICS_POLICY};
+    use crate::user_agent;
+    use axum::http::request::Parts;
+    use chrono::Utc;
+    use leptos_axum::extract;
+
+    let rate_limits = expect_context::<RateLimits>();
+    let trusted_proxies = expect_context::<TrustedProxies>();
+    let ip_hasher = expect_context::<IpHasher>();
+    let parts: Parts = extract().await?;
+
+    if tracking_opted_out(&parts.headers) {
+        return Ok(());
+    }
+
+    let user_agent = parts
+        .headers
+        .get("user-agent")
+        .and_then(|v| v.to_str().ok())
+        .unwrap_or_default();
+    if user_agent::classify(user_agent).is_bot {
+        return Ok(());
+    }
+    let ip = trusted_proxies.client_ip(&parts).to_string();
+    if !rate_limits.check(&ANALYTICS_POLICY, &ip).await?.is_allowed() {
+        return Ok(());
+    }
+    let session = session_hash(&ip_hasher, &ip, user_agent, Utc::now().date_naive());
+
+    let events: Vec<NewAnalyticsEvent> = events
+        .into_iter()
+        .take(MAX_EVENTS_PER_BATCH)
+        .filter(|e| ALLOWED_EVENTS.contains(&e.name.as_str()))
+        .filter_map(|e| {
+            // Payload must be a small JSON object
+            let event_data = match e.data {
+                Some(raw) if raw.len() > MAX_EVENT_DATA_BYTES => return None,
+                Some(raw) => match serde_json::from_str::<serde_json::Value>(&raw) {
+                    Ok(value @ serde_json::Value::Object(_)) => Some(value),
+                    _ => return None,
+                },
+                None => None,
+            };
+
+            Some(NewAnalyticsEvent {
+                event_name: e.name,
+                event_category: e
+                    .category
+                    .filter(|c| ALLOWED_EVENT_CATEGORIES.contains(&c.as_str())),
+                event_data,
+                page_path: e
+                    .page_path
+                    .filter(|p| p.starts_with('/') && p.len() <= 2048),
+                session_hash: Some(session.clone()),
+            })
+        })
+        .collect();
+
+    if events.is_empty() {
+        return Ok(());
+    }
+
+    let pool = expect_context::<sqlx::PgPool>();
+
+    if let Err(e) = insert_analytics_events(&pool, events).await {
+        tracing::error!(?e, "Failed to record analytics events");
+        return Err(ServerFnError::new("Database error"));
+    }
+
+    Ok(())
+}
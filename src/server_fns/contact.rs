@@ -1,10 +1,14 @@
 //! Contact form server function
 //!
 //! Handles form submission with validation, rate limiting, storage, and email.
+//! The modal fetches a signed time-trap token first (see `spam::FormTokenKey`)
+//! and, when enabled, solves its proof of work (see `crate::pow`).
 
 use leptos::prelude::*;
 use serde::{Deserialize, Serialize};
 
+use crate::i18n::Language;
+
 /// Contact form input from the client
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ContactFormInput {
@@ -13,6 +17,23 @@ pub struct ContactFormInput {
     pub subject: String,
     pub message: String,
     pub website: String, // Honeypot field - should be empty for real users
+    /// Language the visitor was browsing in (for the acknowledgement email)
+    #[serde(default)]
+    pub language: Language,
+    /// Signed token from `get_contact_token`, issued when the form opened
+    #[serde(default)]
+    pub token: String,
+    /// Proof-of-work counter for the token
+    #[serde(default)]
+    pub pow: u64,
+}
+
+/// Token and proof-of-work difficulty for a freshly opened contact form
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ContactChallenge {
+    pub token: String,
+    /// Leading zero bits required; 0 when no proof of work is needed
+    pub difficulty: u32,
 }
 
 /// Contact form submission result
@@ -22,34 +43,64 @@ pub struct ContactResult {
     pub message_key: String, // i18n key for the message
 }
 
+/// Issue a time-trap token for a contact form that was just opened. The
+/// proof-of-work difficulty rises with recent contact traffic.
+#[server]
+pub async fn get_contact_token() -> Result<ContactChallenge, ServerFnError> {
+    use crate::pow::PowConfig;
+
+    let key = expect_context::<crate::spam::FormTokenKey>();
+    let pool = expect_context::<sqlx::PgPool>();
+
+    let config = PowConfig::from_env();
+    let difficulty = if config.base_difficulty == 0 {
+        0
+    } else {
+        match crate::db::rate_limit_pressure(&pool, &crate::rate_limit::CONTACT_POLICY).await {
+            Ok(pressure) => config.difficulty(pressure),
+            Err(e) => {
+                tracing::error!(?e, "Contact pressure lookup failed");
+                config.base_difficulty
+            }
+        }
+    };
+
+    Ok(ContactChallenge {
+        token: key.issue(chrono::Utc::now().timestamp(), difficulty),
+        difficulty,
+    })
+}
+
 /// Submit a contact form
 #[server]
 pub async fn submit_contact(input: ContactFormInput) -> Result<ContactResult, ServerFnError> {
-    use crate::db::{check_rate_limit, insert_contact, NewContactSubmission};
-    use crate::email::{send_contact_notification, EmailConfig};
+    use crate::db::{
+        count_duplicate_messages, insert_contact, ContactStatus,
+        NewAcknowledgement, NewContactSubmission, NewOutboxEmail, OutboxKind,
+    };
+    use crate::data_request::email_index;
+    use crate::email::EmailConfig;
+    use crate::encryption::{ContactCipher, ContactPii};
+    use crate::pow;
+    use crate::rate_limit::{RateLimits, CONTACT_POLICY};
+    use crate::spam::{self, FormTokenKey, SpamConfig, SpamInput, TokenRejection, DUPLICATE_WINDOW_DAYS};
+    use crate::client_ip::TrustedProxies;
+    use crate::ip_hash::IpHasher;
     use axum::http::request::Parts;
     use leptos_axum::extract;
-    use sha2::{Digest, Sha256};
+
+    let trusted_proxies = expect_context::<TrustedProxies>();
+    // Keyed hashes for values that must never be stored in clear
+    let ip_hasher = expect_context::<IpHasher>();
 
     // Extract request info for IP-based rate limiting
     let parts: Parts = extract().await?;
 
-    // Get IP address (check X-Forwarded-For for proxied requests)
-    let ip = parts
-        .headers
-        .get("x-forwarded-for")
-        .and_then(|v| v.to_str().ok())
-        .and_then(|s| s.split(',').next())
-        .map(|s| s.trim().to_string())
-        .unwrap_or_else(|| "unknown".to_string());
+    // Client IP (forwarding headers only count from trusted proxies)
+    let ip = trusted_proxies.client_ip(&parts).to_string();
 
     // Hash the IP for privacy (never store raw IPs)
-    let salt = std::env::var("CONTACT_SALT")
-        .unwrap_or_else(|_| "portfolio-contact-salt-2025".to_string());
-    let mut hasher = Sha256::new();
-    hasher.update(ip.as_bytes());
-    hasher.update(salt.as_bytes());
-    let ip_hash = format!("{:x}", hasher.finalize());
+    let ip_hash = ip_hasher.hash("contact", &ip);
 
     // Get user agent for logging
     let user_agent = parts
@@ -58,12 +109,55 @@ pub async fn submit_contact(input: ContactFormInput) -> Result<ContactResult, Se
         .and_then(|v| v.to_str().ok())
         .map(|s| s.to_string());
 
-    // Get database pool from context
+    // Get database pool and token key from context
     let pool = expect_context::<sqlx::PgPool>();
+    let token_key = expect_context::<FormTokenKey>();
+    let rate_limits = expect_context::<RateLimits>();
+    // Name, email, subject and message are only stored sealed
+    let cipher = expect_context::<ContactCipher>();
+    let id = uuid::Uuid::new_v4();
+
+    // Time-trap token and proof of work, checked before any database write.
+    // Scripts that post instantly, skip the work or replay a token get the
+    // same fake success as the honeypot; a stale or missing token asks the
+    // visitor to send again (the modal fetches a new one).
+    let fake_success = ContactResult {
+        success: true,
+        message_key: "contact_success".to_string(),
+    };
+    let token = match token_key.verify(&input.token, chrono::Utc::now().timestamp()) {
+        Ok(token) => token,
+        Err(TokenRejection::TooFast) => {
+            tracing::info!("Contact form submitted too fast - dropped");
+            return Ok(fake_success);
+        }
+        Err(rejection) => {
+            tracing::info!(?rejection, "Contact form token refused");
+            return Ok(ContactResult {
+                success: false,
+                message_key: "contact_error_expired".to_string(),
+            });
+        }
+    };
+    if !pow::is_solution(&input.token, input.pow, token.difficulty) {
+        tracing::info!(difficulty = token.difficulty, "Contact proof of work invalid - dropped");
+        return Ok(fake_success);
+    }
 
     // Check honeypot - if filled, it's a bot. Accept silently but flag as spam.
+    // Rate-limited like any submission, and a replayed token stores nothing.
     if !input.website.is_empty() {
-        let submission = NewContactSubmission {
+        match rate_limits.check(&CONTACT_POLICY, &ip).await {
+            Ok(decision) if decision.is_allowed() => {}
+            Ok(_) => return Ok(fake_success),
+            Err(e) => {
+                tracing::error!(?e, "Rate limit check failed");
+                return Ok(fake_success);
+            }
+        }
+
+        let email_index = email_index(&ip_hasher, &input.email);
+        let pii = ContactPii {
             name: input.name,
             email: input.email,
             subject: if input.subject.is_empty() {
@@ -72,11 +166,20 @@ pub async fn submit_contact(input: ContactFormInput) -> Result<ContactResult, Se
                 Some(input.subject)
             },
             message: input.message,
+        };
+        let submission = NewContactSubmission {
+            id,
+            pii: cipher.seal(id, &pii),
+            email_index,
             honeypot_filled: true,
             ip_hash: Some(ip_hash),
             user_agent,
+            status: ContactStatus::Pending,
+            spam_score: None,
+            spam_rules: None,
+            message_hash: None,
         };
-        let _ = insert_contact(&pool, submission).await;
+        let _ = insert_contact(&pool, submission, &[], None, Some(&token.nonce)).await;
 
         // Return success to not reveal detection
         return Ok(ContactResult {
@@ -128,9 +231,9 @@ pub async fn submit_contact(input: ContactFormInput) -> Result<ContactResult, Se
     }
 
     // Check rate limit
-    match check_rate_limit(&pool, &ip_hash).await {
-        Ok(true) => {} // Allowed
-        Ok(false) => {
+    match rate_limits.check(&CONTACT_POLICY, &ip).await {
+        Ok(decision) if decision.is_allowed() => {}
+        Ok(_) => {
             return Ok(ContactResult {
                 success: false,
                 message_key: "contact_error_rate_limit".to_string(),
@@ -142,8 +245,29 @@ pub async fn submit_contact(input: ContactFormInput) -> Result<ContactResult, Se
         }
     }
 
+    // Content-based spam scoring; spam is stored but sends no email
+    let message_hashes = spam::message_hashes(&ip_hasher, message);
+    let duplicate_count = count_duplicate_messages(&pool, &message_hashes, DUPLICATE_WINDOW_DAYS)
+        .await
+        .unwrap_or_else(|e| {
+            tracing::error!(?e, "Duplicate message lookup failed");
+            0
+        });
+    let spam_score = spam::score(&SpamInput {
+        name,
+        email,
+        subject,
+        message,
+        user_agent: user_agent.as_deref(),
+        duplicate_count,
+    });
+    let is_spam = spam_score.is_spam(&SpamConfig::from_env());
+    if is_spam {
+        tracing::info!(score = spam_score.total, rules = ?spam_score.rules, "Contact submission scored as spam");
+    }
+
     // Insert into database
-    let submission = NewContactSubmission {
+    let pii = ContactPii {
         name: name.to_string(),
         email: email.to_string(),
         subject: if subject.is_empty() {
@@ -152,38 +276,71 @@ pub async fn submit_contact(input: ContactFormInput) -> Result<ContactResult, Se
             Some(subject.to_string())
         },
         message: message.to_string(),
+    };
+    let submission = NewContactSubmission {
+        id,
+        pii: cipher.seal(id, &pii),
+        email_index: email_index(&ip_hasher, email),
         honeypot_filled: false,
         ip_hash: Some(ip_hash),
         user_agent,
+        status: if is_spam {
+            ContactStatus::Spam
+        } else {
+            ContactStatus::Pending
+        },
+        spam_score: Some(spam_score.total),
+        spam_rules: Some(spam_score.rules_json()),
+        // Under the current key
+        message_hash: message_hashes.into_iter().next(),
     };
 
-    if let Err(e) = insert_contact(&pool, submission).await {
-        tracing::error!(?e, "Failed to insert contact");
-        return Err(ServerFnError::new("Failed to save message"));
-    }
+    // Emails are queued with the submission and sent by the outbox worker
+    let mut emails = Vec::new();
+    let mut acknowledgement = None;
+    if is_spam {
+        // Stored for review in the Spam folder only
+    } else if let Some(config) = use_context::<EmailConfig>() {
+        emails.push(NewOutboxEmail {
+            kind: OutboxKind::ContactNotification,
+            language: config.owner_language.code().to_string(),
+        });
 
-    // Send email notification (don't fail the request if email fails)
-    if let Some(config) = EmailConfig::from_env() {
-        if let Err(e) = send_contact_notification(
-            &config,
-            name,
-            email,
-            if subject.is_empty() {
-                None
-            } else {
-                Some(subject)
-            },
-            message,
-        )
-        .await
-        {
-            tracing::error!(?e, "Failed to send email notification");
-            // Don't fail - message is saved in DB, email is a bonus
+        // Acknowledge to the visitor, at most once per address per throttle
+        // window (claimed with the insert). Honeypot hits and rate-limited
+        // requests returned earlier.
+        if config.send_acknowledgements {
+            acknowledgement = Some(NewAcknowledgement {
+                recipient_hashes: ip_hasher.hashes("ack_recipient", &email.to_lowercase()),
+                email: NewOutboxEmail {
+                    kind: OutboxKind::ContactAcknowledgement,
+                    language: input.language.code().to_string(),
+                },
+            });
         }
     } else {
         tracing::warn!("Email not configured - skipping notification");
     }
 
+    // The nonce is used up with the insert, so validation errors and failed
+    // inserts do not burn the visitor's token
+    let nonce = Some(token.nonce.as_str());
+    match insert_contact(&pool, submission, &emails, acknowledgement.as_ref(), nonce).await {
+        Ok(Some((_, acknowledged))) => {
+            if acknowledgement.is_some() && !acknowledged {
+                tracing::info!("Acknowledgement throttled for recipient");
+            }
+        }
+        Ok(None) => {
+            tracing::info!("Contact form token replayed - dropped");
+            return Ok(fake_success);
+        }
+        Err(e) => {
+            tracing::error!(?e, "Failed to insert contact");
+            return Err(ServerFnError::new("Failed to save message"));
+        }
+    }
+
     Ok(ContactResult {
         success: true,
         message_key: "contact_success".to_string(),
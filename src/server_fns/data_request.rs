@@ -0,0 +1,283 @@
+//! Export and erasure of a visitor's own contact submissions
+//!
+//! The visitor asks for a link with `request_data_link`; it is emailed to
+//! the address they typed (see `crate::data_request`), and the data page
+//! calls `get_contact_data` and `erase_contact_data` with its token. The
+//! request answers the same whether or not anything was found, so the form
+//! cannot be used to learn who has written.
+//!
+//! `get_contact_retention` gives the privacy page the retention periods
+//! actually configured (see `crate::jobs::ContactRetention`).
+
+use leptos::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::i18n::Language;
+
+/// Data link request result
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DataRequestResult {
+    pub success: bool,
+    pub message_key: String, // i18n key for the message
+}
+
+/// Everything stored about one submission, as downloaded by its sender
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ExportedSubmission {
+    pub id: String,
+    pub created_at: String,
+    pub name: String,
+    pub email: String,
+    pub subject: Option<String>,
+    pub message: String,
+    pub status: String,
+    pub user_agent: Option<String>,
+    /// Keyed hash of the IP the message was sent from
+    pub ip_hash: Option<String>,
+    pub replies: Vec<ExportedReply>,
+}
+
+/// Reply sent to a submission
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ExportedReply {
+    pub sent_at: String,
+    pub subject: String,
+    pub body: String,
+}
+
+/// Submissions found through a data link
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ContactDataExport {
+    pub submissions: Vec<ExportedSubmission>,
+    /// When the link stops working (RFC 3339)
+    pub link_expires_at: String,
+}
+
+/// Days each kind of submission is kept; None when it is never deleted
+/// automatically
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ContactRetentionPolicy {
+    pub pending: Option<i64>,
+    pub read: Option<i64>,
+    pub replied: Option<i64>,
+    pub spam: Option<i64>,
+    pub quarantine: Option<i64>,
+}
+
+/// Retention periods for the privacy page. In dry-run mode nothing is
+/// deleted, so every period is reported as None.
+#[server]
+pub async fn get_contact_retention() -> Result<ContactRetentionPolicy, ServerFnError> {
+    use crate::jobs::ContactRetention;
+
+    let retention = ContactRetention::from_env();
+    let live = |days: Option<i64>| days.filter(|_| !retention.dry_run);
+    Ok(ContactRetentionPolicy {
+        pending: live(retention.pending),
+        read: live(retention.read),
+        replied: live(retention.replied),
+        spam: live(retention.spam),
+        quarantine: live(retention.quarantine),
+    })
+}
+
+/// Email a link to the submissions sent from `email`, if there are any.
+/// Answers `privacy_request_sent` either way, before looking anything up.
+#[server]
+pub async fn request_data_link(
+    email: String,
+    language: Language,
+) -> Result<DataRequestResult, ServerFnError> {
+    use crate::client_ip::TrustedProxies;
+    use crate::data_request::{data_link_token, DataLinkKey, DATA_LINK_TTL_SECONDS};
+    use crate::email::{send_contact_data_link, EmailConfig};
+    use crate::ip_hash::IpHasher;
+    use crate::rate_limit::{RateLimits, DATA_REQUEST_EMAIL_POLICY, DATA_REQUEST_POLICY};
+    use axum::http::request::Parts;
+    use leptos_axum::extract;
+
+    let parts: Parts = extract().await?;
+    let ip = expect_context::<TrustedProxies>().client_ip(&parts).to_string();
+    let pool = expect_context::<sqlx::PgPool>();
+    let rate_limits = expect_context::<RateLimits>();
+    let ip_hasher = expect_context::<IpHasher>();
+    let key = expect_context::<DataLinkKey>();
+    let email_config = use_context::<EmailConfig>();
+
+    // Same answer whether or not anything was found or sent
+    let sent = DataRequestResult {
+        success: true,
+        message_key: "privacy_request_sent".to_string(),
+    };
+
+    // Security: reject newlines to prevent email header injection attacks
+    let email = email.trim();
+    if email.is_empty()
+        || !email.contains('@')
+        || email.len() > 255
+        || email.contains('\n')
+        || email.contains('\r')
+    {
+        return Ok(DataRequestResult {
+            success: false,
+            message_key: "privacy_error_email".to_string(),
+        });
+    }
+
+    match rate_limits.check(&DATA_REQUEST_POLICY, &ip).await {
+        Ok(decision) if decision.is_allowed() => {}
+        Ok(_) => {
+            return Ok(DataRequestResult {
+                success: false,
+                message_key: "privacy_error_rate_limit".to_string(),
+            });
+        }
+        Err(e) => {
+            tracing::error!(?e, "Rate limit check failed");
+            return Err(ServerFnError::new("Database error"));
+        }
+    }
+
+    // Limit links per recipient too, without telling the requester
+    match rate_limits
+        .hit(&DATA_REQUEST_EMAIL_POLICY, &email.to_lowercase())
+        .await
+    {
+        Ok(decision) if decision.is_allowed() => {}
+        Ok(_) => {
+            tracing::info!("Data link throttled for recipient");
+            return Ok(sent);
+        }
+        Err(e) => {
+            tracing::error!(?e, "Rate limit check failed");
+            return Err(ServerFnError::new("Database error"));
+        }
+    }
+
+    // Looked up and sent in the background, so neither the answer nor the
+    // response time tells whether the address has written
+    let email = email.to_string();
+    tokio::spawn(async move {
+        let now = chrono::Utc::now().timestamp();
+        let token = match data_link_token(&pool, &ip_hasher, &key, &email, now).await {
+            Ok(Some(token)) => token,
+            Ok(None) => return,
+            Err(e) => {
+                tracing::error!(?e, "Data request lookup failed");
+                return;
+            }
+        };
+        let Some(config) = email_config else {
+            tracing::warn!("Email not configured - data link not sent");
+            return;
+        };
+        let link = format!("{}/privacy/data?token={}", config.branding.site_url, token);
+        let minutes = DATA_LINK_TTL_SECONDS / 60;
+        if let Err(e) = send_contact_data_link(&config, language, &email, &link, minutes).await {
+            tracing::error!(%e, "Failed to send data link");
+        }
+    });
+
+    Ok(sent)
+}
+
+/// Submissions and replies reachable through a data link, decrypted.
+/// Returns None when the link is invalid or expired.
+#[server]
+pub async fn get_contact_data(token: String) -> Result<Option<ContactDataExport>, ServerFnError> {
+    use crate::db::{list_contact_replies, list_contacts_by_email_index};
+    use crate::encryption::ContactCipher;
+
+    let Some(link) = verify_data_link(&token) else {
+        return Ok(None);
+    };
+    let pool = expect_context::<sqlx::PgPool>();
+    let cipher = expect_context::<ContactCipher>();
+
+    let rows = list_contacts_by_email_index(&pool, &link.email_indexes)
+        .await
+        .map_err(data_request_db_error)?;
+    let mut submissions = Vec::with_capacity(rows.len());
+    for row in rows {
+        let pii = cipher.open(&row).map_err(data_request_decrypt_error)?;
+        let replies = list_contact_replies(&pool, row.id)
+            .await
+            .map_err(data_request_db_error)?
+            .into_iter()
+            .map(|reply| {
+                let content = cipher.open_reply(&row, &reply)?;
+                Ok(ExportedReply {
+                    sent_at: reply.sent_at.to_rfc3339(),
+                    subject: content.subject,
+                    body: content.body,
+                })
+            })
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(data_request_decrypt_error)?;
+        submissions.push(ExportedSubmission {
+            id: row.id.to_string(),
+            created_at: row.created_at.to_rfc3339(),
+            name: pii.name,
+            email: pii.email,
+            subject: pii.subject,
+            message: pii.message,
+            status: row.status,
+            user_agent: row.user_agent,
+            ip_hash: row.ip_hash,
+            replies,
+        });
+    }
+
+    let expires_at = link.issued_at + crate::data_request::DATA_LINK_TTL_SECONDS;
+    Ok(Some(ContactDataExport {
+        submissions,
+        link_expires_at: chrono::DateTime::from_timestamp(expires_at, 0)
+            .unwrap_or_default()
+            .to_rfc3339(),
+    }))
+}
+
+/// Erase every submission reachable through a data link, with its replies
+/// and queued emails. Returns the number erased, or None when the link is
+/// invalid or expired.
+#[server]
+pub async fn erase_contact_data(token: String) -> Result<Option<i32>, ServerFnError> {
+    use crate::db::erase_contacts_by_email_index;
+
+    let Some(link) = verify_data_link(&token) else {
+        return Ok(None);
+    };
+    let pool = expect_context::<sqlx::PgPool>();
+    let issued_at = chrono::DateTime::from_timestamp(link.issued_at, 0).unwrap_or_default();
+
+    let erasure = erase_contacts_by_email_index(&pool, &link.email_indexes, issued_at)
+        .await
+        .map_err(data_request_db_error)?;
+    let count = erasure.map(|e| e.submission_count).unwrap_or(0);
+    tracing::info!(count, "Contact submissions erased on request");
+    Ok(Some(count))
+}
+
+#[cfg(feature = "ssr")]
+fn verify_data_link(token: &str) -> Option<crate::data_request::DataLink> {
+    let key = expect_context::<crate::data_request::DataLinkKey>();
+    match key.verify(token, chrono::Utc::now().timestamp()) {
+        Ok(link) => Some(link),
+        Err(rejection) => {
+            tracing::info!(?rejection, "Data link refused");
+            None
+        }
+    }
+}
+
+#[cfg(feature = "ssr")]
+fn data_request_db_error(e: sqlx::Error) -> ServerFnError {
+    tracing::error!(?e, "Data request query failed");
+    ServerFnError::new("Database error")
+}
+
+#[cfg(feature = "ssr")]
+fn data_request_decrypt_error(e: crate::encryption::EncryptionError) -> ServerFnError {
+    tracing::error!(%e, "Data export decryption failed");
+    ServerFnError::new("Submission could not be decrypted")
+}
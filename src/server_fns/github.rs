@@ -30,9 +30,11 @@ pub async fn get_github_repos() -> Result<GithubReposResult, ServerFnError> {
     use crate::db::github::{
         get_cache_metadata, get_cached_repos, has_cached_data, is_cache_fresh,
     };
+    use crate::rate_limit::{RateLimits, GITHUB_REFRESH_POLICY};
     use chrono::Utc;
 
     let pool = expect_context::<sqlx::PgPool>();
+    let rate_limits = expect_context::<RateLimits>();
 
     // Check cache freshness
     let cache_fresh = is_cache_fresh(&pool).await.unwrap_or(false);
@@ -82,13 +84,19 @@ pub async fn get_github_repos() -> Result<GithubReposResult, ServerFnError> {
 
         let metadata = get_cache_metadata(&pool).await.ok();
 
-        // Spawn background refresh (fire-and-forget)
-        let pool_clone = pool.clone();
-        tokio::spawn(async move {
-            if let Err(e) = refresh_github_cache(&pool_clone).await {
-                tracing::error!(?e, "Background GitHub refresh failed");
-            }
-        });
+        // Spawn background refresh (fire-and-forget), unless one ran recently
+        if rate_limits
+            .hit(&GITHUB_REFRESH_POLICY, "github")
+            .await
+            .is_ok_and(|d| d.is_allowed())
+        {
+            let pool_clone = pool.clone();
+            tokio::spawn(async move {
+                if let Err(e) = refresh_github_cache(&pool_clone).await {
+                    tracing::error!(?e, "Background GitHub refresh failed");
+                }
+            });
+        }
 
         return Ok(GithubReposResult {
             repos: convert_repos(repos),
@@ -100,6 +108,9 @@ pub async fn get_github_repos() -> Result<GithubReposResult, ServerFnError> {
     }
 
     // No cached data - must fetch synchronously
+    if !rate_limits.check(&GITHUB_REFRESH_POLICY, "github").await?.is_allowed() {
+        return Err(ServerFnError::new("GitHub refresh rate limited"));
+    }
     match refresh_github_cache(&pool).await {
         Ok(_) => {
             let repos = get_cached_repos(&pool).await.map_err(|e| {
@@ -2,8 +2,20 @@
 //!
 //! These functions run on the server and can be called from the client.
 
+mod admin_analytics;
+mod admin_auth;
+mod admin_inbox;
+mod admin_outbox;
+mod analytics;
 mod contact;
+mod data_request;
 mod github;
 
+pub use admin_analytics::*;
+pub use admin_auth::*;
+pub use admin_inbox::*;
+pub use admin_outbox::*;
+pub use analytics::*;
 pub use contact::*;
+pub use data_request::*;
 pub use github::*;
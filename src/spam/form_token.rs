@@ -0,0 +1,200 @@
+//! Signed time-trap tokens for the contact form
+//!
+//! The modal fetches a token when it opens:
+//! `{issued_at}.{difficulty}.{nonce}.{mac}`, where `mac` is HMAC-SHA256 over
+//! the first three parts and `difficulty` is the proof of work required
+//! with the submission (see `crate::pow`). A submission sent
+//! less than `MIN_FORM_SECONDS` after the token was issued came from a
+//! script; one older than `MAX_TOKEN_AGE_SECONDS` is refused so the visitor
+//! can resend with a fresh token. Each nonce is consumed once.
+//!
+//! The key comes from `FORM_TOKEN_SECRET`. Without it a random key is
+//! generated at startup, which invalidates open forms on every restart; in
+//! production the server refuses to start without it, or with a short or
+//! publicly known secret.
+
+use std::sync::Arc;
+
+use argon2::password_hash::rand_core::{OsRng, RngCore};
+use hmac::{Hmac, Mac};
+use sha2::Sha256;
+
+use crate::ip_hash::check_secret;
+
+/// Fastest plausible time for a human to fill in the form
+pub const MIN_FORM_SECONDS: i64 = 3;
+
+/// How long an unused token stays valid
+pub const MAX_TOKEN_AGE_SECONDS: i64 = 2 * 60 * 60;
+
+/// A token that passed verification
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct VerifiedToken {
+    /// Consumed once the submission is accepted
+    pub nonce: String,
+    /// Leading zero bits the proof of work must have
+    pub difficulty: u32,
+}
+
+/// Why a token was refused
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TokenRejection {
+    /// Missing or not in the token format
+    Malformed,
+    /// Not signed with the current key (forged, or issued before a restart)
+    BadSignature,
+    /// Submitted faster than `MIN_FORM_SECONDS`
+    TooFast,
+    /// Older than `MAX_TOKEN_AGE_SECONDS`
+    Expired,
+}
+
+/// HMAC key for form tokens, shared through the Leptos context
+#[derive(Clone)]
+pub struct FormTokenKey {
+    secret: Arc<[u8]>,
+}
+
+impl std::fmt::Debug for FormTokenKey {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("FormTokenKey(..)")
+    }
+}
+
+impl FormTokenKey {
+    pub fn new(secret: &[u8]) -> Self {
+        Self {
+            secret: secret.into(),
+        }
+    }
+
+    /// Load the key from `FORM_TOKEN_SECRET`. Outside production a random
+    /// key is generated when it is unset; in production it must be set, at
+    /// least `MIN_SECRET_BYTES` long and not one of the defaults from the
+    /// source.
+    pub fn from_env(production: bool) -> Result<Self, String> {
+        match std::env::var("FORM_TOKEN_SECRET") {
+            Ok(secret) if !secret.is_empty() => {
+                if production {
+                    check_secret(secret.as_bytes()).map_err(|e| format!("secret is {e}"))?;
+                }
+                Ok(Self::new(secret.as_bytes()))
+            }
+            _ if production => Err("FORM_TOKEN_SECRET must be set in production".to_string()),
+            _ => {
+                tracing::warn!("FORM_TOKEN_SECRET not set - open contact forms expire on restart");
+                let mut secret = [0u8; 32];
+                OsRng.fill_bytes(&mut secret);
+                Ok(Self::new(&secret))
+            }
+        }
+    }
+
+    fn mac(&self, payload: &str) -> Hmac<Sha256> {
+        let mut mac =
+            Hmac::<Sha256>::new_from_slice(&self.secret).expect("HMAC accepts any key length");
+        mac.update(payload.as_bytes());
+        mac
+    }
+
+    /// Issue a token for a form opened at `now` (Unix seconds)
+    pub fn issue(&self, now: i64, difficulty: u32) -> String {
+        let mut nonce = [0u8; 16];
+        OsRng.fill_bytes(&mut nonce);
+        let payload = format!("{}.{}.{}", now, difficulty, to_hex(&nonce));
+        let tag = self.mac(&payload).finalize().into_bytes();
+        format!("{}.{}", payload, to_hex(&tag))
+    }
+
+    /// Check a token submitted at `now`. The caller must consume the nonce
+    /// to detect replays.
+    pub fn verify(&self, token: &str, now: i64) -> Result<VerifiedToken, TokenRejection> {
+        let mut parts = token.trim().split('.');
+        let (Some(issued), Some(difficulty), Some(nonce), Some(tag), None) = (
+            parts.next(),
+            parts.next(),
+            parts.next(),
+            parts.next(),
+            parts.next(),
+        ) else {
+            return Err(TokenRejection::Malformed);
+        };
+        let issued_at: i64 = issued.parse().map_err(|_| TokenRejection::Malformed)?;
+        let difficulty: u32 = difficulty.parse().map_err(|_| TokenRejection::Malformed)?;
+        let tag = from_hex(tag).ok_or(TokenRejection::Malformed)?;
+        if nonce.len() != 32 || from_hex(nonce).is_none() {
+            return Err(TokenRejection::Malformed);
+        }
+
+        // Constant-time comparison
+        self.mac(&format!("{}.{}.{}", issued, difficulty, nonce))
+            .verify_slice(&tag)
+            .map_err(|_| TokenRejection::BadSignature)?;
+
+        let age = now - issued_at;
+        if age < MIN_FORM_SECONDS {
+            Err(TokenRejection::TooFast)
+        } else if age > MAX_TOKEN_AGE_SECONDS {
+            Err(TokenRejection::Expired)
+        } else {
+            Ok(VerifiedToken {
+                nonce: nonce.to_string(),
+                difficulty,
+            })
+        }
+    }
+}
+
+fn to_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+fn from_hex(hex: &str) -> Option<Vec<u8>> {
+    if !hex.len().is_multiple_of(2) || !hex.is_ascii() {
+        return None;
+    }
+    (0..hex.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(&hex[i..i + 2], 16).ok())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn accepts_a_token_within_the_window() {
+        let key = FormTokenKey::new(b"test secret");
+        let token = key.issue(1_000, 16);
+
+        assert_eq!(key.verify(&token, 1_000 + 1), Err(TokenRejection::TooFast));
+        let verified = key.verify(&token, 1_000 + MIN_FORM_SECONDS).unwrap();
+        assert_eq!(verified.nonce.len(), 32);
+        assert!(token.contains(&verified.nonce));
+        assert_eq!(verified.difficulty, 16);
+        assert_eq!(
+            key.verify(&token, 1_000 + MAX_TOKEN_AGE_SECONDS + 1),
+            Err(TokenRejection::Expired)
+        );
+    }
+
+    #[test]
+    fn rejects_forged_and_malformed_tokens() {
+        let key = FormTokenKey::new(b"test secret");
+        let token = key.issue(1_000, 16);
+
+        // Backdating the issue time or lowering the difficulty invalidates
+        // the signature
+        let backdated = token.replacen("1000.", "900.", 1);
+        assert_eq!(key.verify(&backdated, 1_010), Err(TokenRejection::BadSignature));
+        let easier = token.replacen(".16.", ".0.", 1);
+        assert_eq!(key.verify(&easier, 1_010), Err(TokenRejection::BadSignature));
+        assert_eq!(
+            FormTokenKey::new(b"other secret").verify(&token, 1_010),
+            Err(TokenRejection::BadSignature)
+        );
+        assert_eq!(key.verify("", 1_010), Err(TokenRejection::Malformed));
+        assert_eq!(key.verify("1000.0.abc.def", 1_010), Err(TokenRejection::Malformed));
+    }
+}
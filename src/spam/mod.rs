@@ -0,0 +1,361 @@
+//! Content-based spam scoring for contact submissions
+//!
+//! Each rule adds points; a submission at or above `SPAM_THRESHOLD`
+//! (default 8) is stored as spam and sends no email. The per-rule points
+//! are stored with the submission so the weights can be tuned from real
+//! traffic. Rules and the disposable domain list are compiled in. Repeated
+//! messages are spotted through a keyed hash (see `crate::ip_hash`).
+//!
+//! Submissions must also carry a signed time-trap token (see `form_token`).
+
+mod form_token;
+
+pub use form_token::{
+    FormTokenKey, TokenRejection, VerifiedToken, MAX_TOKEN_AGE_SECONDS, MIN_FORM_SECONDS,
+};
+
+use std::collections::BTreeMap;
+
+use crate::ip_hash::IpHasher;
+use crate::user_agent;
+
+/// Score at which a submission is treated as spam
+pub const DEFAULT_SPAM_THRESHOLD: i32 = 8;
+
+/// Days a repeated message hash counts as a duplicate
+pub const DUPLICATE_WINDOW_DAYS: i32 = 30;
+
+const DISPOSABLE_DOMAINS: &str = include_str!("disposable_domains.txt");
+
+/// URL shortener domains (links hide their destination)
+const SHORTENER_DOMAINS: &[&str] = &[
+    "bit.ly",
+    "bitly.com",
+    "tinyurl.com",
+    "t.co",
+    "goo.gl",
+    "ow.ly",
+    "is.gd",
+    "buff.ly",
+    "cutt.ly",
+    "rebrand.ly",
+    "shorturl.at",
+    "tiny.cc",
+    "rb.gy",
+    "t.ly",
+    "s.id",
+    "v.gd",
+    "qr.ae",
+    "adf.ly",
+    "shorte.st",
+    "bl.ink",
+];
+
+/// Forum / HTML link markup, never needed in a plain-text form
+const LINK_MARKUP: &[&str] = &["[url", "[link", "<a href", "<a\thref"];
+
+/// Zero-width and other invisible characters used to dodge filters
+const INVISIBLE_CHARS: &[char] = &[
+    '\u{00AD}', '\u{200B}', '\u{200C}', '\u{200D}', '\u{200E}', '\u{200F}', '\u{2060}', '\u{FEFF}',
+];
+
+/// Spam scoring settings loaded from environment variables
+#[derive(Debug, Clone)]
+pub struct SpamConfig {
+    pub threshold: i32,
+}
+
+impl SpamConfig {
+    /// Load settings from environment variables, falling back to defaults
+    pub fn from_env() -> Self {
+        Self {
+            threshold: std::env::var("SPAM_THRESHOLD")
+                .ok()
+                .and_then(|t| t.parse().ok())
+                .filter(|t| *t > 0)
+                .unwrap_or(DEFAULT_SPAM_THRESHOLD),
+        }
+    }
+}
+
+/// Submission fields the rules look at
+#[derive(Debug, Clone, Copy)]
+pub struct SpamInput<'a> {
+    pub name: &'a str,
+    pub email: &'a str,
+    pub subject: &'a str,
+    pub message: &'a str,
+    pub user_agent: Option<&'a str>,
+    /// Earlier submissions with the same `message_hash`
+    pub duplicate_count: i64,
+}
+
+/// Total score and the points each rule added
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SpamScore {
+    pub total: i32,
+    pub rules: BTreeMap<&'static str, i32>,
+}
+
+impl SpamScore {
+    fn add(&mut self, rule: &'static str, points: i32) {
+        if points > 0 {
+            self.total += points;
+            *self.rules.entry(rule).or_default() += points;
+        }
+    }
+
+    pub fn is_spam(&self, config: &SpamConfig) -> bool {
+        self.total >= config.threshold
+    }
+
+    /// Per-rule points as a JSON object, for `contact_submissions.spam_rules`
+    pub fn rules_json(&self) -> serde_json::Value {
+        serde_json::to_value(&self.rules).unwrap_or_default()
+    }
+}
+
+/// Hash purpose of `contact_submissions.message_hash`
+const MESSAGE_HASH_PURPOSE: &str = "message";
+
+/// Keyed hash of the message with case, punctuation and spacing removed,
+/// so trivially varied copies of one message share a hash. Keyed, so a
+/// stored hash cannot be checked against a guessed message.
+pub fn message_hash(hasher: &IpHasher, message: &str) -> String {
+    hasher.hash(MESSAGE_HASH_PURPOSE, &normalize_message(message))
+}
+
+/// `message_hash` under every key, current first, to find copies stored
+/// before a key rotation
+pub fn message_hashes(hasher: &IpHasher, message: &str) -> Vec<String> {
+    hasher.hashes(MESSAGE_HASH_PURPOSE, &normalize_message(message))
+}
+
+fn normalize_message(message: &str) -> String {
+    message
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .map(str::to_lowercase)
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// True if the address (or a parent domain) is a known throwaway provider
+pub fn is_disposable_email(email: &str) -> bool {
+    let Some((_, domain)) = email.trim().rsplit_once('@') else {
+        return false;
+    };
+    let domain = domain.trim_end_matches('.').to_lowercase();
+
+    DISPOSABLE_DOMAINS
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .any(|listed| {
+            domain == listed
+                || domain
+                    .strip_suffix(listed)
+                    .is_some_and(|prefix| prefix.ends_with('.'))
+        })
+}
+
+/// Score a submission
+pub fn score(input: &SpamInput) -> SpamScore {
+    let mut score = SpamScore::default();
+    let text = format!("{}\n{}\n{}", input.name, input.subject, input.message);
+    let lower = text.to_lowercase();
+
+    // Links: one is normal, every extra link costs 1. Capped below the
+    // default threshold, so links alone never mark a message as spam
+    let links = lower.matches("http://").count()
+        + lower.matches("https://").count()
+        + lower
+            .split_whitespace()
+            .filter(|w| w.starts_with("www."))
+            .count();
+    score.add("links", (links.saturating_sub(1) as i32).min(6));
+
+    if lower
+        .split(|c: char| c.is_whitespace() || c == '/' || c == '(' || c == ')')
+        .any(|token| SHORTENER_DOMAINS.contains(&token.trim_end_matches(['.', ',', ';'])))
+    {
+        score.add("shortened_url", 5);
+    }
+
+    if LINK_MARKUP.iter().any(|m| lower.contains(m)) {
+        score.add("link_markup", 4);
+    }
+
+    // Script and charset anomalies
+    if text.contains(INVISIBLE_CHARS) {
+        score.add("invisible_characters", 3);
+    }
+    if text
+        .split(|c: char| !c.is_alphanumeric())
+        .any(mixes_scripts)
+    {
+        score.add("mixed_script", 4);
+    }
+    let letters: Vec<char> = input
+        .message
+        .chars()
+        .filter(|c| c.is_alphabetic())
+        .collect();
+    if letters.len() >= 20 {
+        let upper = letters.iter().filter(|c| c.is_uppercase()).count();
+        if upper * 10 > letters.len() * 7 {
+            score.add("shouting", 2);
+        }
+    }
+
+    score.add(
+        "duplicate_message",
+        match input.duplicate_count {
+            0 => 0,
+            1..=2 => 4,
+            _ => 8,
+        },
+    );
+
+    if is_disposable_email(input.email) {
+        score.add("disposable_email", 5);
+    }
+
+    // Missing user agents and HTTP libraries classify as bots
+    if user_agent::classify(input.user_agent.unwrap_or_default()).is_bot {
+        score.add("automated_client", 5);
+    }
+
+    score
+}
+
+fn is_latin(c: char) -> bool {
+    c.is_ascii_alphabetic() || ('\u{00C0}'..='\u{024F}').contains(&c)
+}
+
+fn is_cyrillic_or_greek(c: char) -> bool {
+    ('\u{0370}'..='\u{03FF}').contains(&c) || ('\u{0400}'..='\u{04FF}').contains(&c)
+}
+
+/// A single word mixing Latin with look-alike Cyrillic/Greek letters
+/// (e.g. "pаypal" with a Cyrillic "а") is almost always evasion. Words are
+/// split at any separator, so "React-приложение" is two words.
+fn mixes_scripts(word: &str) -> bool {
+    word.chars().any(is_latin) && word.chars().any(is_cyrillic_or_greek)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const BROWSER: &str = "Mozilla/5.0 (X11; Linux x86_64; rv:128.0) Gecko/20100101 Firefox/128.0";
+
+    fn input<'a>(email: &'a str, message: &'a str) -> SpamInput<'a> {
+        SpamInput {
+            name: "Ada",
+            email,
+            subject: "Hello",
+            message,
+            user_agent: Some(BROWSER),
+            duplicate_count: 0,
+        }
+    }
+
+    #[test]
+    fn ordinary_message_scores_zero() {
+        let result = score(&input(
+            "ada@example.com",
+            "Bonjour, j'ai vu votre projet sur https://github.com/example et j'aimerais en discuter.",
+        ));
+        assert_eq!(result, SpamScore::default());
+    }
+
+    #[test]
+    fn link_spam_crosses_the_threshold() {
+        let result = score(&SpamInput {
+            user_agent: Some("python-requests/2.31"),
+            ..input(
+                "promo@mailinator.com",
+                "Best deals http://a.example http://b.example see bit.ly/xyz [url=http://c.example]",
+            )
+        });
+        assert_eq!(result.rules.get("links"), Some(&2));
+        assert_eq!(result.rules.get("shortened_url"), Some(&5));
+        assert_eq!(result.rules.get("link_markup"), Some(&4));
+        assert_eq!(result.rules.get("disposable_email"), Some(&5));
+        assert_eq!(result.rules.get("automated_client"), Some(&5));
+        assert!(result.is_spam(&SpamConfig {
+            threshold: DEFAULT_SPAM_THRESHOLD
+        }));
+    }
+
+    #[test]
+    fn links_alone_are_not_spam() {
+        let result = score(&input(
+            "ada@example.com",
+            "Hi, here is my work: https://github.com/ada/one https://github.com/ada/two \
+             https://github.com/ada/three https://gitlab.com/ada/four and www.ada.dev",
+        ));
+        assert_eq!(result.rules.get("links"), Some(&4));
+        assert!(!result.is_spam(&SpamConfig {
+            threshold: DEFAULT_SPAM_THRESHOLD
+        }));
+
+        let many = "https://example.com/page ".repeat(20);
+        let result = score(&input("ada@example.com", &many));
+        assert_eq!(result.rules.get("links"), Some(&6));
+        assert!(!result.is_spam(&SpamConfig {
+            threshold: DEFAULT_SPAM_THRESHOLD
+        }));
+    }
+
+    #[test]
+    fn detects_script_tricks_and_duplicates() {
+        let result = score(&SpamInput {
+            duplicate_count: 3,
+            ..input(
+                "ada@sub.yopmail.com",
+                "Verify your p\u{0430}ypal account\u{200B} now",
+            )
+        });
+        assert_eq!(result.rules.get("mixed_script"), Some(&4));
+        assert_eq!(result.rules.get("invisible_characters"), Some(&3));
+        assert_eq!(result.rules.get("duplicate_message"), Some(&8));
+        assert_eq!(result.rules.get("disposable_email"), Some(&5));
+    }
+
+    #[test]
+    fn non_latin_messages_are_not_spam() {
+        let config = SpamConfig {
+            threshold: DEFAULT_SPAM_THRESHOLD,
+        };
+        for message in [
+            "Здравствуйте! Нам нужен разработчик для React-приложение и API на Rust, \
+             можем обсудить сроки?",
+            "Καλησπέρα, βρήκα το portfolio σας και θα ήθελα να συζητήσουμε ένα έργο σε Rust.",
+            "Hello, I saw your Leptos talk. Привет из Москвы, we could meet (Αθήνα/Athens) next week.",
+        ] {
+            let result = score(&input("ada@example.com", message));
+            assert!(!result.is_spam(&config), "{message}: {result:?}");
+            assert_eq!(result.rules.get("mixed_script"), None, "{message}");
+        }
+    }
+
+    #[test]
+    fn message_hash_ignores_case_and_punctuation() {
+        let hasher = IpHasher::parse("2:second-secret-value, 1:first-secret-value").unwrap();
+        assert_eq!(
+            message_hash(&hasher, "Hello,   WORLD!\nBuy now."),
+            message_hash(&hasher, "hello world buy now")
+        );
+        assert_ne!(
+            message_hash(&hasher, "hello world"),
+            message_hash(&hasher, "hello there")
+        );
+
+        let hashes = message_hashes(&hasher, "hello world");
+        assert_eq!(hashes[0], message_hash(&hasher, "hello world"));
+        let old = IpHasher::parse("1:first-secret-value").unwrap();
+        assert_eq!(hashes[1], message_hash(&old, "Hello world!"));
+    }
+}
@@ -0,0 +1,299 @@
+//! Offline user-agent classifier
+//!
+//! Turns a raw User-Agent header into coarse, non-identifying families
+//! (device type, browser, OS) plus a bot flag. Rules are compiled into the
+//! binary; there are no network lookups. Shared by analytics, spam scoring
+//! and the admin contact view.
+
+/// Coarse device class
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DeviceType {
+    Desktop,
+    Mobile,
+    Tablet,
+    Bot,
+}
+
+impl DeviceType {
+    /// Value stored in `page_views.device_type`
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            DeviceType::Desktop => "desktop",
+            DeviceType::Mobile => "mobile",
+            DeviceType::Tablet => "tablet",
+            DeviceType::Bot => "bot",
+        }
+    }
+}
+
+/// Classification result for a user agent
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct UserAgentInfo {
+    pub device_type: DeviceType,
+    pub browser_family: &'static str,
+    pub os_family: &'static str,
+    pub is_bot: bool,
+}
+
+impl UserAgentInfo {
+    /// Short human-readable summary, e.g. "Firefox on Linux (desktop)"
+    pub fn summary(&self) -> String {
+        format!(
+            "{} on {} ({})",
+            self.browser_family,
+            self.os_family,
+            self.device_type.as_str()
+        )
+    }
+}
+
+/// Family used when no rule matches
+pub const OTHER: &str = "Other";
+
+/// Substrings (lowercase) that identify crawlers, monitors and HTTP libraries.
+/// A bare "bot" only counts at the end of a product token ("Googlebot/2.1",
+/// "AdsBot-Google"), so device names like "CUBOT" do not match.
+const BOT_PATTERNS: &[&str] = &[
+    "bot/", "bot;", "bot-", "bot)", "crawler", "spider", "crawl", "slurp", "mediapartners-google",
+    "google-inspectiontool", "feedfetcher", "facebookexternalhit", "embedly",
+    "bingpreview", "yandex.com/bots", "baiduspider", "applebot",
+    "ia_archiver", "archive.org_bot", "semrush", "ahrefs", "mj12bot", "dotbot",
+    "petalbot", "bytespider", "gptbot", "chatgpt-user", "claudebot", "ccbot",
+    "perplexitybot", "headlesschrome", "phantomjs", "lighthouse", "pingdom",
+    "uptimerobot", "statuscake", "curl/", "wget/", "python-requests",
+    "python-urllib", "aiohttp", "go-http-client", "java/", "okhttp",
+    "libwww-perl", "apache-httpclient", "axios/", "node-fetch", "undici",
+    "scrapy", "httpie", "postmanruntime", "insomnia",
+];
+
+/// Browser rules, first match wins (order matters: most UAs mention several engines)
+const BROWSER_RULES: &[(&str, &str)] = &[
+    ("edg/", "Edge"),
+    ("edga/", "Edge"),
+    ("edgios/", "Edge"),
+    ("edge/", "Edge"),
+    ("opr/", "Opera"),
+    ("opera", "Opera"),
+    ("samsungbrowser/", "Samsung Internet"),
+    ("yabrowser/", "Yandex Browser"),
+    ("vivaldi/", "Vivaldi"),
+    ("ucbrowser/", "UC Browser"),
+    ("duckduckgo/", "DuckDuckGo"),
+    ("fxios/", "Firefox"),
+    ("firefox/", "Firefox"),
+    ("crios/", "Chrome"),
+    ("chromium/", "Chromium"),
+    ("chrome/", "Chrome"),
+    ("trident/", "Internet Explorer"),
+    ("msie ", "Internet Explorer"),
+    ("safari/", "Safari"),
+];
+
+/// OS rules, first match wins ("like Mac OS X" appears in iOS UAs, Android UAs mention Linux)
+const OS_RULES: &[(&str, &str)] = &[
+    ("windows phone", "Windows Phone"),
+    ("windows", "Windows"),
+    ("iphone", "iOS"),
+    ("ipad", "iOS"),
+    ("ipod", "iOS"),
+    ("android", "Android"),
+    // A token of its own: "cros" also appears inside "Microsoft"
+    (" cros ", "ChromeOS"),
+    ("mac os x", "macOS"),
+    ("macintosh", "macOS"),
+    ("freebsd", "FreeBSD"),
+    ("linux", "Linux"),
+];
+
+/// Substrings that identify tablets
+const TABLET_PATTERNS: &[&str] = &["ipad", "tablet", "kindle", "silk/", "playbook"];
+
+/// Substrings that identify phones
+const MOBILE_PATTERNS: &[&str] = &["mobi", "iphone", "ipod", "windows phone", "opera mini"];
+
+/// Classify a raw User-Agent header value
+pub fn classify(user_agent: &str) -> UserAgentInfo {
+    let ua = user_agent.trim().to_lowercase();
+
+    let first_match = |rules: &[(&str, &'static str)]| {
+        rules
+            .iter()
+            .find(|(pattern, _)| ua.contains(pattern))
+            .map(|(_, family)| *family)
+            .unwrap_or(OTHER)
+    };
+
+    let browser_family = first_match(BROWSER_RULES);
+    let os_family = first_match(OS_RULES);
+
+    // No user agent at all is never a real browser
+    let is_bot = ua.is_empty() || BOT_PATTERNS.iter().any(|p| ua.contains(p));
+
+    let device_type = if is_bot {
+        DeviceType::Bot
+    } else if TABLET_PATTERNS.iter().any(|p| ua.contains(p))
+        || (os_family == "Android" && !ua.contains("mobile"))
+    {
+        DeviceType::Tablet
+    } else if MOBILE_PATTERNS.iter().any(|p| ua.contains(p)) {
+        DeviceType::Mobile
+    } else {
+        DeviceType::Desktop
+    };
+
+    UserAgentInfo {
+        device_type,
+        browser_family,
+        os_family,
+        is_bot,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// (user agent, device, browser, os)
+    const CORPUS: &[(&str, DeviceType, &str, &str)] = &[
+        (
+            "Mozilla/5.0 (Windows NT 10.0; Win64; x64) AppleWebKit/537.36 (KHTML, like Gecko) Chrome/120.0.0.0 Safari/537.36",
+            DeviceType::Desktop, "Chrome", "Windows",
+        ),
+        (
+            "Mozilla/5.0 (Windows NT 10.0; Win64; x64) AppleWebKit/537.36 (KHTML, like Gecko) Chrome/120.0.0.0 Safari/537.36 Edg/120.0.2210.91",
+            DeviceType::Desktop, "Edge", "Windows",
+        ),
+        (
+            "Mozilla/5.0 (Windows NT 10.0; Win64; x64) AppleWebKit/537.36 (KHTML, like Gecko) Chrome/119.0.0.0 Safari/537.36 OPR/105.0.0.0",
+            DeviceType::Desktop, "Opera", "Windows",
+        ),
+        (
+            "Mozilla/5.0 (X11; Linux x86_64; rv:121.0) Gecko/20100101 Firefox/121.0",
+            DeviceType::Desktop, "Firefox", "Linux",
+        ),
+        (
+            "Mozilla/5.0 (X11; Ubuntu; Linux x86_64; rv:109.0) Gecko/20100101 Firefox/115.0",
+            DeviceType::Desktop, "Firefox", "Linux",
+        ),
+        (
+            "Mozilla/5.0 (Macintosh; Intel Mac OS X 10_15_7) AppleWebKit/605.1.15 (KHTML, like Gecko) Version/17.2 Safari/605.1.15",
+            DeviceType::Desktop, "Safari", "macOS",
+        ),
+        (
+            "Mozilla/5.0 (Macintosh; Intel Mac OS X 10.15; rv:121.0) Gecko/20100101 Firefox/121.0",
+            DeviceType::Desktop, "Firefox", "macOS",
+        ),
+        (
+            "Mozilla/5.0 (X11; CrOS x86_64 14541.0.0) AppleWebKit/537.36 (KHTML, like Gecko) Chrome/120.0.0.0 Safari/537.36",
+            DeviceType::Desktop, "Chrome", "ChromeOS",
+        ),
+        (
+            "Mozilla/5.0 (Windows NT 6.1; WOW64; Trident/7.0; rv:11.0) like Gecko",
+            DeviceType::Desktop, "Internet Explorer", "Windows",
+        ),
+        (
+            "Mozilla/5.0 (iPhone; CPU iPhone OS 17_2 like Mac OS X) AppleWebKit/605.1.15 (KHTML, like Gecko) Version/17.2 Mobile/15E148 Safari/604.1",
+            DeviceType::Mobile, "Safari", "iOS",
+        ),
+        (
+            "Mozilla/5.0 (iPhone; CPU iPhone OS 17_2 like Mac OS X) AppleWebKit/605.1.15 (KHTML, like Gecko) CriOS/120.0.6099.119 Mobile/15E148 Safari/604.1",
+            DeviceType::Mobile, "Chrome", "iOS",
+        ),
+        (
+            "Mozilla/5.0 (iPhone; CPU iPhone OS 17_2 like Mac OS X) AppleWebKit/605.1.15 (KHTML, like Gecko) FxiOS/121.0 Mobile/15E148 Safari/605.1.15",
+            DeviceType::Mobile, "Firefox", "iOS",
+        ),
+        (
+            "Mozilla/5.0 (iPad; CPU OS 17_2 like Mac OS X) AppleWebKit/605.1.15 (KHTML, like Gecko) Version/17.2 Mobile/15E148 Safari/604.1",
+            DeviceType::Tablet, "Safari", "iOS",
+        ),
+        (
+            "Mozilla/5.0 (Linux; Android 10; K) AppleWebKit/537.36 (KHTML, like Gecko) Chrome/120.0.0.0 Mobile Safari/537.36",
+            DeviceType::Mobile, "Chrome", "Android",
+        ),
+        (
+            "Mozilla/5.0 (Linux; Android 13; SM-S918B) AppleWebKit/537.36 (KHTML, like Gecko) SamsungBrowser/23.0 Chrome/115.0.0.0 Mobile Safari/537.36",
+            DeviceType::Mobile, "Samsung Internet", "Android",
+        ),
+        (
+            "Mozilla/5.0 (Android 14; Mobile; rv:121.0) Gecko/121.0 Firefox/121.0",
+            DeviceType::Mobile, "Firefox", "Android",
+        ),
+        (
+            "Mozilla/5.0 (Linux; Android 13; SM-X700) AppleWebKit/537.36 (KHTML, like Gecko) Chrome/120.0.0.0 Safari/537.36",
+            DeviceType::Tablet, "Chrome", "Android",
+        ),
+        (
+            "Mozilla/5.0 (Windows Phone 10.0; Android 6.0.1; Microsoft; Lumia 950) AppleWebKit/537.36 (KHTML, like Gecko) Chrome/52.0.2743.116 Mobile Safari/537.36 Edge/15.14977",
+            DeviceType::Mobile, "Edge", "Windows Phone",
+        ),
+        (
+            "Mozilla/5.0 (Macintosh; Intel Mac OS X 10_15_7) AppleWebKit/605.1.15 (KHTML, like Gecko) Version/17.2 DuckDuckGo/7 Safari/605.1.15",
+            DeviceType::Desktop, "DuckDuckGo", "macOS",
+        ),
+        (
+            "Mozilla/5.0 (Linux; Android 14; Pixel 8) AppleWebKit/537.36 (KHTML, like Gecko) Version/4.0 Chrome/120.0.0.0 Mobile DuckDuckGo/5 Safari/537.36",
+            DeviceType::Mobile, "DuckDuckGo", "Android",
+        ),
+        (
+            "Mozilla/5.0 (Linux; Android 10; CUBOT_X30) AppleWebKit/537.36 (KHTML, like Gecko) Chrome/120.0.0.0 Mobile Safari/537.36",
+            DeviceType::Mobile, "Chrome", "Android",
+        ),
+        (
+            "Mozilla/5.0 (Linux; Android 12; Cubot KingKong 7 Build/SP1A.210812.016) AppleWebKit/537.36 (KHTML, like Gecko) Chrome/119.0.0.0 Mobile Safari/537.36",
+            DeviceType::Mobile, "Chrome", "Android",
+        ),
+        (
+            "Microsoft Office/16.0 (Macintosh; Mac OS X 10.15; Microsoft Outlook 16.78.23100802; Pro)",
+            DeviceType::Desktop, OTHER, "macOS",
+        ),
+    ];
+
+    const BOTS: &[&str] = &[
+        "Mozilla/5.0 (compatible; Googlebot/2.1; +http://www.google.com/bot.html)",
+        "Mozilla/5.0 (Linux; Android 6.0.1; Nexus 5X Build/MMB29P) AppleWebKit/537.36 (KHTML, like Gecko) Chrome/120.0.6099.71 Mobile Safari/537.36 (compatible; Googlebot/2.1; +http://www.google.com/bot.html)",
+        "Mozilla/5.0 (compatible; bingbot/2.0; +http://www.bing.com/bingbot.htm)",
+        "Mozilla/5.0 (compatible; YandexBot/3.0; +http://yandex.com/bots)",
+        "DuckDuckBot/1.1; (+http://duckduckgo.com/duckduckbot.html)",
+        "Mozilla/5.0 (compatible; AhrefsBot/7.0; +http://ahrefs.com/robot/)",
+        "AdsBot-Google (+http://www.google.com/adsbot.html)",
+        "Mozilla/5.0 (compatible; SemrushBot/7~bl; +http://www.semrush.com/bot.html)",
+        "Slackbot-LinkExpanding 1.0 (+https://api.slack.com/robots)",
+        "Mozilla/5.0 AppleWebKit/537.36 (KHTML, like Gecko; compatible; GPTBot/1.0; +https://openai.com/gptbot)",
+        "facebookexternalhit/1.1 (+http://www.facebook.com/externalhit_uatext.php)",
+        "Mozilla/5.0 (X11; Linux x86_64) AppleWebKit/537.36 (KHTML, like Gecko) HeadlessChrome/120.0.0.0 Safari/537.36",
+        "curl/8.4.0",
+        "Wget/1.21.4",
+        "python-requests/2.31.0",
+        "Go-http-client/1.1",
+        "",
+    ];
+
+    #[test]
+    fn classifies_browser_corpus() {
+        for (ua, device, browser, os) in CORPUS {
+            let info = classify(ua);
+            assert!(!info.is_bot, "flagged as bot: {ua}");
+            assert_eq!(info.device_type, *device, "device for {ua}");
+            assert_eq!(info.browser_family, *browser, "browser for {ua}");
+            assert_eq!(info.os_family, *os, "os for {ua}");
+        }
+    }
+
+    #[test]
+    fn flags_bots_and_http_clients() {
+        for ua in BOTS {
+            let info = classify(ua);
+            assert!(info.is_bot, "not flagged as bot: {ua}");
+            assert_eq!(info.device_type, DeviceType::Bot);
+        }
+    }
+
+    #[test]
+    fn unknown_agents_fall_back_to_other() {
+        let info = classify("SomethingNew/1.0");
+        assert_eq!(info.browser_family, OTHER);
+        assert_eq!(info.os_family, OTHER);
+        assert_eq!(info.device_type, DeviceType::Desktop);
+    }
+}
@@ -0,0 +1,127 @@
+#!/usr/bin/env python3
+"""Generate geoip-country-test.mmdb, a tiny MaxMind-format country database.
+
+Only documentation address ranges are used, so the fixture contains no real
+GeoIP data. Run from this directory: python3 make_geoip_fixture.py
+"""
+
+import ipaddress
+import struct
+
+NETWORKS = {
+    "192.0.2.0/24": "FR",
+    "198.51.100.0/24": "HU",
+    "203.0.113.0/25": "CA",
+    "2001:db8::/32": "DE",
+}
+
+RECORD_SIZE = 24
+
+
+def encode_ctrl(type_id, size):
+    out = bytearray()
+    if type_id <= 7:
+        first = type_id << 5
+        ext = b""
+    else:
+        first = 0
+        ext = bytes([type_id - 7])
+    if size < 29:
+        out.append(first | size)
+        out += ext
+    elif size < 285:
+        out.append(first | 29)
+        out += ext
+        out.append(size - 29)
+    else:
+        out.append(first | 30)
+        out += ext
+        out += struct.pack(">H", size - 285)
+    return bytes(out)
+
+
+def encode(value):
+    if isinstance(value, str):
+        raw = value.encode()
+        return encode_ctrl(2, len(raw)) + raw
+    if isinstance(value, dict):
+        out = encode_ctrl(7, len(value))
+        for k, v in value.items():
+            out += encode(k) + encode(v)
+        return out
+    if isinstance(value, list):
+        out = encode_ctrl(11, len(value))
+        for v in value:
+            out += encode(v)
+        return out
+    if isinstance(value, tuple):  # (type_id, int) for sized unsigned ints
+        type_id, n = value
+        raw = n.to_bytes((n.bit_length() + 7) // 8, "big") if n else b""
+        return encode_ctrl(type_id, len(raw)) + raw
+    raise TypeError(value)
+
+
+def network_bits(cidr):
+    net = ipaddress.ip_network(cidr)
+    # IPv4 lives under ::/96 in an IPv6 tree
+    prefix = net.prefixlen + (96 if net.version == 4 else 0)
+    value = int(net.network_address)
+    return [(value >> (127 - i)) & 1 for i in range(prefix)]
+
+
+def main():
+    # Data section: one record per country
+    data = bytearray()
+    offsets = {}
+    for code in sorted(set(NETWORKS.values())):
+        offsets[code] = len(data)
+        data += encode({"country": {"iso_code": code}})
+
+    # Binary trie: nodes[i] = [left, right], entries are ("node", n) / ("data", code) / None
+    nodes = [[None, None]]
+    for cidr, code in NETWORKS.items():
+        node = 0
+        bits = network_bits(cidr)
+        for depth, bit in enumerate(bits):
+            if depth == len(bits) - 1:
+                nodes[node][bit] = ("data", code)
+            else:
+                if nodes[node][bit] is None:
+                    nodes.append([None, None])
+                    nodes[node][bit] = ("node", len(nodes) - 1)
+                node = nodes[node][bit][1]
+
+    node_count = len(nodes)
+    tree = bytearray()
+    for left, right in nodes:
+        for record in (left, right):
+            if record is None:
+                value = node_count
+            elif record[0] == "node":
+                value = record[1]
+            else:
+                value = node_count + 16 + offsets[record[1]]
+            tree += value.to_bytes(RECORD_SIZE // 8, "big")
+
+    metadata = encode({
+        "binary_format_major_version": (5, 2),
+        "binary_format_minor_version": (5, 0),
+        "build_epoch": (9, 1764547200),
+        "database_type": "Portfolio-Country-Test",
+        "description": {"en": "Test fixture for the portfolio GeoIP resolver"},
+        "ip_version": (5, 6),
+        "languages": ["en"],
+        "node_count": (6, node_count),
+        "record_size": (5, RECORD_SIZE),
+    })
+
+    with open("geoip-country-test.mmdb", "wb") as f:
+        f.write(tree)
+        f.write(b"\x00" * 16)
+        f.write(data)
+        f.write(b"\xab\xcd\xefMaxMind.com")
+        f.write(metadata)
+
+
+if __name__ == "__main__":
+    main()